@@ -0,0 +1,374 @@
+#![deny(missing_docs)]
+
+use core::alloc::Layout;
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::iter::FromIterator;
+use core::marker::PhantomData;
+use core::mem::{self, ManuallyDrop};
+use core::ops::{Deref, DerefMut};
+use core::ptr::{self, NonNull};
+use core::slice;
+
+use super::allocator::{allocate_or_abort, Allocator, Global};
+use super::storage::Storage;
+
+/// The capacity of the first allocation of a non-empty AllocVec.
+const MIN_CAPACITY: usize = 4;
+
+/// A growable vector that stores its items in memory from an `Allocator`,
+/// like `Vec` with an allocator parameter.
+/// ## Examples
+/// ```
+/// # use stdlib_rs::collections::alloc_vec::AllocVec;
+/// # use stdlib_rs::collections::arena::Bump;
+/// let bump = Bump::new();
+/// let mut v = AllocVec::new_in(&bump);
+/// v.push(1);
+/// v.push(2);
+/// v.extend(3..=4);
+/// assert_eq!(&v[..], &[1, 2, 3, 4]);
+/// assert_eq!(v.pop(), Some(4));
+/// ```
+pub struct AllocVec<T, A: Allocator = Global> {
+    ptr: NonNull<T>,
+    capacity: usize,
+    len: usize,
+    alloc: A,
+    _marker: PhantomData<T>,
+}
+
+unsafe impl<T: Send, A: Allocator + Send> Send for AllocVec<T, A> {}
+unsafe impl<T: Sync, A: Allocator + Sync> Sync for AllocVec<T, A> {}
+
+impl<T> AllocVec<T> {
+    /// Creates a new, empty AllocVec that uses the global allocator.
+    pub fn new() -> Self {
+        Self::new_in(Global)
+    }
+}
+
+impl<T, A: Allocator> AllocVec<T, A> {
+    /// Creates a new, empty AllocVec that allocates from `alloc`.
+    /// Nothing is allocated until the first item is pushed.
+    pub fn new_in(alloc: A) -> Self {
+        AllocVec {
+            ptr: NonNull::dangling(),
+            capacity: if mem::size_of::<T>() == 0 {
+                usize::MAX
+            } else {
+                0
+            },
+            len: 0,
+            alloc,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Creates a new AllocVec with room for `capacity` items, allocated
+    /// from `alloc`.
+    pub fn with_capacity_in(capacity: usize, alloc: A) -> Self {
+        let mut vec = Self::new_in(alloc);
+        vec.reserve(capacity);
+        vec
+    }
+
+    /// Returns the allocator the items are stored in.
+    pub fn allocator(&self) -> &A {
+        &self.alloc
+    }
+
+    /// Returns the number of items the vector can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Adds an item to the end.
+    pub fn push(&mut self, item: T) {
+        if self.len == self.capacity {
+            self.reserve(1);
+        }
+        unsafe { ptr::write(self.ptr.as_ptr().add(self.len), item) };
+        self.len += 1;
+    }
+
+    /// Removes the last item and returns it, or `None` if it is empty.
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        // The item is outside `len` from now on, so it is read only once.
+        Some(unsafe { ptr::read(self.ptr.as_ptr().add(self.len)) })
+    }
+
+    /// Shortens the vector to `len` items, dropping the rest.
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.len {
+            return;
+        }
+        let tail: *mut [T] = &mut self[len..];
+        // Shorten first, so a panicking destructor cannot cause a double drop.
+        self.len = len;
+        unsafe { ptr::drop_in_place(tail) };
+    }
+
+    /// Removes all the items, keeping the memory.
+    pub fn clear(&mut self) {
+        self.truncate(0)
+    }
+
+    /// Reserves room for at least `additional` more items.
+    /// ## Panics
+    /// Panics if the new capacity overflows a `usize`.
+    pub fn reserve(&mut self, additional: usize) {
+        let needed = self.len.checked_add(additional).expect("capacity overflow");
+        if needed <= self.capacity {
+            return;
+        }
+        let capacity = needed.max(2 * self.capacity).max(MIN_CAPACITY);
+        let layout = Layout::array::<T>(capacity).expect("capacity overflow");
+        let ptr = allocate_or_abort(&self.alloc, layout).cast::<T>();
+        unsafe {
+            ptr::copy_nonoverlapping(self.ptr.as_ptr(), ptr.as_ptr(), self.len);
+            self.free();
+        }
+        self.ptr = ptr;
+        self.capacity = capacity;
+    }
+
+    /// Frees the memory without dropping the items.
+    unsafe fn free(&mut self) {
+        if mem::size_of::<T>() != 0 && self.capacity != 0 {
+            let layout = Layout::array::<T>(self.capacity).unwrap();
+            self.alloc.deallocate(self.ptr.cast(), layout);
+        }
+    }
+}
+
+impl<T, A: Allocator> Drop for AllocVec<T, A> {
+    fn drop(&mut self) {
+        self.clear();
+        unsafe { self.free() };
+    }
+}
+
+impl<T, A: Allocator> Deref for AllocVec<T, A> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        // The first `len` items are initialized.
+        unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<T, A: Allocator> DerefMut for AllocVec<T, A> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<T, A: Allocator + Default> Default for AllocVec<T, A> {
+    fn default() -> Self {
+        Self::new_in(A::default())
+    }
+}
+
+impl<T: Clone, A: Allocator + Clone> Clone for AllocVec<T, A> {
+    fn clone(&self) -> Self {
+        let mut vec = Self::with_capacity_in(self.len, self.alloc.clone());
+        vec.extend(self.iter().cloned());
+        vec
+    }
+}
+
+impl<T: fmt::Debug, A: Allocator> fmt::Debug for AllocVec<T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq, A: Allocator> PartialEq for AllocVec<T, A> {
+    fn eq(&self, other: &Self) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl<T: Eq, A: Allocator> Eq for AllocVec<T, A> {}
+
+impl<T: PartialOrd, A: Allocator> PartialOrd for AllocVec<T, A> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self[..].partial_cmp(&other[..])
+    }
+}
+
+impl<T: Hash, A: Allocator> Hash for AllocVec<T, A> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self[..].hash(state)
+    }
+}
+
+impl<T, A: Allocator> Extend<T> for AllocVec<T, A> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for item in iter {
+            self.push(item);
+        }
+    }
+}
+
+impl<T> FromIterator<T> for AllocVec<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut vec = AllocVec::new();
+        vec.extend(iter);
+        vec
+    }
+}
+
+impl<T, A: Allocator> IntoIterator for AllocVec<T, A> {
+    type Item = T;
+    type IntoIter = IntoIter<T, A>;
+
+    fn into_iter(self) -> IntoIter<T, A> {
+        let this = ManuallyDrop::new(self);
+        IntoIter {
+            // The vector is forgotten, so its fields are moved exactly once.
+            vec: AllocVec {
+                ptr: this.ptr,
+                capacity: this.capacity,
+                len: 0,
+                alloc: unsafe { ptr::read(&this.alloc) },
+                _marker: PhantomData,
+            },
+            start: 0,
+            end: this.len,
+        }
+    }
+}
+
+/// An iterator that moves the items out of an `AllocVec`.
+pub struct IntoIter<T, A: Allocator = Global> {
+    // Owns the memory; its `len` is zero so dropping it frees no items.
+    vec: AllocVec<T, A>,
+    // The items in `start..end` have not been yielded yet.
+    start: usize,
+    end: usize,
+}
+
+impl<T, A: Allocator> Iterator for IntoIter<T, A> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.start == self.end {
+            return None;
+        }
+        self.start += 1;
+        Some(unsafe { ptr::read(self.vec.ptr.as_ptr().add(self.start - 1)) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.start;
+        (len, Some(len))
+    }
+}
+
+impl<T, A: Allocator> DoubleEndedIterator for IntoIter<T, A> {
+    fn next_back(&mut self) -> Option<T> {
+        if self.start == self.end {
+            return None;
+        }
+        self.end -= 1;
+        Some(unsafe { ptr::read(self.vec.ptr.as_ptr().add(self.end)) })
+    }
+}
+
+impl<T, A: Allocator> ExactSizeIterator for IntoIter<T, A> {}
+
+impl<T, A: Allocator> Drop for IntoIter<T, A> {
+    fn drop(&mut self) {
+        for _ in self {}
+    }
+}
+
+impl<T, A: Allocator> Storage<T> for AllocVec<T, A> {
+    fn push(&mut self, item: T) {
+        AllocVec::push(self, item)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        AllocVec::pop(self)
+    }
+
+    fn clear(&mut self) {
+        AllocVec::clear(self)
+    }
+
+    fn capacity(&self) -> usize {
+        AllocVec::capacity(self)
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        AllocVec::reserve(self, additional)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::arena::Bump;
+    use super::*;
+    use alloc::rc::Rc;
+
+    #[test]
+    fn grows_and_shrinks() {
+        let mut v = AllocVec::new();
+        assert_eq!(v.capacity(), 0);
+        for i in 0..100 {
+            v.push(i);
+        }
+        assert!(v.capacity() >= 100);
+        v.truncate(10);
+        assert_eq!(v.iter().sum::<i32>(), 45);
+        assert_eq!(v.clone(), v);
+        assert_eq!(v.into_iter().next_back(), Some(9));
+    }
+
+    #[test]
+    fn uses_the_given_allocator() {
+        let bump = Bump::new();
+        let mut v = AllocVec::with_capacity_in(8, &bump);
+        let used = bump.allocated_bytes();
+        assert!(used >= 8 * mem::size_of::<u64>());
+        v.extend(0..8u64);
+        assert_eq!(bump.allocated_bytes(), used);
+        v.push(8);
+        assert!(bump.allocated_bytes() > used);
+    }
+
+    #[test]
+    fn drops_every_item_once() {
+        let counter = Rc::new(());
+        let mut v = AllocVec::new();
+        for _ in 0..10 {
+            v.push(counter.clone());
+        }
+        v.pop();
+        let mut iter = v.into_iter();
+        iter.next();
+        iter.next_back();
+        assert_eq!(Rc::strong_count(&counter), 8);
+        drop(iter);
+        assert_eq!(Rc::strong_count(&counter), 1);
+    }
+
+    #[test]
+    fn zero_sized_items() {
+        let mut v = AllocVec::new();
+        for _ in 0..1000 {
+            v.push(());
+        }
+        assert_eq!(v.len(), 1000);
+        assert_eq!(v.into_iter().count(), 1000);
+    }
+}
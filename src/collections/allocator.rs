@@ -0,0 +1,237 @@
+#![deny(missing_docs)]
+
+use alloc::alloc::{alloc, dealloc, handle_alloc_error};
+use core::alloc::Layout;
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem;
+use core::ops::{Deref, DerefMut};
+use core::ptr::{self, NonNull};
+
+use super::arena::Bump;
+
+/// Returned when an allocator cannot provide the memory it was asked for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AllocError;
+
+impl fmt::Display for AllocError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "memory allocation failed")
+    }
+}
+
+impl core::error::Error for AllocError {}
+
+/// A source of memory for the collections in this crate, standing in for
+/// the unstable `core::alloc::Allocator` trait.
+/// Collections take an allocator by value, so pass a reference such as
+/// `&Bump` to share one allocator between many collections.
+/// ## Safety
+/// Memory returned by `allocate` must be valid for `layout` and must not be
+/// handed out again until it is passed to `deallocate`, or until the
+/// allocator and every copy of it has been dropped. Zero-sized layouts must
+/// be supported.
+pub unsafe trait Allocator {
+    /// Allocates a block of memory that fits `layout`.
+    fn allocate(&self, layout: Layout) -> Result<NonNull<u8>, AllocError>;
+
+    /// Frees a block of memory.
+    /// ## Safety
+    /// `ptr` must have been returned by `allocate` on this allocator, or a
+    /// copy of it, with the same `layout`, and must not be used again.
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout);
+}
+
+/// The global allocator, which collections use unless they are given
+/// another one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Global;
+
+unsafe impl Allocator for Global {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
+        if layout.size() == 0 {
+            return Ok(dangling(layout));
+        }
+        NonNull::new(unsafe { alloc(layout) }).ok_or(AllocError)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        if layout.size() != 0 {
+            dealloc(ptr.as_ptr(), layout);
+        }
+    }
+}
+
+unsafe impl<A: Allocator + ?Sized> Allocator for &A {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
+        (**self).allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        (**self).deallocate(ptr, layout)
+    }
+}
+
+/// A `Bump` arena frees its memory all at once when it is dropped, so
+/// `deallocate` does nothing.
+/// ## Examples
+/// ```
+/// # use stdlib_rs::collections::arena::Bump;
+/// # use stdlib_rs::collections::min_stack::MinStack;
+/// let bump = Bump::new();
+/// let mut stack = MinStack::new_in(&bump);
+/// stack.push(3);
+/// stack.push(1);
+/// assert_eq!(stack.min(), Some(1));
+/// assert!(bump.allocated_bytes() > 0);
+/// ```
+unsafe impl Allocator for Bump {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
+        Ok(self.alloc_layout(layout))
+    }
+
+    unsafe fn deallocate(&self, _ptr: NonNull<u8>, _layout: Layout) {}
+}
+
+/// Returns an aligned, non-null pointer for a zero-sized allocation.
+pub(crate) fn dangling(layout: Layout) -> NonNull<u8> {
+    NonNull::new(layout.align() as *mut u8).unwrap()
+}
+
+/// Allocates `layout` from `alloc`, aborting through `handle_alloc_error`
+/// if it fails, like the standard collections do.
+pub(crate) fn allocate_or_abort<A: Allocator>(alloc: &A, layout: Layout) -> NonNull<u8> {
+    alloc
+        .allocate(layout)
+        .unwrap_or_else(|_| handle_alloc_error(layout))
+}
+
+/// A pointer to a single value stored in memory from an `Allocator`, like
+/// `Box` with an allocator parameter.
+/// ## Examples
+/// ```
+/// # use stdlib_rs::collections::allocator::{AllocBox, Global};
+/// let mut b = AllocBox::new_in(5, Global);
+/// *b += 1;
+/// assert_eq!(AllocBox::into_inner(b), 6);
+/// ```
+pub struct AllocBox<T, A: Allocator = Global> {
+    ptr: NonNull<T>,
+    alloc: A,
+    _marker: PhantomData<T>,
+}
+
+unsafe impl<T: Send, A: Allocator + Send> Send for AllocBox<T, A> {}
+unsafe impl<T: Sync, A: Allocator + Sync> Sync for AllocBox<T, A> {}
+
+impl<T, A: Allocator> AllocBox<T, A> {
+    /// Moves `value` into memory from `alloc`.
+    pub fn new_in(value: T, alloc: A) -> Self {
+        let ptr = allocate_or_abort(&alloc, Layout::new::<T>()).cast::<T>();
+        unsafe { ptr::write(ptr.as_ptr(), value) };
+        AllocBox {
+            ptr,
+            alloc,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Moves the value out of the box and frees its memory.
+    pub fn into_inner(boxed: Self) -> T {
+        let boxed = mem::ManuallyDrop::new(boxed);
+        unsafe {
+            let value = ptr::read(boxed.ptr.as_ptr());
+            let alloc = ptr::read(&boxed.alloc);
+            alloc.deallocate(boxed.ptr.cast(), Layout::new::<T>());
+            value
+        }
+    }
+
+    /// Returns the allocator the value is stored in.
+    pub fn allocator(boxed: &Self) -> &A {
+        &boxed.alloc
+    }
+}
+
+impl<T, A: Allocator> Deref for AllocBox<T, A> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { self.ptr.as_ref() }
+    }
+}
+
+impl<T, A: Allocator> DerefMut for AllocBox<T, A> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { self.ptr.as_mut() }
+    }
+}
+
+impl<T, A: Allocator> Drop for AllocBox<T, A> {
+    fn drop(&mut self) {
+        unsafe {
+            ptr::drop_in_place(self.ptr.as_ptr());
+            self.alloc.deallocate(self.ptr.cast(), Layout::new::<T>());
+        }
+    }
+}
+
+impl<T: fmt::Debug, A: Allocator> fmt::Debug for AllocBox<T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::cell::Cell;
+
+    /// Counts the blocks that are allocated and not yet freed.
+    #[derive(Default)]
+    struct Counting {
+        live: Cell<usize>,
+    }
+
+    unsafe impl Allocator for Counting {
+        fn allocate(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
+            self.live.set(self.live.get() + 1);
+            Global.allocate(layout)
+        }
+
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+            self.live.set(self.live.get() - 1);
+            Global.deallocate(ptr, layout)
+        }
+    }
+
+    #[test]
+    fn box_frees_its_memory() {
+        let counting = Counting::default();
+        let a = AllocBox::new_in(String::from("a"), &counting);
+        let b = AllocBox::new_in(7u64, &counting);
+        assert_eq!(counting.live.get(), 2);
+        assert_eq!(AllocBox::into_inner(a), "a");
+        drop(b);
+        assert_eq!(counting.live.get(), 0);
+    }
+
+    #[test]
+    fn zero_sized_values() {
+        let b = AllocBox::new_in((), Global);
+        assert_eq!(*b, ());
+        let bump = Bump::new();
+        let c = AllocBox::new_in([0u8; 0], &bump);
+        assert_eq!(c.len(), 0);
+        assert_eq!(bump.allocated_bytes(), 0);
+    }
+
+    #[test]
+    fn bump_backed_box() {
+        let bump = Bump::new();
+        let mut b = AllocBox::new_in(vec![1, 2], &bump);
+        b.push(3);
+        assert_eq!(*b, [1, 2, 3]);
+        assert!(ptr::eq(*AllocBox::allocator(&b), &bump));
+    }
+}
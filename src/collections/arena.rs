@@ -0,0 +1,308 @@
+#![deny(missing_docs)]
+
+use alloc::{boxed::Box, vec, vec::Vec};
+use core::alloc::Layout;
+use core::cell::{Cell, RefCell};
+use core::mem::{self, MaybeUninit};
+use core::ptr::{self, NonNull};
+use core::slice;
+
+/// The number of items or bytes in the first chunk of an arena.
+const FIRST_CHUNK: usize = 64;
+
+struct Chunks<T> {
+    current: Vec<T>,
+    rest: Vec<Vec<T>>,
+}
+
+/// An arena that hands out references to values of a single type and drops
+/// them all at once when the arena is dropped.
+/// Values are stored in chunks that are never moved, so each allocation is
+/// O(1) and there is no per-value heap allocation.
+pub struct TypedArena<T> {
+    chunks: RefCell<Chunks<T>>,
+}
+
+impl<T> Default for TypedArena<T> {
+    fn default() -> Self {
+        Self::with_capacity(FIRST_CHUNK)
+    }
+}
+
+impl<T> TypedArena<T> {
+    /// Creates a new TypedArena.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new TypedArena whose first chunk holds `capacity` values.
+    pub fn with_capacity(capacity: usize) -> Self {
+        TypedArena {
+            chunks: RefCell::new(Chunks {
+                current: Vec::with_capacity(capacity.max(1)),
+                rest: vec![],
+            }),
+        }
+    }
+
+    /// Moves `value` into the arena and returns a reference to it that
+    /// lives as long as the arena.
+    /// ## Examples
+    /// ```
+    /// # use stdlib_rs::collections::arena::TypedArena;
+    /// let arena = TypedArena::new();
+    /// let a = arena.alloc(1);
+    /// let b = arena.alloc(2);
+    /// *a += *b;
+    /// assert_eq!(*a, 3);
+    /// assert_eq!(arena.len(), 2);
+    /// ```
+    #[allow(clippy::mut_from_ref)]
+    pub fn alloc(&self, value: T) -> &mut T {
+        let mut chunks = self.chunks.borrow_mut();
+        if chunks.current.len() == chunks.current.capacity() {
+            chunks.grow(1);
+        }
+        let len = chunks.current.len();
+        chunks.current.push(value);
+        // The push fit in the chunk's capacity, so the chunk did not move and
+        // nothing else will ever refer to this slot.
+        unsafe { &mut *chunks.current.as_mut_ptr().add(len) }
+    }
+
+    /// Moves every item of `iter` into the arena and returns them as one
+    /// contiguous slice that lives as long as the arena.
+    #[allow(clippy::mut_from_ref)]
+    pub fn alloc_extend<I: IntoIterator<Item = T>>(&self, iter: I) -> &mut [T] {
+        let items: Vec<T> = iter.into_iter().collect();
+        let mut chunks = self.chunks.borrow_mut();
+        if chunks.current.capacity() - chunks.current.len() < items.len() {
+            chunks.grow(items.len());
+        }
+        let start = chunks.current.len();
+        chunks.current.extend(items);
+        let len = chunks.current.len() - start;
+        unsafe { slice::from_raw_parts_mut(chunks.current.as_mut_ptr().add(start), len) }
+    }
+
+    /// Returns the number of values in the arena.
+    pub fn len(&self) -> usize {
+        let chunks = self.chunks.borrow();
+        chunks.current.len() + chunks.rest.iter().map(Vec::len).sum::<usize>()
+    }
+
+    /// Returns `true` if the arena holds no values.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Consumes the arena, returning its values in allocation order.
+    pub fn into_vec(self) -> Vec<T> {
+        let Chunks { current, rest } = self.chunks.into_inner();
+        let mut values: Vec<T> = rest.into_iter().flatten().collect();
+        values.extend(current);
+        values
+    }
+}
+
+impl<T> Chunks<T> {
+    /// Retires the current chunk and starts one with room for `additional`.
+    fn grow(&mut self, additional: usize) {
+        let capacity = (2 * self.current.capacity()).max(additional);
+        let full = mem::replace(&mut self.current, Vec::with_capacity(capacity));
+        if !full.is_empty() {
+            self.rest.push(full);
+        }
+    }
+}
+
+/// A bump allocator that hands out references to values of any type from
+/// large chunks of memory, and frees every chunk at once when dropped.
+///
+/// Allocation is a pointer bump, but the destructors of allocated values
+/// are never run, so it is best suited to plain data.
+pub struct Bump {
+    chunks: RefCell<Vec<NonNull<[MaybeUninit<u8>]>>>,
+    // The offset of the next free byte in the last chunk.
+    next: Cell<usize>,
+}
+
+impl Default for Bump {
+    fn default() -> Self {
+        Bump {
+            chunks: RefCell::new(vec![]),
+            next: Cell::new(0),
+        }
+    }
+}
+
+impl Bump {
+    /// Creates a new Bump. No memory is allocated until the first value is.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Moves `value` into the arena and returns a reference to it that
+    /// lives as long as the arena. `value` is never dropped.
+    /// ## Examples
+    /// ```
+    /// # use stdlib_rs::collections::arena::Bump;
+    /// let bump = Bump::new();
+    /// let n = bump.alloc(10u64);
+    /// let s = bump.alloc_str("hello");
+    /// *n += 1;
+    /// assert_eq!(*n, 11);
+    /// assert_eq!(s, "hello");
+    /// ```
+    #[allow(clippy::mut_from_ref)]
+    pub fn alloc<T>(&self, value: T) -> &mut T {
+        let ptr = self.alloc_layout(Layout::new::<T>()).cast::<T>();
+        unsafe {
+            ptr::write(ptr.as_ptr(), value);
+            &mut *ptr.as_ptr()
+        }
+    }
+
+    /// Copies `items` into the arena and returns the copy.
+    #[allow(clippy::mut_from_ref)]
+    pub fn alloc_slice_copy<T: Copy>(&self, items: &[T]) -> &mut [T] {
+        let layout = Layout::array::<T>(items.len()).expect("slice is too large");
+        let ptr = self.alloc_layout(layout).cast::<T>();
+        unsafe {
+            ptr::copy_nonoverlapping(items.as_ptr(), ptr.as_ptr(), items.len());
+            slice::from_raw_parts_mut(ptr.as_ptr(), items.len())
+        }
+    }
+
+    /// Copies `text` into the arena and returns the copy.
+    #[allow(clippy::mut_from_ref)]
+    pub fn alloc_str(&self, text: &str) -> &mut str {
+        let bytes = self.alloc_slice_copy(text.as_bytes());
+        unsafe { core::str::from_utf8_unchecked_mut(bytes) }
+    }
+
+    /// Reserves memory for `layout` and returns a pointer to it.
+    pub fn alloc_layout(&self, layout: Layout) -> NonNull<u8> {
+        if layout.size() == 0 {
+            // Any aligned, non-null address is valid for a zero-sized value.
+            return NonNull::new(layout.align() as *mut u8).unwrap();
+        }
+        if let Some(ptr) = self.bump(layout) {
+            return ptr;
+        }
+        self.grow(layout);
+        self.bump(layout).expect("a new chunk fits the allocation")
+    }
+
+    /// Returns the number of bytes held by the arena's chunks.
+    pub fn allocated_bytes(&self) -> usize {
+        self.chunks.borrow().iter().map(|chunk| chunk.len()).sum()
+    }
+
+    /// Carves `layout` out of the last chunk, if it fits.
+    fn bump(&self, layout: Layout) -> Option<NonNull<u8>> {
+        let chunks = self.chunks.borrow();
+        let chunk = chunks.last()?;
+        let base = chunk.as_ptr() as *mut u8;
+        let next = self.next.get();
+        let address = base as usize + next;
+        let start = next + (align_up(address, layout.align()) - address);
+        let end = start.checked_add(layout.size())?;
+        if end > chunk.len() {
+            return None;
+        }
+        self.next.set(end);
+        // `start` is within the chunk, which is never freed before `self`.
+        NonNull::new(unsafe { base.add(start) })
+    }
+
+    /// Starts a new chunk big enough for `layout`.
+    fn grow(&self, layout: Layout) {
+        let mut chunks = self.chunks.borrow_mut();
+        let last = chunks.last().map_or(FIRST_CHUNK / 2, |chunk| chunk.len());
+        let size = (2 * last).max(layout.size() + layout.align());
+        let chunk = vec![MaybeUninit::<u8>::uninit(); size].into_boxed_slice();
+        chunks.push(NonNull::from(Box::leak(chunk)));
+        self.next.set(0);
+    }
+}
+
+impl Drop for Bump {
+    fn drop(&mut self) {
+        for chunk in self.chunks.get_mut().drain(..) {
+            // Every chunk was leaked from a `Box` in `grow`.
+            unsafe { drop(Box::from_raw(chunk.as_ptr())) };
+        }
+    }
+}
+
+fn align_up(address: usize, align: usize) -> usize {
+    (address + align - 1) & !(align - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::rc::Rc;
+
+    #[test]
+    fn typed_arena_keeps_references() {
+        let arena = TypedArena::with_capacity(2);
+        let refs: Vec<&mut usize> = (0..100).map(|i| arena.alloc(i)).collect();
+        for (i, value) in refs.into_iter().enumerate() {
+            assert_eq!(*value, i);
+        }
+        assert_eq!(arena.len(), 100);
+        assert_eq!(arena.into_vec(), (0..100).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn typed_arena_drops_values() {
+        let counter = Rc::new(());
+        {
+            let arena = TypedArena::new();
+            for _ in 0..200 {
+                arena.alloc(counter.clone());
+            }
+            assert_eq!(Rc::strong_count(&counter), 201);
+        }
+        assert_eq!(Rc::strong_count(&counter), 1);
+    }
+
+    #[test]
+    fn typed_arena_alloc_extend() {
+        let arena = TypedArena::new();
+        arena.alloc(0);
+        let items = arena.alloc_extend(1..=100);
+        assert_eq!(items.len(), 100);
+        assert_eq!(items[99], 100);
+        assert_eq!(arena.len(), 101);
+    }
+
+    #[test]
+    fn bump_alignment() {
+        let bump = Bump::new();
+        for i in 0..500u32 {
+            let byte = bump.alloc(i as u8);
+            let word = bump.alloc(i as u64);
+            let wide = bump.alloc(i as u128);
+            assert_eq!(*byte, i as u8);
+            assert_eq!(*word, i as u64);
+            assert_eq!(*wide, i as u128);
+            assert_eq!(word as *mut u64 as usize % mem::align_of::<u64>(), 0);
+            assert_eq!(wide as *mut u128 as usize % mem::align_of::<u128>(), 0);
+        }
+        assert!(bump.allocated_bytes() >= 500 * 25);
+    }
+
+    #[test]
+    fn bump_slices() {
+        let bump = Bump::new();
+        let empty: &mut [u32] = bump.alloc_slice_copy(&[]);
+        assert!(empty.is_empty());
+        let big = bump.alloc_slice_copy(&[7u16; 1000]);
+        assert!(big.iter().all(|&x| x == 7));
+        assert_eq!(bump.alloc_str("héllo"), "héllo");
+        bump.alloc(());
+    }
+}
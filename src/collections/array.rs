@@ -0,0 +1,461 @@
+#![deny(missing_docs)]
+
+use core::fmt;
+use core::mem::MaybeUninit;
+use core::ops::{Deref, DerefMut};
+use core::ptr;
+use core::slice;
+
+/// A stack that holds up to `N` items in a `[T; N]` array and never
+/// allocates.
+/// ## Examples
+/// ```
+/// # use stdlib_rs::collections::array::ArrayStack;
+/// let mut stack: ArrayStack<i32, 2> = ArrayStack::new();
+/// stack.push(1);
+/// assert_eq!(stack.try_push(2), Ok(()));
+/// assert_eq!(stack.try_push(3), Err(3));
+/// assert_eq!(stack.pop(), Some(2));
+/// assert_eq!(&*stack, &[1]);
+/// ```
+pub struct ArrayStack<T, const N: usize> {
+    items: [MaybeUninit<T>; N],
+    len: usize,
+}
+
+impl<T, const N: usize> ArrayStack<T, N> {
+    /// Creates a new, empty ArrayStack.
+    pub const fn new() -> Self {
+        ArrayStack {
+            items: [const { MaybeUninit::uninit() }; N],
+            len: 0,
+        }
+    }
+
+    /// Adds an item to the top of the stack in O(1) time, or gives it back
+    /// if the stack is full.
+    pub fn try_push(&mut self, item: T) -> Result<(), T> {
+        if self.is_full() {
+            return Err(item);
+        }
+        self.items[self.len].write(item);
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Adds an item to the top of the stack in O(1) time.
+    /// ## Panics
+    /// Panics if the stack is full.
+    pub fn push(&mut self, item: T) {
+        if self.try_push(item).is_err() {
+            panic!("ArrayStack is full with {} items", N);
+        }
+    }
+
+    /// Removes the top item and returns it, or `None` if it is empty.
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        // The slot was initialized, and is outside `len` from now on.
+        Some(unsafe { self.items[self.len].as_ptr().read() })
+    }
+
+    /// Returns the top item, or `None` if it is empty.
+    pub fn peek(&self) -> Option<&T> {
+        self.last()
+    }
+
+    /// Removes all the items.
+    pub fn clear(&mut self) {
+        let items: *mut [T] = &mut **self;
+        // Forget the items first, so a panicking destructor cannot lead to
+        // a double drop.
+        self.len = 0;
+        unsafe { ptr::drop_in_place(items) };
+    }
+
+    /// Returns the number of items in the stack.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the stack has no items.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns `true` if the stack holds `N` items.
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    /// Returns the number of items the stack can hold, which is `N`.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+}
+
+impl<T, const N: usize> Deref for ArrayStack<T, N> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        // The first `len` slots are initialized.
+        unsafe { slice::from_raw_parts(self.items.as_ptr() as *const T, self.len) }
+    }
+}
+
+impl<T, const N: usize> DerefMut for ArrayStack<T, N> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.items.as_mut_ptr() as *mut T, self.len) }
+    }
+}
+
+impl<T, const N: usize> Drop for ArrayStack<T, N> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T, const N: usize> Default for ArrayStack<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone, const N: usize> Clone for ArrayStack<T, N> {
+    fn clone(&self) -> Self {
+        let mut stack = Self::new();
+        for item in self.iter() {
+            stack.push(item.clone());
+        }
+        stack
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for ArrayStack<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq, const N: usize> PartialEq for ArrayStack<T, N> {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl<T: Eq, const N: usize> Eq for ArrayStack<T, N> {}
+
+/// A stack that holds up to `N` items in an array and finds its minimum
+/// item in O(1) time, like `MinStack`, without allocating.
+/// ## Examples
+/// ```
+/// # use stdlib_rs::collections::array::ArrayMinStack;
+/// let mut stack: ArrayMinStack<i32, 4> = ArrayMinStack::new();
+/// stack.push(3);
+/// stack.push(1);
+/// stack.push(2);
+/// assert_eq!(stack.min(), Some(1));
+/// stack.pop();
+/// stack.pop();
+/// assert_eq!(stack.min(), Some(3));
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct ArrayMinStack<T: Ord + Clone, const N: usize>(ArrayStack<(T, T), N>);
+
+impl<T: Ord + Clone, const N: usize> ArrayMinStack<T, N> {
+    /// Creates a new, empty ArrayMinStack.
+    pub const fn new() -> Self {
+        ArrayMinStack(ArrayStack::new())
+    }
+
+    /// Adds an item to the top of the stack in O(1) time, or gives it back
+    /// if the stack is full.
+    pub fn try_push(&mut self, item: T) -> Result<(), T> {
+        let min = match self.0.peek() {
+            Some((_, min)) if *min < item => min.clone(),
+            _ => item.clone(),
+        };
+        self.0.try_push((item, min)).map_err(|(item, _)| item)
+    }
+
+    /// Adds an item to the top of the stack in O(1) time.
+    /// ## Panics
+    /// Panics if the stack is full.
+    pub fn push(&mut self, item: T) {
+        if self.try_push(item).is_err() {
+            panic!("ArrayMinStack is full with {} items", N);
+        }
+    }
+
+    /// Removes the top item and returns it, or `None` if it is empty.
+    pub fn pop(&mut self) -> Option<T> {
+        self.0.pop().map(|(item, _)| item)
+    }
+
+    /// Returns the top item, or `None` if it is empty.
+    pub fn peek(&self) -> Option<T> {
+        self.0.peek().map(|item| item.0.clone())
+    }
+
+    /// Returns the minimum item in O(1) time, or `None` if it is empty.
+    pub fn min(&self) -> Option<T> {
+        self.0.peek().map(|item| item.1.clone())
+    }
+
+    /// Returns the number of items in the stack.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if the stack has no items.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns `true` if the stack holds `N` items.
+    pub fn is_full(&self) -> bool {
+        self.0.is_full()
+    }
+
+    /// Returns the number of items the stack can hold, which is `N`.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+}
+
+impl<T: Ord + Clone, const N: usize> Default for ArrayMinStack<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord + Clone + fmt::Debug, const N: usize> fmt::Debug for ArrayMinStack<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.0.iter().map(|(item, _)| item))
+            .finish()
+    }
+}
+
+/// A first-in, first-out queue that holds up to `N` items in a ring buffer
+/// backed by a `[T; N]` array, and never allocates.
+/// ## Examples
+/// ```
+/// # use stdlib_rs::collections::array::ArrayQueue;
+/// let mut queue: ArrayQueue<i32, 2> = ArrayQueue::new();
+/// queue.push(1);
+/// queue.push(2);
+/// assert_eq!(queue.try_push(3), Err(3));
+/// assert_eq!(queue.pop(), Some(1));
+/// queue.push(3);
+/// assert_eq!(queue.iter().collect::<Vec<_>>(), [&2, &3]);
+/// ```
+pub struct ArrayQueue<T, const N: usize> {
+    items: [MaybeUninit<T>; N],
+    // The slot of the first item.
+    head: usize,
+    len: usize,
+}
+
+impl<T, const N: usize> ArrayQueue<T, N> {
+    /// Creates a new, empty ArrayQueue.
+    pub const fn new() -> Self {
+        ArrayQueue {
+            items: [const { MaybeUninit::uninit() }; N],
+            head: 0,
+            len: 0,
+        }
+    }
+
+    /// Adds an item to the end of the queue in O(1) time, or gives it back
+    /// if the queue is full.
+    pub fn try_push(&mut self, item: T) -> Result<(), T> {
+        if self.is_full() {
+            return Err(item);
+        }
+        let slot = self.slot(self.len);
+        self.items[slot].write(item);
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Adds an item to the end of the queue in O(1) time.
+    /// ## Panics
+    /// Panics if the queue is full.
+    pub fn push(&mut self, item: T) {
+        if self.try_push(item).is_err() {
+            panic!("ArrayQueue is full with {} items", N);
+        }
+    }
+
+    /// Removes the first item in O(1) time and returns it, or `None` if the
+    /// queue is empty.
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        // The slot was initialized, and is outside the queue from now on.
+        let item = unsafe { self.items[self.head].as_ptr().read() };
+        self.head = self.slot(1);
+        self.len -= 1;
+        Some(item)
+    }
+
+    /// Returns the first item, or `None` if the queue is empty.
+    pub fn peek(&self) -> Option<&T> {
+        self.iter().next()
+    }
+
+    /// Returns an iterator over the items, from first to last.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator {
+        // The `len` slots starting at `head` are initialized.
+        (0..self.len).map(move |i| unsafe { &*self.items[self.slot(i)].as_ptr() })
+    }
+
+    /// Removes all the items.
+    pub fn clear(&mut self) {
+        while self.pop().is_some() {}
+    }
+
+    /// Returns the number of items in the queue.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the queue has no items.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns `true` if the queue holds `N` items.
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    /// Returns the number of items the queue can hold, which is `N`.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    // The slot of the item `offset` places after the first.
+    fn slot(&self, offset: usize) -> usize {
+        (self.head + offset) % N
+    }
+}
+
+impl<T, const N: usize> Drop for ArrayQueue<T, N> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T, const N: usize> Default for ArrayQueue<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone, const N: usize> Clone for ArrayQueue<T, N> {
+    fn clone(&self) -> Self {
+        let mut queue = Self::new();
+        for item in self.iter() {
+            queue.push(item.clone());
+        }
+        queue
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for ArrayQueue<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq, const N: usize> PartialEq for ArrayQueue<T, N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq, const N: usize> Eq for ArrayQueue<T, N> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::rc::Rc;
+
+    #[test]
+    fn stack_fills_and_drains() {
+        let mut stack: ArrayStack<i32, 3> = ArrayStack::new();
+        assert_eq!(stack.pop(), None);
+        for i in 0..3 {
+            stack.push(i);
+        }
+        assert!(stack.is_full());
+        assert_eq!(stack.try_push(3), Err(3));
+        assert_eq!(stack.peek(), Some(&2));
+        assert_eq!(stack.pop(), Some(2));
+        assert_eq!(stack.clone(), stack);
+        assert_eq!(format!("{:?}", stack), "[0, 1]");
+    }
+
+    #[test]
+    #[should_panic(expected = "full")]
+    fn push_panics_when_full() {
+        let mut stack: ArrayStack<i32, 1> = ArrayStack::new();
+        stack.push(1);
+        stack.push(2);
+    }
+
+    #[test]
+    fn min_stack_tracks_min() {
+        let mut stack: ArrayMinStack<i32, 4> = ArrayMinStack::default();
+        for &i in [5, 2, 7, 1].iter() {
+            stack.push(i);
+        }
+        assert_eq!(stack.try_push(0), Err(0));
+        assert_eq!(stack.min(), Some(1));
+        assert_eq!(stack.pop(), Some(1));
+        assert_eq!(stack.min(), Some(2));
+        assert_eq!(stack.peek(), Some(7));
+        stack.pop();
+        stack.pop();
+        assert_eq!(stack.min(), Some(5));
+    }
+
+    #[test]
+    fn queue_wraps_around() {
+        let mut queue: ArrayQueue<i32, 3> = ArrayQueue::new();
+        for round in 0..10 {
+            queue.push(round);
+            queue.push(round + 100);
+            assert_eq!(queue.pop(), Some(round));
+            assert_eq!(queue.pop(), Some(round + 100));
+        }
+        for i in 1..=3 {
+            queue.push(i);
+        }
+        assert_eq!(queue.try_push(4), Err(4));
+        assert_eq!(queue.iter().rev().collect::<Vec<_>>(), [&3, &2, &1]);
+        assert_eq!(queue.peek(), Some(&1));
+    }
+
+    #[test]
+    fn drops_remaining_items() {
+        let counter = Rc::new(());
+        let mut stack: ArrayStack<Rc<()>, 4> = ArrayStack::new();
+        let mut queue: ArrayQueue<Rc<()>, 4> = ArrayQueue::new();
+        for _ in 0..3 {
+            stack.push(counter.clone());
+            queue.push(counter.clone());
+        }
+        queue.pop();
+        queue.push(counter.clone());
+        assert_eq!(Rc::strong_count(&counter), 7);
+        drop((stack, queue));
+        assert_eq!(Rc::strong_count(&counter), 1);
+    }
+}
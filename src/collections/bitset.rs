@@ -0,0 +1,623 @@
+#![deny(missing_docs)]
+
+use alloc::{vec, vec::Vec};
+use core::iter::FromIterator;
+use core::ops::{BitAndAssign, BitOrAssign, BitXorAssign};
+
+const WORD_BITS: usize = 64;
+
+/// A growable sequence of bits packed into 64-bit words.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitVec {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl BitVec {
+    /// Creates a new, empty BitVec.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a BitVec of `len` bits, all set to `value`.
+    pub fn from_elem(len: usize, value: bool) -> Self {
+        let fill = if value { !0 } else { 0 };
+        let mut bits = BitVec {
+            words: vec![fill; words_for(len)],
+            len,
+        };
+        bits.clear_unused();
+        bits
+    }
+
+    /// Creates a BitVec of `len` bits from packed words, least significant
+    /// bit first. Bits past `len` are ignored.
+    /// ## Panics
+    /// Panics if `words` holds fewer than `len` bits.
+    pub fn from_words(words: &[u64], len: usize) -> Self {
+        assert!(
+            words.len() * WORD_BITS >= len,
+            "not enough words for {} bits",
+            len
+        );
+        let mut bits = BitVec {
+            words: words[..words_for(len)].to_vec(),
+            len,
+        };
+        bits.clear_unused();
+        bits
+    }
+
+    /// Returns the number of bits.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if there are no bits.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the bits packed into words, least significant bit first.
+    /// Bits past `len` in the last word are always zero.
+    pub fn as_words(&self) -> &[u64] {
+        &self.words
+    }
+
+    /// Returns the bit at `index`, or `None` if it is out of bounds.
+    pub fn get(&self, index: usize) -> Option<bool> {
+        if index < self.len {
+            Some(self.words[index / WORD_BITS] >> (index % WORD_BITS) & 1 == 1)
+        } else {
+            None
+        }
+    }
+
+    /// Sets the bit at `index` to `value`.
+    /// ## Panics
+    /// Panics if `index` is out of bounds.
+    pub fn set(&mut self, index: usize, value: bool) {
+        assert!(index < self.len, "index {} is out of bounds", index);
+        let mask = 1 << (index % WORD_BITS);
+        if value {
+            self.words[index / WORD_BITS] |= mask;
+        } else {
+            self.words[index / WORD_BITS] &= !mask;
+        }
+    }
+
+    /// Adds a bit to the end in amortized O(1) time.
+    /// ## Examples
+    /// ```
+    /// # use stdlib_rs::collections::bitset::BitVec;
+    /// let mut bits = BitVec::new();
+    /// bits.push(true);
+    /// bits.push(false);
+    /// assert_eq!(bits.get(0), Some(true));
+    /// assert_eq!(bits.get(1), Some(false));
+    /// assert_eq!(bits.pop(), Some(false));
+    /// assert_eq!(bits.len(), 1);
+    /// ```
+    pub fn push(&mut self, value: bool) {
+        if self.len.is_multiple_of(WORD_BITS) {
+            self.words.push(0);
+        }
+        self.len += 1;
+        self.set(self.len - 1, value);
+    }
+
+    /// Removes the last bit and returns it, or `None` if there are no bits.
+    pub fn pop(&mut self) -> Option<bool> {
+        let value = self.get(self.len.checked_sub(1)?)?;
+        self.resize(self.len - 1, false);
+        Some(value)
+    }
+
+    /// Resizes to `len` bits, filling any new bits with `value`.
+    pub fn resize(&mut self, len: usize, value: bool) {
+        if len > self.len {
+            let old = self.len;
+            self.words.resize(words_for(len), 0);
+            self.len = len;
+            if value {
+                for i in old..len {
+                    self.set(i, true);
+                }
+            }
+        } else {
+            self.words.truncate(words_for(len));
+            self.len = len;
+            self.clear_unused();
+        }
+    }
+
+    /// Returns the number of bits that are set, one word at a time.
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// Returns the number of bits that are not set.
+    pub fn count_zeros(&self) -> usize {
+        self.len - self.count_ones()
+    }
+
+    /// Flips every bit, one word at a time.
+    pub fn negate(&mut self) {
+        for word in self.words.iter_mut() {
+            *word = !*word;
+        }
+        self.clear_unused();
+    }
+
+    /// Iterates over every bit in order.
+    pub fn iter(&self) -> impl Iterator<Item = bool> + '_ {
+        (0..self.len).map(move |i| self.get(i).unwrap())
+    }
+
+    /// Iterates over the indexes of the set bits in increasing order,
+    /// skipping empty words.
+    /// ## Examples
+    /// ```
+    /// # use stdlib_rs::collections::bitset::BitVec;
+    /// let bits: BitVec = vec![false, true, true, false, true].into_iter().collect();
+    /// assert_eq!(bits.iter_ones().collect::<Vec<_>>(), vec![1, 2, 4]);
+    /// ```
+    pub fn iter_ones(&self) -> Ones<'_> {
+        Ones {
+            words: &self.words,
+            index: 0,
+            word: self.words.first().copied().unwrap_or(0),
+        }
+    }
+
+    fn clear_unused(&mut self) {
+        let used = self.len % WORD_BITS;
+        if used != 0 {
+            if let Some(last) = self.words.last_mut() {
+                *last &= (1 << used) - 1;
+            }
+        }
+    }
+
+    fn zip_words<F: Fn(u64, u64) -> u64>(&mut self, other: &BitVec, f: F) {
+        assert_eq!(self.len, other.len, "bit vectors have different lengths");
+        for (a, b) in self.words.iter_mut().zip(other.words.iter()) {
+            *a = f(*a, *b);
+        }
+        self.clear_unused();
+    }
+}
+
+fn words_for(bits: usize) -> usize {
+    bits.div_ceil(WORD_BITS)
+}
+
+impl BitAndAssign<&BitVec> for BitVec {
+    /// ## Panics
+    /// Panics if the vectors have different lengths.
+    fn bitand_assign(&mut self, other: &BitVec) {
+        self.zip_words(other, |a, b| a & b);
+    }
+}
+
+impl BitOrAssign<&BitVec> for BitVec {
+    /// ## Panics
+    /// Panics if the vectors have different lengths.
+    fn bitor_assign(&mut self, other: &BitVec) {
+        self.zip_words(other, |a, b| a | b);
+    }
+}
+
+impl BitXorAssign<&BitVec> for BitVec {
+    /// ## Panics
+    /// Panics if the vectors have different lengths.
+    fn bitxor_assign(&mut self, other: &BitVec) {
+        self.zip_words(other, |a, b| a ^ b);
+    }
+}
+
+impl FromIterator<bool> for BitVec {
+    fn from_iter<I: IntoIterator<Item = bool>>(iter: I) -> Self {
+        let mut bits = BitVec::new();
+        for value in iter {
+            bits.push(value);
+        }
+        bits
+    }
+}
+
+/// An iterator over the indexes of the set bits of a `BitVec` or `BitSet`.
+pub struct Ones<'a> {
+    words: &'a [u64],
+    index: usize,
+    word: u64,
+}
+
+impl<'a> Iterator for Ones<'a> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while self.word == 0 {
+            self.index += 1;
+            self.word = *self.words.get(self.index)?;
+        }
+        let bit = self.word.trailing_zeros() as usize;
+        // Clear the lowest set bit.
+        self.word &= self.word - 1;
+        Some(self.index * WORD_BITS + bit)
+    }
+}
+
+/// A set of `usize`s stored as a bitmap that grows to fit the largest item.
+#[derive(Default, Debug, Clone)]
+pub struct BitSet(BitVec);
+
+impl BitSet {
+    /// Creates a new, empty BitSet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new BitSet with room for items below `capacity`.
+    pub fn with_capacity(capacity: usize) -> Self {
+        BitSet(BitVec::from_elem(capacity, false))
+    }
+
+    /// Returns the number of items in the set.
+    pub fn len(&self) -> usize {
+        self.0.count_ones()
+    }
+
+    /// Returns `true` if the set has no items.
+    pub fn is_empty(&self) -> bool {
+        self.0.words.iter().all(|&w| w == 0)
+    }
+
+    /// Adds `item` to the set, returning `true` if it was not already present.
+    /// ## Examples
+    /// ```
+    /// # use stdlib_rs::collections::bitset::BitSet;
+    /// let mut set = BitSet::new();
+    /// assert!(set.insert(100));
+    /// assert!(!set.insert(100));
+    /// assert!(set.contains(100));
+    /// assert_eq!(set.len(), 1);
+    /// ```
+    pub fn insert(&mut self, item: usize) -> bool {
+        if item >= self.0.len() {
+            self.0.resize(item + 1, false);
+        }
+        let present = self.contains(item);
+        self.0.set(item, true);
+        !present
+    }
+
+    /// Removes `item` from the set, returning `true` if it was present.
+    pub fn remove(&mut self, item: usize) -> bool {
+        let present = self.contains(item);
+        if present {
+            self.0.set(item, false);
+        }
+        present
+    }
+
+    /// Returns `true` if `item` is in the set.
+    pub fn contains(&self, item: usize) -> bool {
+        self.0.get(item).unwrap_or(false)
+    }
+
+    /// Removes all the items from the set.
+    pub fn clear(&mut self) {
+        self.0 = BitVec::new();
+    }
+
+    /// Iterates over the items of the set in increasing order.
+    pub fn iter(&self) -> Ones<'_> {
+        self.0.iter_ones()
+    }
+
+    /// Adds every item of `other` to the set, one word at a time.
+    pub fn union_with(&mut self, other: &BitSet) {
+        if other.0.len() > self.0.len() {
+            self.0.resize(other.0.len(), false);
+        }
+        for (a, b) in self.0.words.iter_mut().zip(other.0.words.iter()) {
+            *a |= b;
+        }
+    }
+
+    /// Removes every item that is not in `other`, one word at a time.
+    pub fn intersect_with(&mut self, other: &BitSet) {
+        for (i, a) in self.0.words.iter_mut().enumerate() {
+            *a &= other.0.words.get(i).copied().unwrap_or(0);
+        }
+    }
+
+    /// Removes every item of `other` from the set, one word at a time.
+    pub fn difference_with(&mut self, other: &BitSet) {
+        for (a, b) in self.0.words.iter_mut().zip(other.0.words.iter()) {
+            *a &= !b;
+        }
+    }
+
+    /// Keeps the items that are in exactly one of the sets, one word at a time.
+    pub fn symmetric_difference_with(&mut self, other: &BitSet) {
+        if other.0.len() > self.0.len() {
+            self.0.resize(other.0.len(), false);
+        }
+        for (a, b) in self.0.words.iter_mut().zip(other.0.words.iter()) {
+            *a ^= b;
+        }
+    }
+
+    /// Returns `true` if every item of the set is also in `other`.
+    pub fn is_subset(&self, other: &BitSet) -> bool {
+        self.0
+            .words
+            .iter()
+            .enumerate()
+            .all(|(i, a)| a & !other.0.words.get(i).copied().unwrap_or(0) == 0)
+    }
+
+    /// Returns `true` if the sets have no items in common.
+    pub fn is_disjoint(&self, other: &BitSet) -> bool {
+        self.0
+            .words
+            .iter()
+            .zip(other.0.words.iter())
+            .all(|(a, b)| a & b == 0)
+    }
+}
+
+impl PartialEq for BitSet {
+    /// Two sets are equal if they hold the same items, whatever their capacity.
+    fn eq(&self, other: &BitSet) -> bool {
+        self.is_subset(other) && other.is_subset(self)
+    }
+}
+
+impl Eq for BitSet {}
+
+impl FromIterator<usize> for BitSet {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut set = BitSet::new();
+        for item in iter {
+            set.insert(item);
+        }
+        set
+    }
+}
+
+/// The number of words covered by each superblock of a `RankSelect` index.
+const SUPERBLOCK_WORDS: usize = 8;
+
+/// A succinct index over a `BitVec` that answers rank queries in O(1) time
+/// and select queries in O(log n) time, using o(n) extra bits.
+pub struct RankSelect {
+    bits: BitVec,
+    // The number of ones before each superblock.
+    superblocks: Vec<usize>,
+    // The number of ones before each word, within its superblock.
+    blocks: Vec<u16>,
+    ones: usize,
+}
+
+impl RankSelect {
+    /// Builds the index over `bits` in O(n) time.
+    /// ## Examples
+    /// ```
+    /// # use stdlib_rs::collections::bitset::{BitVec, RankSelect};
+    /// let bits: BitVec = vec![true, false, true, true, false].into_iter().collect();
+    /// let index = RankSelect::new(bits);
+    /// assert_eq!(index.rank1(3), 2);
+    /// assert_eq!(index.rank0(5), 2);
+    /// assert_eq!(index.select1(2), Some(3));
+    /// assert_eq!(index.select0(1), Some(4));
+    /// ```
+    pub fn new(bits: BitVec) -> Self {
+        let mut superblocks = Vec::with_capacity(bits.words.len() / SUPERBLOCK_WORDS + 1);
+        let mut blocks = Vec::with_capacity(bits.words.len());
+        let mut total = 0;
+        let mut within = 0;
+        for (i, word) in bits.words.iter().enumerate() {
+            if i % SUPERBLOCK_WORDS == 0 {
+                superblocks.push(total);
+                within = 0;
+            }
+            blocks.push(within as u16);
+            let ones = word.count_ones() as usize;
+            within += ones;
+            total += ones;
+        }
+        RankSelect {
+            bits,
+            superblocks,
+            blocks,
+            ones: total,
+        }
+    }
+
+    /// Returns the indexed bits.
+    pub fn bits(&self) -> &BitVec {
+        &self.bits
+    }
+
+    /// Returns the number of set bits before `index`.
+    /// ## Panics
+    /// Panics if `index` is greater than the number of bits.
+    pub fn rank1(&self, index: usize) -> usize {
+        assert!(index <= self.bits.len, "index {} is out of bounds", index);
+        let word = index / WORD_BITS;
+        if word == self.bits.words.len() {
+            return self.ones;
+        }
+        let mask = (1u64 << (index % WORD_BITS)) - 1;
+        self.superblocks[word / SUPERBLOCK_WORDS]
+            + self.blocks[word] as usize
+            + (self.bits.words[word] & mask).count_ones() as usize
+    }
+
+    /// Returns the number of unset bits before `index`.
+    /// ## Panics
+    /// Panics if `index` is greater than the number of bits.
+    pub fn rank0(&self, index: usize) -> usize {
+        index - self.rank1(index)
+    }
+
+    /// Returns the index of the set bit with rank `k`, counting from zero,
+    /// or `None` if there are not that many set bits.
+    pub fn select1(&self, k: usize) -> Option<usize> {
+        if k >= self.ones {
+            return None;
+        }
+        Some(self.select(k, |before, _| before, |word| word))
+    }
+
+    /// Returns the index of the unset bit with rank `k`, counting from zero,
+    /// or `None` if there are not that many unset bits.
+    pub fn select0(&self, k: usize) -> Option<usize> {
+        if k >= self.bits.len - self.ones {
+            return None;
+        }
+        Some(self.select(k, |before, bits| bits - before, |word| !word))
+    }
+
+    /// Finds the `k`th bit, where `count(ones, bits)` turns the number of
+    /// ones in the first `bits` bits into the number of matching bits and
+    /// `flip` turns a word into one with the matching bits set.
+    fn select<C, F>(&self, k: usize, count: C, flip: F) -> usize
+    where
+        C: Fn(usize, usize) -> usize,
+        F: Fn(u64) -> u64,
+    {
+        // Find the last superblock with at most k matching bits before it.
+        let (mut lo, mut hi) = (0, self.superblocks.len());
+        while hi - lo > 1 {
+            let mid = (lo + hi) / 2;
+            let bits = mid * SUPERBLOCK_WORDS * WORD_BITS;
+            if count(self.superblocks[mid], bits) <= k {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+        let mut word = lo * SUPERBLOCK_WORDS;
+        let end = (word + SUPERBLOCK_WORDS).min(self.bits.words.len());
+        while word + 1 < end {
+            let before = self.superblocks[lo] + self.blocks[word + 1] as usize;
+            if count(before, (word + 1) * WORD_BITS) > k {
+                break;
+            }
+            word += 1;
+        }
+        let before = self.superblocks[lo] + self.blocks[word] as usize;
+        let mut rest = k - count(before, word * WORD_BITS);
+        let mut bits = flip(self.bits.words[word]);
+        while rest > 0 {
+            bits &= bits - 1;
+            rest -= 1;
+        }
+        word * WORD_BITS + bits.trailing_zeros() as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pattern(len: usize) -> BitVec {
+        (0..len).map(|i| (i * 7919) % 13 < 5).collect()
+    }
+
+    #[test]
+    fn push_pop_test() {
+        let mut bits = BitVec::new();
+        for i in 0..200 {
+            bits.push(i % 3 == 0);
+        }
+        assert_eq!(bits.len(), 200);
+        assert_eq!(bits.count_ones(), 67);
+        for i in (0..200).rev() {
+            assert_eq!(bits.pop(), Some(i % 3 == 0));
+        }
+        assert_eq!(bits.pop(), None);
+        assert!(bits.is_empty());
+    }
+
+    #[test]
+    fn bulk_ops_test() {
+        let mut a = BitVec::from_elem(130, true);
+        let b = pattern(130);
+        a ^= &b;
+        assert_eq!(a.count_ones(), b.count_zeros());
+        a |= &b;
+        assert_eq!(a.count_ones(), 130);
+        a &= &b;
+        assert_eq!(a, b);
+        a.negate();
+        assert_eq!(a.count_ones(), b.count_zeros());
+        assert_eq!(a.as_words()[2] >> 2, 0);
+    }
+
+    #[test]
+    fn iter_ones_test() {
+        let bits = pattern(300);
+        let expected: Vec<usize> = (0..300).filter(|&i| bits.get(i).unwrap()).collect();
+        assert_eq!(bits.iter_ones().collect::<Vec<_>>(), expected);
+        assert_eq!(BitVec::new().iter_ones().count(), 0);
+    }
+
+    #[test]
+    fn set_ops_test() {
+        let mut a: BitSet = vec![1, 3, 5, 200].into_iter().collect();
+        let b: BitSet = vec![3, 4, 5].into_iter().collect();
+        let mut union = a.clone();
+        union.union_with(&b);
+        assert_eq!(union.iter().collect::<Vec<_>>(), vec![1, 3, 4, 5, 200]);
+        let mut intersection = a.clone();
+        intersection.intersect_with(&b);
+        assert_eq!(intersection.iter().collect::<Vec<_>>(), vec![3, 5]);
+        let mut symmetric = a.clone();
+        symmetric.symmetric_difference_with(&b);
+        assert_eq!(symmetric.iter().collect::<Vec<_>>(), vec![1, 4, 200]);
+        a.difference_with(&b);
+        assert_eq!(a.iter().collect::<Vec<_>>(), vec![1, 200]);
+        assert!(a.is_disjoint(&b));
+        assert!(intersection.is_subset(&b));
+        assert!(!b.is_subset(&intersection));
+    }
+
+    #[test]
+    fn set_equality_ignores_capacity() {
+        let mut a = BitSet::with_capacity(1000);
+        a.insert(3);
+        let b: BitSet = vec![3].into_iter().collect();
+        assert_eq!(a, b);
+        assert!(a.remove(3));
+        assert!(!a.remove(3));
+        assert!(a.is_empty());
+    }
+
+    #[test]
+    fn rank_select_matches_naive() {
+        let bits = pattern(2000);
+        let ones: Vec<usize> = bits.iter_ones().collect();
+        let zeros: Vec<usize> = (0..2000).filter(|&i| !bits.get(i).unwrap()).collect();
+        let index = RankSelect::new(bits.clone());
+        let mut rank = 0;
+        for i in 0..=2000 {
+            assert_eq!(index.rank1(i), rank);
+            if i < 2000 && bits.get(i).unwrap() {
+                rank += 1;
+            }
+        }
+        for (k, &position) in ones.iter().enumerate() {
+            assert_eq!(index.select1(k), Some(position));
+        }
+        for (k, &position) in zeros.iter().enumerate() {
+            assert_eq!(index.select0(k), Some(position));
+        }
+        assert_eq!(index.select1(ones.len()), None);
+        assert_eq!(index.select0(zeros.len()), None);
+    }
+}
@@ -0,0 +1,170 @@
+#![deny(missing_docs)]
+
+use alloc::{vec, vec::Vec};
+use core::iter::FromIterator;
+use core::ops::{Add, RangeBounds, Sub};
+
+use super::to_bounds;
+
+/// A Fenwick tree (binary indexed tree) that supports point updates and
+/// prefix sums over an array in O(log n) time.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct FenwickTree<T>(Vec<T>);
+
+impl<T> FenwickTree<T>
+where
+    T: Copy + Default + Add<Output = T> + Sub<Output = T>,
+{
+    /// Creates a new FenwickTree of `len` elements, all set to `T::default()`.
+    pub fn new(len: usize) -> Self {
+        FenwickTree(vec![T::default(); len])
+    }
+
+    /// Returns the number of elements in the tree.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if the tree has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Adds `delta` to the element at `index` in O(log n) time.
+    /// ## Panics
+    /// Panics if `index` is out of bounds.
+    /// ## Examples
+    /// ```
+    /// # use stdlib_rs::collections::fenwick::FenwickTree;
+    /// let mut tree = FenwickTree::new(4);
+    /// tree.add(1, 5);
+    /// tree.add(3, 2);
+    /// assert_eq!(tree.prefix_sum(2), 5);
+    /// assert_eq!(tree.prefix_sum(4), 7);
+    /// ```
+    pub fn add(&mut self, index: usize, delta: T) {
+        assert!(index < self.len(), "index {} is out of bounds", index);
+        let mut i = index;
+        while i < self.0.len() {
+            self.0[i] = self.0[i] + delta;
+            i |= i + 1;
+        }
+    }
+
+    /// Returns the sum of the first `end` elements in O(log n) time.
+    /// ## Panics
+    /// Panics if `end` is greater than the length of the tree.
+    pub fn prefix_sum(&self, end: usize) -> T {
+        assert!(end <= self.len(), "end {} is out of bounds", end);
+        let mut sum = T::default();
+        let mut i = end;
+        while i > 0 {
+            sum = sum + self.0[i - 1];
+            i &= i - 1;
+        }
+        sum
+    }
+
+    /// Returns the sum of the elements in `range` in O(log n) time.
+    /// ## Panics
+    /// Panics if the range is out of bounds.
+    /// ## Examples
+    /// ```
+    /// # use stdlib_rs::collections::fenwick::FenwickTree;
+    /// let tree: FenwickTree<i32> = vec![1, 2, 3, 4].into();
+    /// assert_eq!(tree.sum(1..3), 5);
+    /// assert_eq!(tree.sum(..), 10);
+    /// ```
+    pub fn sum<R: RangeBounds<usize>>(&self, range: R) -> T {
+        let (start, end) = to_bounds(range, self.len());
+        self.prefix_sum(end) - self.prefix_sum(start)
+    }
+
+    /// Returns the element at `index` in O(log n) time.
+    pub fn get(&self, index: usize) -> T {
+        self.sum(index..=index)
+    }
+
+    /// Sets the element at `index` to `value` in O(log n) time.
+    pub fn set(&mut self, index: usize, value: T) {
+        let current = self.get(index);
+        self.add(index, value - current);
+    }
+}
+
+impl<T> From<Vec<T>> for FenwickTree<T>
+where
+    T: Copy + Add<Output = T>,
+{
+    /// Builds a FenwickTree from a vector in O(n) time.
+    fn from(mut vec: Vec<T>) -> Self {
+        for i in 0..vec.len() {
+            let parent = i | (i + 1);
+            if parent < vec.len() {
+                vec[parent] = vec[parent] + vec[i];
+            }
+        }
+        FenwickTree(vec)
+    }
+}
+
+impl<T> FromIterator<T> for FenwickTree<T>
+where
+    T: Copy + Add<Output = T>,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        FenwickTree::from(iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_test() {
+        let tree: FenwickTree<i32> = FenwickTree::new(0);
+        assert!(tree.is_empty());
+        assert_eq!(tree.prefix_sum(0), 0);
+    }
+
+    #[test]
+    fn prefix_sum_test() {
+        let tree: FenwickTree<i32> = (1..=10).collect();
+        for end in 0..=10 {
+            assert_eq!(tree.prefix_sum(end), (1..=end as i32).sum());
+        }
+    }
+
+    #[test]
+    fn add_matches_naive() {
+        let mut naive = [0i64; 17];
+        let mut tree = FenwickTree::new(17);
+        for (i, item) in naive.iter_mut().enumerate() {
+            let delta = (i as i64 * 7) % 5 - 2;
+            *item += delta;
+            tree.add(i, delta);
+        }
+        for start in 0..17 {
+            for end in start..=17 {
+                assert_eq!(tree.sum(start..end), naive[start..end].iter().sum());
+            }
+        }
+    }
+
+    #[test]
+    fn get_and_set_test() {
+        let mut tree: FenwickTree<i32> = vec![4, 8, 15, 16, 23, 42].into();
+        assert_eq!(tree.get(3), 16);
+        tree.set(3, 0);
+        assert_eq!(tree.get(3), 0);
+        assert_eq!(tree.sum(..), 92);
+    }
+
+    #[test]
+    #[should_panic]
+    fn sum_out_of_bounds() {
+        let tree: FenwickTree<i32> = FenwickTree::new(3);
+        tree.sum(0..4);
+    }
+}
@@ -1,17 +1,23 @@
-use std::mem;
+use core::mem;
 
-pub struct List {
-    head: Link,
+use super::allocator::{AllocBox, Allocator, Global};
+
+/// A singly linked stack of `i32`s, with one allocation per node.
+/// Nodes come from the global allocator unless another `Allocator` is
+/// chosen with `A`.
+pub struct List<A: Allocator = Global> {
+    head: Link<A>,
+    alloc: A,
 }
 
-enum Link {
+enum Link<A: Allocator> {
     Empty,
-    More(Box<Node>),
+    More(AllocBox<Node<A>, A>),
 }
 
-struct Node {
+struct Node<A: Allocator> {
     elem: i32,
-    next: Link,
+    next: Link<A>,
 }
 
 impl Default for List {
@@ -22,14 +28,27 @@ impl Default for List {
 
 impl List {
     pub fn new() -> Self {
-        List { head: Link::Empty }
+        Self::new_in(Global)
+    }
+}
+
+impl<A: Allocator + Clone> List<A> {
+    /// Creates a new List that allocates its nodes from `alloc`.
+    pub fn new_in(alloc: A) -> Self {
+        List {
+            head: Link::Empty,
+            alloc,
+        }
     }
 
     pub fn push(&mut self, elem: i32) {
-        let new_node = Box::new(Node {
-            elem,
-            next: mem::replace(&mut self.head, Link::Empty),
-        });
+        let new_node = AllocBox::new_in(
+            Node {
+                elem,
+                next: mem::replace(&mut self.head, Link::Empty),
+            },
+            self.alloc.clone(),
+        );
         self.head = Link::More(new_node);
     }
 
@@ -37,6 +56,7 @@ impl List {
         match mem::replace(&mut self.head, Link::Empty) {
             Link::Empty => None,
             Link::More(node) => {
+                let node = AllocBox::into_inner(node);
                 self.head = node.next;
                 Some(node.elem)
             }
@@ -44,7 +64,7 @@ impl List {
     }
 }
 
-impl Drop for List {
+impl<A: Allocator> Drop for List<A> {
     fn drop(&mut self) {
         let mut cur_link = mem::replace(&mut self.head, Link::Empty);
         while let Link::More(mut boxed_node) = cur_link {
@@ -55,6 +75,7 @@ impl Drop for List {
 
 #[cfg(test)]
 mod test {
+    use super::super::arena::Bump;
     use super::List;
 
     #[test]
@@ -85,4 +106,18 @@ mod test {
         assert_eq!(list.pop(), Some(1));
         assert_eq!(list.pop(), None);
     }
+
+    #[test]
+    fn allocator_basics() {
+        let bump = Bump::new();
+        let mut list = List::new_in(&bump);
+        for i in 0..100 {
+            list.push(i);
+        }
+        assert!(bump.allocated_bytes() >= 100 * 8);
+        for i in (0..100).rev() {
+            assert_eq!(list.pop(), Some(i));
+        }
+        assert_eq!(list.pop(), None);
+    }
 }
@@ -0,0 +1,171 @@
+#![deny(missing_docs)]
+
+use alloc::{vec, vec::Vec};
+use core::iter::FromIterator;
+
+/// A gap buffer implemented with two stacks.
+/// Items before the cursor live on the first stack and items after it live
+/// on the second stack in reverse, so edits at the cursor are O(1) and
+/// moving the cursor costs O(distance moved).
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct GapBuffer<T>(Vec<T>, Vec<T>);
+
+impl<T> GapBuffer<T> {
+    /// Creates a new GapBuffer with the cursor at position 0.
+    pub fn new() -> Self {
+        GapBuffer(vec![], vec![])
+    }
+
+    /// Returns the number of items in the buffer.
+    pub fn len(&self) -> usize {
+        self.0.len() + self.1.len()
+    }
+
+    /// Returns `true` if the buffer has no items.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty() && self.1.is_empty()
+    }
+
+    /// Returns the position of the cursor, which is the number of items
+    /// before it.
+    pub fn cursor(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Moves the cursor to `position` in O(distance moved) time.
+    /// ## Panics
+    /// Panics if `position` is greater than the length of the buffer.
+    /// ## Examples
+    /// ```
+    /// # use stdlib_rs::collections::gap_buffer::GapBuffer;
+    /// let mut buffer: GapBuffer<char> = "held".chars().collect();
+    /// buffer.move_to(3);
+    /// buffer.insert('l');
+    /// buffer.insert('o');
+    /// buffer.move_to(5);
+    /// buffer.insert(' ');
+    /// assert_eq!(buffer.iter().collect::<String>(), "hello d");
+    /// ```
+    pub fn move_to(&mut self, position: usize) {
+        assert!(
+            position <= self.len(),
+            "position {} is out of bounds",
+            position
+        );
+        while self.0.len() > position {
+            let item = self.0.pop().unwrap();
+            self.1.push(item);
+        }
+        while self.0.len() < position {
+            let item = self.1.pop().unwrap();
+            self.0.push(item);
+        }
+    }
+
+    /// Inserts an item at the cursor in O(1) time, leaving the cursor
+    /// after it.
+    pub fn insert(&mut self, item: T) {
+        self.0.push(item);
+    }
+
+    /// Removes and returns the item before the cursor in O(1) time, or
+    /// `None` if the cursor is at the start.
+    pub fn remove_before(&mut self) -> Option<T> {
+        self.0.pop()
+    }
+
+    /// Removes and returns the item after the cursor in O(1) time, or
+    /// `None` if the cursor is at the end.
+    pub fn remove_after(&mut self) -> Option<T> {
+        self.1.pop()
+    }
+
+    /// Returns the item at `index`, or `None` if it is out of bounds.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index >= self.len() {
+            None
+        } else if index < self.0.len() {
+            self.0.get(index)
+        } else {
+            self.1.get(self.len() - index - 1)
+        }
+    }
+
+    /// Iterates over the items of the buffer in order.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.0.iter().chain(self.1.iter().rev())
+    }
+}
+
+impl<T> Extend<T> for GapBuffer<T> {
+    /// Inserts every item at the cursor, leaving the cursor after them.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.0.extend(iter);
+    }
+}
+
+impl<T> FromIterator<T> for GapBuffer<T> {
+    /// Creates a GapBuffer with the cursor after the last item.
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        GapBuffer(iter.into_iter().collect(), vec![])
+    }
+}
+
+impl<T> IntoIterator for GapBuffer<T> {
+    type Item = T;
+    type IntoIter =
+        core::iter::Chain<alloc::vec::IntoIter<T>, core::iter::Rev<alloc::vec::IntoIter<T>>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter().chain(self.1.into_iter().rev())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_test() {
+        let buffer: GapBuffer<i32> = GapBuffer::new();
+        assert!(buffer.is_empty());
+        assert_eq!(buffer.cursor(), 0);
+    }
+
+    #[test]
+    fn edit_at_cursor_test() {
+        let mut buffer: GapBuffer<i32> = (1..=5).collect();
+        buffer.move_to(2);
+        assert_eq!(buffer.remove_after(), Some(3));
+        assert_eq!(buffer.remove_before(), Some(2));
+        buffer.extend(vec![7, 8]);
+        assert_eq!(buffer.cursor(), 3);
+        assert_eq!(buffer.into_iter().collect::<Vec<_>>(), vec![1, 7, 8, 4, 5]);
+    }
+
+    #[test]
+    fn get_test() {
+        let mut buffer: GapBuffer<i32> = (0..6).collect();
+        buffer.move_to(4);
+        for i in 0..6 {
+            assert_eq!(buffer.get(i), Some(&(i as i32)));
+        }
+        assert_eq!(buffer.get(6), None);
+        assert_eq!(buffer.get(usize::MAX), None);
+    }
+
+    #[test]
+    fn remove_at_ends_test() {
+        let mut buffer: GapBuffer<i32> = (0..2).collect();
+        assert_eq!(buffer.remove_after(), None);
+        buffer.move_to(0);
+        assert_eq!(buffer.remove_before(), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn move_out_of_bounds() {
+        let mut buffer: GapBuffer<i32> = (0..2).collect();
+        buffer.move_to(3);
+    }
+}
@@ -0,0 +1,661 @@
+#![deny(missing_docs)]
+
+use alloc::{collections::BinaryHeap, vec, vec::Vec};
+use core::cmp::Reverse;
+use core::error::Error;
+use core::fmt;
+use core::ops::{Add, Index, IndexMut};
+
+use super::min_stack::MinStack;
+use super::queue_with_stack::Queue;
+
+/// The id of a node in a `Graph`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeId(usize);
+
+/// The id of an edge in a `Graph`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EdgeId(usize);
+
+impl NodeId {
+    /// Returns the position of the node in the order nodes were added.
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+impl EdgeId {
+    /// Returns the position of the edge in the order edges were added.
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+/// An edge of a `Graph`, with the value it carries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edge<E> {
+    /// The node the edge starts from.
+    pub source: NodeId,
+    /// The node the edge points to.
+    pub target: NodeId,
+    /// The value carried by the edge, used as its weight by the shortest
+    /// path and spanning tree algorithms.
+    pub weight: E,
+}
+
+/// Returned by `Graph::topological_sort` when the graph has a cycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle(pub NodeId);
+
+impl fmt::Display for Cycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "node {} is on a cycle", self.0.index())
+    }
+}
+
+impl Error for Cycle {}
+
+/// Returned by `Graph::bellman_ford` when a negative cycle is reachable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NegativeCycle(pub NodeId);
+
+impl fmt::Display for NegativeCycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "node {} is reachable from a negative cycle",
+            self.0.index()
+        )
+    }
+}
+
+impl Error for NegativeCycle {}
+
+/// The shortest distances and paths from one node to every other node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShortestPaths<E> {
+    distances: Vec<Option<E>>,
+    previous: Vec<Option<NodeId>>,
+}
+
+impl<E: Copy> ShortestPaths<E> {
+    /// Returns the length of the shortest path to `node`, or `None` if it
+    /// is unreachable.
+    pub fn distance(&self, node: NodeId) -> Option<E> {
+        self.distances[node.0]
+    }
+
+    /// Returns the nodes on the shortest path to `node`, starting with the
+    /// source, or `None` if it is unreachable.
+    pub fn path(&self, node: NodeId) -> Option<Vec<NodeId>> {
+        self.distances[node.0]?;
+        let mut path = vec![node];
+        let mut current = node;
+        while let Some(previous) = self.previous[current.0] {
+            path.push(previous);
+            current = previous;
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// A graph stored as adjacency lists, with values of type `N` on its nodes
+/// and `E` on its edges.
+/// Edges of an undirected graph can be followed from either end.
+#[derive(Debug, Clone)]
+pub struct Graph<N, E> {
+    nodes: Vec<N>,
+    edges: Vec<Edge<E>>,
+    // For each node, the edges leaving it and the node at their other end.
+    adjacency: Vec<Vec<(EdgeId, NodeId)>>,
+    directed: bool,
+}
+
+impl<N, E> Graph<N, E> {
+    /// Creates a new directed Graph.
+    pub fn directed() -> Self {
+        Graph {
+            nodes: vec![],
+            edges: vec![],
+            adjacency: vec![],
+            directed: true,
+        }
+    }
+
+    /// Creates a new undirected Graph.
+    pub fn undirected() -> Self {
+        Graph {
+            directed: false,
+            ..Self::directed()
+        }
+    }
+
+    /// Returns `true` if the graph is directed.
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    /// Returns the number of nodes in the graph.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns the number of edges in the graph.
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// Adds a node carrying `value` and returns its id.
+    pub fn add_node(&mut self, value: N) -> NodeId {
+        self.nodes.push(value);
+        self.adjacency.push(vec![]);
+        NodeId(self.nodes.len() - 1)
+    }
+
+    /// Adds an edge from `source` to `target` carrying `weight` and returns
+    /// its id.
+    /// ## Panics
+    /// Panics if either node is not in the graph.
+    pub fn add_edge(&mut self, source: NodeId, target: NodeId, weight: E) -> EdgeId {
+        assert!(
+            source.0 < self.nodes.len(),
+            "{:?} is not in the graph",
+            source
+        );
+        assert!(
+            target.0 < self.nodes.len(),
+            "{:?} is not in the graph",
+            target
+        );
+        let id = EdgeId(self.edges.len());
+        self.edges.push(Edge {
+            source,
+            target,
+            weight,
+        });
+        self.adjacency[source.0].push((id, target));
+        if !self.directed && source != target {
+            self.adjacency[target.0].push((id, source));
+        }
+        id
+    }
+
+    /// Returns the edge with the given id.
+    pub fn edge(&self, id: EdgeId) -> &Edge<E> {
+        &self.edges[id.0]
+    }
+
+    /// Iterates over the ids of every node, in the order they were added.
+    pub fn node_ids(&self) -> impl Iterator<Item = NodeId> {
+        (0..self.nodes.len()).map(NodeId)
+    }
+
+    /// Iterates over every edge with its id, in the order they were added.
+    pub fn edges(&self) -> impl Iterator<Item = (EdgeId, &Edge<E>)> {
+        self.edges
+            .iter()
+            .enumerate()
+            .map(|(i, edge)| (EdgeId(i), edge))
+    }
+
+    /// Iterates over the nodes that can be reached from `node` by one edge.
+    pub fn neighbors(&self, node: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        self.adjacency[node.0].iter().map(|&(_, next)| next)
+    }
+
+    /// Iterates over the edges that can be followed from `node`, with the
+    /// node at their other end.
+    pub fn edges_from(&self, node: NodeId) -> impl Iterator<Item = (EdgeId, NodeId, &E)> + '_ {
+        self.adjacency[node.0]
+            .iter()
+            .map(move |&(id, next)| (id, next, &self.edges[id.0].weight))
+    }
+
+    /// Returns the nodes reachable from `start` in breadth-first order.
+    /// ## Examples
+    /// ```
+    /// # use stdlib_rs::collections::graph::Graph;
+    /// let mut graph = Graph::directed();
+    /// let a = graph.add_node("a");
+    /// let b = graph.add_node("b");
+    /// let c = graph.add_node("c");
+    /// let d = graph.add_node("d");
+    /// graph.add_edge(a, b, ());
+    /// graph.add_edge(b, d, ());
+    /// graph.add_edge(a, c, ());
+    /// assert_eq!(graph.bfs(a), vec![a, b, c, d]);
+    /// assert_eq!(graph.dfs(a), vec![a, b, d, c]);
+    /// ```
+    pub fn bfs(&self, start: NodeId) -> Vec<NodeId> {
+        let mut seen = vec![false; self.nodes.len()];
+        let mut order = vec![];
+        let mut queue = Queue::new();
+        seen[start.0] = true;
+        queue.push(start);
+        while let Some(node) = queue.pop() {
+            order.push(node);
+            for next in self.neighbors(node) {
+                if !seen[next.0] {
+                    seen[next.0] = true;
+                    queue.push(next);
+                }
+            }
+        }
+        order
+    }
+
+    /// Returns the nodes reachable from `start` in depth-first preorder,
+    /// following each node's edges in the order they were added.
+    pub fn dfs(&self, start: NodeId) -> Vec<NodeId> {
+        let mut seen = vec![false; self.nodes.len()];
+        let mut order = vec![];
+        let mut stack = MinStack::new();
+        stack.push(start);
+        while let Some(node) = stack.pop() {
+            if seen[node.0] {
+                continue;
+            }
+            seen[node.0] = true;
+            order.push(node);
+            // Push in reverse so the first edge is explored first.
+            let next: Vec<NodeId> = self.neighbors(node).filter(|n| !seen[n.0]).collect();
+            for &n in next.iter().rev() {
+                stack.push(n);
+            }
+        }
+        order
+    }
+
+    /// Orders the nodes so that every edge points from an earlier node to a
+    /// later one, or returns a node on a cycle if there is no such order.
+    /// Every edge of an undirected graph counts as a cycle.
+    /// ## Examples
+    /// ```
+    /// # use stdlib_rs::collections::graph::{Cycle, Graph};
+    /// let mut graph = Graph::directed();
+    /// let build = graph.add_node("build");
+    /// let test = graph.add_node("test");
+    /// let deploy = graph.add_node("deploy");
+    /// graph.add_edge(test, deploy, ());
+    /// graph.add_edge(build, test, ());
+    /// assert_eq!(graph.topological_sort(), Ok(vec![build, test, deploy]));
+    /// graph.add_edge(deploy, build, ());
+    /// assert!(graph.topological_sort().is_err());
+    /// ```
+    pub fn topological_sort(&self) -> Result<Vec<NodeId>, Cycle> {
+        let mut incoming = vec![0; self.nodes.len()];
+        for edges in self.adjacency.iter() {
+            for &(_, next) in edges.iter() {
+                incoming[next.0] += 1;
+            }
+        }
+        let mut queue: Queue<NodeId> = self.node_ids().filter(|n| incoming[n.0] == 0).collect();
+        let mut order = Vec::with_capacity(self.nodes.len());
+        while let Some(node) = queue.pop() {
+            order.push(node);
+            for next in self.neighbors(node) {
+                incoming[next.0] -= 1;
+                if incoming[next.0] == 0 {
+                    queue.push(next);
+                }
+            }
+        }
+        match self.node_ids().find(|n| incoming[n.0] > 0) {
+            Some(node) => Err(Cycle(node)),
+            None => Ok(order),
+        }
+    }
+
+    /// Returns the strongly connected components of the graph, each as a
+    /// list of nodes, in reverse topological order of the components.
+    /// For an undirected graph these are its connected components.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<NodeId>> {
+        // Tarjan's algorithm, with an explicit call stack of (node, next edge).
+        let count = self.nodes.len();
+        let mut index = vec![usize::MAX; count];
+        let mut low = vec![0; count];
+        let mut on_stack = vec![false; count];
+        let mut stack = vec![];
+        let mut components = vec![];
+        let mut next_index = 0;
+        for root in self.node_ids() {
+            if index[root.0] != usize::MAX {
+                continue;
+            }
+            let mut calls = vec![(root, 0)];
+            while let Some(&mut (node, ref mut edge)) = calls.last_mut() {
+                if *edge == 0 {
+                    index[node.0] = next_index;
+                    low[node.0] = next_index;
+                    next_index += 1;
+                    stack.push(node);
+                    on_stack[node.0] = true;
+                }
+                if let Some(&(_, next)) = self.adjacency[node.0].get(*edge) {
+                    *edge += 1;
+                    if index[next.0] == usize::MAX {
+                        calls.push((next, 0));
+                    } else if on_stack[next.0] {
+                        low[node.0] = low[node.0].min(index[next.0]);
+                    }
+                    continue;
+                }
+                calls.pop();
+                if let Some(&(parent, _)) = calls.last() {
+                    low[parent.0] = low[parent.0].min(low[node.0]);
+                }
+                if low[node.0] == index[node.0] {
+                    let mut component = vec![];
+                    while let Some(member) = stack.pop() {
+                        on_stack[member.0] = false;
+                        component.push(member);
+                        if member == node {
+                            break;
+                        }
+                    }
+                    components.push(component);
+                }
+            }
+        }
+        components
+    }
+}
+
+impl<N, E> Graph<N, E>
+where
+    E: Copy + Ord + Add<Output = E> + Default,
+{
+    /// Finds the shortest paths from `start` with Dijkstra's algorithm in
+    /// O((V + E) log V) time. `E::default()` is the length of an empty path.
+    /// Edge weights must not be negative; use `bellman_ford` if they can be.
+    /// ## Examples
+    /// ```
+    /// # use stdlib_rs::collections::graph::Graph;
+    /// let mut graph = Graph::directed();
+    /// let a = graph.add_node(());
+    /// let b = graph.add_node(());
+    /// let c = graph.add_node(());
+    /// graph.add_edge(a, b, 5);
+    /// graph.add_edge(a, c, 1);
+    /// graph.add_edge(c, b, 2);
+    /// let paths = graph.dijkstra(a);
+    /// assert_eq!(paths.distance(b), Some(3));
+    /// assert_eq!(paths.path(b), Some(vec![a, c, b]));
+    /// ```
+    pub fn dijkstra(&self, start: NodeId) -> ShortestPaths<E> {
+        let mut paths = ShortestPaths {
+            distances: vec![None; self.nodes.len()],
+            previous: vec![None; self.nodes.len()],
+        };
+        let mut heap = BinaryHeap::new();
+        paths.distances[start.0] = Some(E::default());
+        heap.push(Reverse((E::default(), start)));
+        while let Some(Reverse((distance, node))) = heap.pop() {
+            if paths.distances[node.0].is_some_and(|best| distance > best) {
+                continue;
+            }
+            for (_, next, &weight) in self.edges_from(node) {
+                let candidate = distance + weight;
+                if paths.distances[next.0].is_none_or(|best| candidate < best) {
+                    paths.distances[next.0] = Some(candidate);
+                    paths.previous[next.0] = Some(node);
+                    heap.push(Reverse((candidate, next)));
+                }
+            }
+        }
+        paths
+    }
+
+    /// Finds the shortest paths from `start` with the Bellman-Ford algorithm
+    /// in O(V * E) time. Edge weights may be negative, but if a negative
+    /// cycle can be reached from `start`, a node on or after it is returned.
+    pub fn bellman_ford(&self, start: NodeId) -> Result<ShortestPaths<E>, NegativeCycle> {
+        let mut paths = ShortestPaths {
+            distances: vec![None; self.nodes.len()],
+            previous: vec![None; self.nodes.len()],
+        };
+        paths.distances[start.0] = Some(E::default());
+        for round in 0..self.nodes.len() {
+            let mut changed = None;
+            for node in self.node_ids() {
+                let distance = match paths.distances[node.0] {
+                    Some(distance) => distance,
+                    None => continue,
+                };
+                for (_, next, &weight) in self.edges_from(node) {
+                    let candidate = distance + weight;
+                    if paths.distances[next.0].is_none_or(|best| candidate < best) {
+                        paths.distances[next.0] = Some(candidate);
+                        paths.previous[next.0] = Some(node);
+                        changed = Some(next);
+                    }
+                }
+            }
+            match changed {
+                None => return Ok(paths),
+                // Distances still shrinking after V - 1 rounds means a cycle.
+                Some(node) if round + 1 == self.nodes.len() => return Err(NegativeCycle(node)),
+                Some(_) => {}
+            }
+        }
+        Ok(paths)
+    }
+
+    /// Returns the edges of a minimum spanning forest with Kruskal's
+    /// algorithm in O(E log E) time, treating every edge as undirected.
+    /// ## Examples
+    /// ```
+    /// # use stdlib_rs::collections::graph::Graph;
+    /// let mut graph = Graph::undirected();
+    /// let a = graph.add_node(());
+    /// let b = graph.add_node(());
+    /// let c = graph.add_node(());
+    /// let ab = graph.add_edge(a, b, 1);
+    /// let bc = graph.add_edge(b, c, 2);
+    /// graph.add_edge(a, c, 3);
+    /// assert_eq!(graph.minimum_spanning_tree(), vec![ab, bc]);
+    /// ```
+    pub fn minimum_spanning_tree(&self) -> Vec<EdgeId> {
+        let mut order: Vec<EdgeId> = (0..self.edges.len()).map(EdgeId).collect();
+        order.sort_by_key(|id| self.edges[id.0].weight);
+        let mut parent: Vec<usize> = (0..self.nodes.len()).collect();
+        let mut tree = vec![];
+        for id in order {
+            let edge = &self.edges[id.0];
+            let (a, b) = (
+                find(&mut parent, edge.source.0),
+                find(&mut parent, edge.target.0),
+            );
+            if a != b {
+                parent[a] = b;
+                tree.push(id);
+            }
+        }
+        tree
+    }
+}
+
+/// Finds the representative of `node` in a union-find forest, halving
+/// the path as it goes.
+fn find(parent: &mut [usize], mut node: usize) -> usize {
+    while parent[node] != node {
+        parent[node] = parent[parent[node]];
+        node = parent[node];
+    }
+    node
+}
+
+impl<N, E> Index<NodeId> for Graph<N, E> {
+    type Output = N;
+
+    fn index(&self, node: NodeId) -> &N {
+        &self.nodes[node.0]
+    }
+}
+
+impl<N, E> IndexMut<NodeId> for Graph<N, E> {
+    fn index_mut(&mut self, node: NodeId) -> &mut N {
+        &mut self.nodes[node.0]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(directed: bool, nodes: usize, edges: &[(usize, usize, i64)]) -> Graph<usize, i64> {
+        let mut graph = if directed {
+            Graph::directed()
+        } else {
+            Graph::undirected()
+        };
+        for i in 0..nodes {
+            graph.add_node(i);
+        }
+        for &(a, b, w) in edges.iter() {
+            graph.add_edge(NodeId(a), NodeId(b), w);
+        }
+        graph
+    }
+
+    fn ids(nodes: &[usize]) -> Vec<NodeId> {
+        nodes.iter().map(|&n| NodeId(n)).collect()
+    }
+
+    #[test]
+    fn undirected_neighbors() {
+        let g = graph(false, 3, &[(0, 1, 1), (1, 2, 1)]);
+        assert_eq!(g.neighbors(NodeId(1)).collect::<Vec<_>>(), ids(&[0, 2]));
+        assert_eq!(g[NodeId(2)], 2);
+        assert_eq!(g.edge_count(), 2);
+    }
+
+    #[test]
+    fn traversals() {
+        let g = graph(
+            true,
+            6,
+            &[
+                (0, 1, 1),
+                (0, 2, 1),
+                (1, 3, 1),
+                (2, 3, 1),
+                (3, 4, 1),
+                (5, 0, 1),
+            ],
+        );
+        assert_eq!(g.bfs(NodeId(0)), ids(&[0, 1, 2, 3, 4]));
+        assert_eq!(g.dfs(NodeId(0)), ids(&[0, 1, 3, 4, 2]));
+        assert_eq!(g.bfs(NodeId(4)), ids(&[4]));
+    }
+
+    #[test]
+    fn dfs_is_linear() {
+        const N: usize = 100_000;
+        let path: Vec<_> = (1..N).map(|i| (i - 1, i, 1)).collect();
+        let g = graph(false, N, &path);
+        assert_eq!(g.dfs(NodeId(0)), ids(&(0..N).collect::<Vec<_>>()));
+        // A star leaves every leaf on the work stack at once, which a stack
+        // with a slow pop would take quadratic time to empty.
+        let star: Vec<_> = (1..N).map(|i| (0, i, 1)).collect();
+        let g = graph(true, N, &star);
+        assert_eq!(g.dfs(NodeId(0)).len(), N);
+    }
+
+    #[test]
+    fn topological_sort_test() {
+        let g = graph(
+            true,
+            5,
+            &[(3, 1, 1), (1, 0, 1), (4, 0, 1), (3, 4, 1), (2, 4, 1)],
+        );
+        let order = g.topological_sort().unwrap();
+        let position = |n: usize| order.iter().position(|&m| m == NodeId(n)).unwrap();
+        for (_, edge) in g.edges() {
+            assert!(position(edge.source.0) < position(edge.target.0));
+        }
+        let cyclic = graph(true, 3, &[(0, 1, 1), (1, 2, 1), (2, 1, 1)]);
+        assert!(matches!(cyclic.topological_sort(), Err(Cycle(NodeId(n))) if n == 1 || n == 2));
+    }
+
+    #[test]
+    fn shortest_paths_agree() {
+        let edges = [
+            (0, 1, 4),
+            (0, 2, 1),
+            (2, 1, 2),
+            (1, 3, 1),
+            (2, 3, 5),
+            (3, 4, 3),
+        ];
+        let g = graph(true, 6, &edges);
+        let dijkstra = g.dijkstra(NodeId(0));
+        let bellman_ford = g.bellman_ford(NodeId(0)).unwrap();
+        assert_eq!(dijkstra, bellman_ford);
+        assert_eq!(dijkstra.distance(NodeId(4)), Some(7));
+        assert_eq!(dijkstra.path(NodeId(4)), Some(ids(&[0, 2, 1, 3, 4])));
+        assert_eq!(dijkstra.distance(NodeId(5)), None);
+        assert_eq!(dijkstra.path(NodeId(5)), None);
+    }
+
+    #[test]
+    fn bellman_ford_negative_edges() {
+        let g = graph(true, 4, &[(0, 1, 4), (0, 2, 5), (2, 1, -3), (1, 3, 2)]);
+        let paths = g.bellman_ford(NodeId(0)).unwrap();
+        assert_eq!(paths.distance(NodeId(3)), Some(4));
+        let cyclic = graph(true, 3, &[(0, 1, 1), (1, 2, -2), (2, 1, 1)]);
+        assert!(cyclic.bellman_ford(NodeId(0)).is_err());
+    }
+
+    #[test]
+    fn strongly_connected_components_test() {
+        let g = graph(
+            true,
+            7,
+            &[
+                (0, 1, 1),
+                (1, 2, 1),
+                (2, 0, 1),
+                (2, 3, 1),
+                (3, 4, 1),
+                (4, 3, 1),
+                (5, 6, 1),
+            ],
+        );
+        let mut components: Vec<Vec<NodeId>> = g
+            .strongly_connected_components()
+            .into_iter()
+            .map(|mut c| {
+                c.sort();
+                c
+            })
+            .collect();
+        components.sort();
+        assert_eq!(
+            components,
+            vec![ids(&[0, 1, 2]), ids(&[3, 4]), ids(&[5]), ids(&[6])]
+        );
+    }
+
+    #[test]
+    fn minimum_spanning_tree_test() {
+        let g = graph(
+            false,
+            5,
+            &[
+                (0, 1, 2),
+                (0, 3, 6),
+                (1, 2, 3),
+                (1, 3, 8),
+                (1, 4, 5),
+                (2, 4, 7),
+                (3, 4, 9),
+            ],
+        );
+        let tree = g.minimum_spanning_tree();
+        let weight: i64 = tree.iter().map(|&id| g.edge(id).weight).sum();
+        assert_eq!(tree.len(), 4);
+        assert_eq!(weight, 16);
+    }
+}
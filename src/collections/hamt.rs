@@ -0,0 +1,390 @@
+#![deny(missing_docs)]
+
+use alloc::{rc::Rc, vec, vec::Vec};
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::iter::FromIterator;
+use core::slice;
+
+use crate::crypto::fnv::Fnv64Hasher;
+
+/// The number of hash bits consumed by each level of the trie.
+const BITS: u32 = 5;
+const MASK: u64 = (1 << BITS) - 1;
+
+#[derive(Clone)]
+enum Entry<K, V> {
+    Leaf(u64, K, V),
+    Node(Rc<Node<K, V>>),
+}
+
+#[derive(Clone)]
+enum Node<K, V> {
+    // A child for each set bit of the bitmap, in bit order.
+    Branch(u32, Vec<Entry<K, V>>),
+    // Leaves whose whole hashes are equal.
+    Collision(Vec<Entry<K, V>>),
+}
+
+impl<K, V> Node<K, V> {
+    fn entries(&self) -> &[Entry<K, V>] {
+        match self {
+            Node::Branch(_, entries) | Node::Collision(entries) => entries,
+        }
+    }
+}
+
+impl<K: Eq, V> Node<K, V> {
+    fn get(&self, shift: u32, hash: u64, key: &K) -> Option<&V> {
+        let entry = match self {
+            Node::Branch(bitmap, entries) => {
+                let bit = 1 << ((hash >> shift) & MASK);
+                if bitmap & bit == 0 {
+                    return None;
+                }
+                &entries[(bitmap & (bit - 1)).count_ones() as usize]
+            }
+            Node::Collision(entries) => {
+                return entries.iter().find_map(|entry| match entry {
+                    Entry::Leaf(_, k, v) if k == key => Some(v),
+                    _ => None,
+                })
+            }
+        };
+        match entry {
+            Entry::Leaf(h, k, v) if *h == hash && k == key => Some(v),
+            Entry::Leaf(..) => None,
+            Entry::Node(node) => node.get(shift + BITS, hash, key),
+        }
+    }
+}
+
+impl<K: Eq + Clone, V: Clone> Node<K, V> {
+    /// A node at `shift` holding two leaves with different keys.
+    fn pair(shift: u32, a: (u64, K, V), b: (u64, K, V)) -> Node<K, V> {
+        if shift >= u64::BITS {
+            return Node::Collision(vec![Entry::Leaf(a.0, a.1, a.2), Entry::Leaf(b.0, b.1, b.2)]);
+        }
+        let (i, j) = ((a.0 >> shift) & MASK, (b.0 >> shift) & MASK);
+        if i == j {
+            let child = Node::pair(shift + BITS, a, b);
+            return Node::Branch(1 << i, vec![Entry::Node(Rc::new(child))]);
+        }
+        let (first, second) = if i < j { (a, b) } else { (b, a) };
+        Node::Branch(
+            (1 << i) | (1 << j),
+            vec![
+                Entry::Leaf(first.0, first.1, first.2),
+                Entry::Leaf(second.0, second.1, second.2),
+            ],
+        )
+    }
+
+    /// Inserts the entry in place and returns the value it replaced.
+    fn insert(&mut self, shift: u32, hash: u64, key: K, value: V) -> Option<V> {
+        let (bitmap, entries) = match self {
+            Node::Branch(bitmap, entries) => (bitmap, entries),
+            Node::Collision(entries) => {
+                for entry in entries.iter_mut() {
+                    if let Entry::Leaf(_, k, v) = entry {
+                        if *k == key {
+                            return Some(core::mem::replace(v, value));
+                        }
+                    }
+                }
+                entries.push(Entry::Leaf(hash, key, value));
+                return None;
+            }
+        };
+        let bit = 1 << ((hash >> shift) & MASK);
+        let index = (*bitmap & (bit - 1)).count_ones() as usize;
+        if *bitmap & bit == 0 {
+            *bitmap |= bit;
+            entries.insert(index, Entry::Leaf(hash, key, value));
+            return None;
+        }
+        match &mut entries[index] {
+            Entry::Leaf(h, k, v) if *h == hash && *k == key => Some(core::mem::replace(v, value)),
+            Entry::Leaf(h, k, v) => {
+                let old = (*h, k.clone(), v.clone());
+                let node = Node::pair(shift + BITS, old, (hash, key, value));
+                entries[index] = Entry::Node(Rc::new(node));
+                None
+            }
+            Entry::Node(node) => Rc::make_mut(node).insert(shift + BITS, hash, key, value),
+        }
+    }
+
+    /// Removes the entry in place and returns its value.
+    fn remove(&mut self, shift: u32, hash: u64, key: &K) -> Option<V> {
+        let (bitmap, entries) = match self {
+            Node::Branch(bitmap, entries) => (bitmap, entries),
+            Node::Collision(entries) => {
+                let index = entries
+                    .iter()
+                    .position(|entry| matches!(entry, Entry::Leaf(_, k, _) if k == key))?;
+                return match entries.swap_remove(index) {
+                    Entry::Leaf(_, _, v) => Some(v),
+                    Entry::Node(_) => unreachable!("collision nodes only hold leaves"),
+                };
+            }
+        };
+        let bit = 1 << ((hash >> shift) & MASK);
+        if *bitmap & bit == 0 {
+            return None;
+        }
+        let index = (*bitmap & (bit - 1)).count_ones() as usize;
+        match &mut entries[index] {
+            Entry::Leaf(h, k, _) if *h == hash && k == key => {
+                *bitmap &= !bit;
+                match entries.remove(index) {
+                    Entry::Leaf(_, _, v) => Some(v),
+                    Entry::Node(_) => unreachable!(),
+                }
+            }
+            Entry::Leaf(..) => None,
+            Entry::Node(node) => {
+                let value = Rc::make_mut(node).remove(shift + BITS, hash, key);
+                // A node left with a single leaf is replaced by that leaf.
+                if let [Entry::Leaf(h, k, v)] = node.entries() {
+                    entries[index] = Entry::Leaf(*h, k.clone(), v.clone());
+                }
+                value
+            }
+        }
+    }
+}
+
+/// A persistent hash map based on a hash array mapped trie (HAMT), hashing
+/// keys with `Fnv64Hasher`.
+///
+/// `insert` and `remove` return a new version and leave the old one
+/// untouched, copying only the O(log32 n) nodes on the path to the key.
+/// Cloning a PHashMap is O(1).
+/// ## Examples
+/// ```
+/// # use stdlib_rs::collections::hamt::PHashMap;
+/// let v1 = PHashMap::new().insert("a", 1).insert("b", 2);
+/// let v2 = v1.insert("a", 10).remove(&"b");
+/// assert_eq!(v1.get(&"a"), Some(&1));
+/// assert_eq!(v1.get(&"b"), Some(&2));
+/// assert_eq!(v2.get(&"a"), Some(&10));
+/// assert_eq!(v2.get(&"b"), None);
+/// ```
+pub struct PHashMap<K, V> {
+    root: Rc<Node<K, V>>,
+    len: usize,
+}
+
+fn hash<K: Hash>(key: &K) -> u64 {
+    let mut hasher = Fnv64Hasher::default();
+    key.hash(&mut hasher);
+    hasher.finish()
+}
+
+impl<K, V> PHashMap<K, V> {
+    /// Creates a new, empty PHashMap.
+    pub fn new() -> Self {
+        PHashMap {
+            root: Rc::new(Node::Branch(0, vec![])),
+            len: 0,
+        }
+    }
+
+    /// Returns the number of entries in the map.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the map has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Iterates over the entries in an unspecified order.
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            stack: vec![self.root.entries().iter()],
+            remaining: self.len,
+        }
+    }
+}
+
+impl<K: Hash + Eq, V> PHashMap<K, V> {
+    /// Returns a reference to the value for `key`, or `None` if it is not
+    /// in the map.
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.root.get(0, hash(key), key)
+    }
+
+    /// Returns `true` if the map has an entry for `key`.
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> PHashMap<K, V> {
+    /// Returns a new version with `key` mapped to `value`.
+    pub fn insert(&self, key: K, value: V) -> Self {
+        let mut next = self.clone();
+        let hash = hash(&key);
+        if Rc::make_mut(&mut next.root)
+            .insert(0, hash, key, value)
+            .is_none()
+        {
+            next.len += 1;
+        }
+        next
+    }
+
+    /// Returns a new version without an entry for `key`.
+    pub fn remove(&self, key: &K) -> Self {
+        let mut next = self.clone();
+        if self.contains_key(key) {
+            Rc::make_mut(&mut next.root).remove(0, hash(key), key);
+            next.len -= 1;
+        }
+        next
+    }
+}
+
+impl<K, V> Clone for PHashMap<K, V> {
+    fn clone(&self) -> Self {
+        PHashMap {
+            root: self.root.clone(),
+            len: self.len,
+        }
+    }
+}
+
+impl<K, V> Default for PHashMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Hash + Eq, V: PartialEq> PartialEq for PHashMap<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().all(|(k, v)| other.get(k) == Some(v))
+    }
+}
+
+impl<K: Hash + Eq, V: Eq> Eq for PHashMap<K, V> {}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for PHashMap<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> FromIterator<(K, V)> for PHashMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = PHashMap::new();
+        for (key, value) in iter {
+            // `map` is the only owner of its nodes, so they are updated in place.
+            let hash = hash(&key);
+            if Rc::make_mut(&mut map.root)
+                .insert(0, hash, key, value)
+                .is_none()
+            {
+                map.len += 1;
+            }
+        }
+        map
+    }
+}
+
+/// An iterator over the entries of a `PHashMap`.
+pub struct Iter<'a, K, V> {
+    stack: Vec<slice::Iter<'a, Entry<K, V>>>,
+    remaining: usize,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<(&'a K, &'a V)> {
+        loop {
+            match self.stack.last_mut()?.next() {
+                Some(Entry::Leaf(_, key, value)) => {
+                    self.remaining -= 1;
+                    return Some((key, value));
+                }
+                Some(Entry::Node(node)) => self.stack.push(node.entries().iter()),
+                None => {
+                    self.stack.pop();
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    // Every value hashes the same, so all entries collide.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    struct Colliding(u32);
+
+    impl Hash for Colliding {
+        fn hash<H: Hasher>(&self, _: &mut H) {}
+    }
+
+    #[test]
+    fn versions_are_independent() {
+        let mut versions = vec![PHashMap::new()];
+        for i in 0..1000u32 {
+            let next = versions.last().unwrap().insert(i, i * 2);
+            versions.push(next);
+        }
+        for (len, version) in versions.iter().enumerate() {
+            assert_eq!(version.len(), len);
+            assert_eq!(version.get(&(len as u32)), None);
+            if len > 0 {
+                assert_eq!(version.get(&(len as u32 - 1)), Some(&(2 * len as u32 - 2)));
+            }
+        }
+    }
+
+    #[test]
+    fn matches_hash_map() {
+        let mut expected = HashMap::new();
+        let mut map = PHashMap::new();
+        for i in 0..5000u64 {
+            let key = i.wrapping_mul(2654435761) % 1500;
+            if i % 3 == 0 {
+                map = map.remove(&key);
+                expected.remove(&key);
+            } else {
+                map = map.insert(key, i);
+                expected.insert(key, i);
+            }
+        }
+        assert_eq!(map.len(), expected.len());
+        assert_eq!(map.iter().count(), expected.len());
+        for (key, value) in expected.iter() {
+            assert_eq!(map.get(key), Some(value));
+        }
+        let rebuilt: PHashMap<u64, u64> = expected.into_iter().collect();
+        assert_eq!(rebuilt, map);
+    }
+
+    #[test]
+    fn collisions() {
+        let map: PHashMap<Colliding, u32> = (0..10).map(|i| (Colliding(i), i)).collect();
+        assert_eq!(map.len(), 10);
+        let smaller = map.remove(&Colliding(3)).insert(Colliding(4), 40);
+        assert_eq!(smaller.get(&Colliding(3)), None);
+        assert_eq!(smaller.get(&Colliding(4)), Some(&40));
+        assert_eq!(map.get(&Colliding(4)), Some(&4));
+        let one = (0..9).fold(smaller, |m, i| m.remove(&Colliding(i)));
+        assert_eq!(one.len(), 1);
+        assert_eq!(one.iter().next(), Some((&Colliding(9), &9)));
+    }
+}
@@ -0,0 +1,571 @@
+#![deny(missing_docs)]
+
+use alloc::{boxed::Box, vec, vec::Vec};
+use core::cmp::{max, Ordering};
+use core::iter::FromIterator;
+use core::mem;
+use core::ops::{Bound, Range, RangeBounds};
+
+type Link<K, V> = Option<Box<Node<K, V>>>;
+
+struct Node<K, V> {
+    range: Range<K>,
+    value: V,
+    // The largest `range.end` in this subtree.
+    max_end: K,
+    height: usize,
+    left: Link<K, V>,
+    right: Link<K, V>,
+}
+
+/// A map from half-open intervals to values, kept as a balanced binary
+/// search tree where every node also knows the largest end point below it.
+/// Finding the `k` intervals that overlap a query takes O(log n + k) time.
+pub struct IntervalTree<K, V> {
+    root: Link<K, V>,
+    len: usize,
+}
+
+impl<K, V> Default for IntervalTree<K, V> {
+    fn default() -> Self {
+        IntervalTree { root: None, len: 0 }
+    }
+}
+
+impl<K, V> IntervalTree<K, V> {
+    /// Creates a new IntervalTree.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of intervals in the tree.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the tree has no intervals.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Removes all the intervals from the tree.
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+impl<K, V> IntervalTree<K, V>
+where
+    K: Ord + Clone,
+{
+    /// Inserts `value` for the interval `range` in O(log n) time.
+    /// If the tree already had a value for exactly this interval, it is
+    /// replaced and the old value is returned.
+    /// ## Examples
+    /// ```
+    /// # use stdlib_rs::collections::interval_tree::IntervalTree;
+    /// let mut tree = IntervalTree::new();
+    /// assert_eq!(tree.insert(1..5, "a"), None);
+    /// assert_eq!(tree.insert(1..5, "b"), Some("a"));
+    /// assert_eq!(tree.len(), 1);
+    /// ```
+    /// ## Panics
+    /// Panics if the interval is empty or decreasing, that is if
+    /// `range.start >= range.end`. An empty interval would overlap nothing.
+    pub fn insert(&mut self, range: Range<K>, value: V) -> Option<V> {
+        assert!(range.start < range.end, "interval is empty or decreasing");
+        let (root, old) = insert(self.root.take(), range, value);
+        self.root = Some(root);
+        if old.is_none() {
+            self.len += 1;
+        }
+        old
+    }
+
+    /// Removes the interval `range` from the tree in O(log n) time,
+    /// returning its value if it was present.
+    pub fn remove(&mut self, range: &Range<K>) -> Option<V> {
+        let (root, removed) = remove(self.root.take(), range);
+        self.root = root;
+        if removed.is_some() {
+            self.len -= 1;
+        }
+        removed
+    }
+
+    /// Returns the value for exactly the interval `range`.
+    pub fn get(&self, range: &Range<K>) -> Option<&V> {
+        let mut link = &self.root;
+        while let Some(node) = link {
+            link = match compare(range, &node.range) {
+                Ordering::Less => &node.left,
+                Ordering::Greater => &node.right,
+                Ordering::Equal => return Some(&node.value),
+            };
+        }
+        None
+    }
+
+    /// Iterates over every interval that overlaps `range`, ordered by start.
+    /// ## Examples
+    /// ```
+    /// # use stdlib_rs::collections::interval_tree::IntervalTree;
+    /// let mut tree = IntervalTree::new();
+    /// tree.insert(1..3, 'a');
+    /// tree.insert(2..6, 'b');
+    /// tree.insert(7..9, 'c');
+    /// let found: Vec<_> = tree.overlapping(2..8).map(|(_, v)| *v).collect();
+    /// assert_eq!(found, vec!['a', 'b', 'c']);
+    /// assert_eq!(tree.overlapping(3..7).count(), 1);
+    /// ```
+    pub fn overlapping<R: RangeBounds<K>>(&self, range: R) -> Overlapping<'_, K, V> {
+        let mut iter = Overlapping {
+            stack: vec![],
+            start: range.start_bound().cloned(),
+            end: range.end_bound().cloned(),
+        };
+        iter.push_left(self.root.as_deref());
+        iter
+    }
+
+    /// Iterates over every interval that contains `point`, ordered by start.
+    /// ## Examples
+    /// ```
+    /// # use stdlib_rs::collections::interval_tree::IntervalTree;
+    /// let mut tree = IntervalTree::new();
+    /// tree.insert(1..3, 'a');
+    /// tree.insert(2..6, 'b');
+    /// let found: Vec<_> = tree.stabbing(&2).map(|(_, v)| *v).collect();
+    /// assert_eq!(found, vec!['a', 'b']);
+    /// assert_eq!(tree.stabbing(&3).count(), 1);
+    /// ```
+    pub fn stabbing(&self, point: &K) -> Overlapping<'_, K, V> {
+        self.overlapping(point.clone()..=point.clone())
+    }
+
+    /// Iterates over every interval in the tree, ordered by start.
+    pub fn iter(&self) -> Overlapping<'_, K, V> {
+        self.overlapping(..)
+    }
+
+    /// Replaces every run of overlapping or touching intervals with a single
+    /// interval covering the run, folding their values together with `merge`
+    /// in order of start. Runs in O(n) time.
+    /// ## Examples
+    /// ```
+    /// # use stdlib_rs::collections::interval_tree::IntervalTree;
+    /// let mut tree = IntervalTree::new();
+    /// tree.insert(1..3, 1);
+    /// tree.insert(3..5, 2);
+    /// tree.insert(4..6, 3);
+    /// tree.insert(8..9, 4);
+    /// tree.merge_adjacent(|a, b| a + b);
+    /// let merged: Vec<_> = tree.iter().map(|(r, v)| (r.clone(), *v)).collect();
+    /// assert_eq!(merged, vec![(1..6, 6), (8..9, 4)]);
+    /// ```
+    pub fn merge_adjacent<F>(&mut self, mut merge: F)
+    where
+        F: FnMut(V, V) -> V,
+    {
+        let mut items = vec![];
+        into_sorted(self.root.take(), &mut items);
+        let mut merged = Vec::with_capacity(items.len());
+        let mut current: Option<(Range<K>, V)> = None;
+        for (range, value) in items {
+            current = match current {
+                Some((mut run, acc)) if range.start <= run.end => {
+                    if range.end > run.end {
+                        run.end = range.end;
+                    }
+                    Some((run, merge(acc, value)))
+                }
+                Some(run) => {
+                    merged.push(run);
+                    Some((range, value))
+                }
+                None => Some((range, value)),
+            };
+        }
+        merged.extend(current);
+        self.len = merged.len();
+        self.root = build(&mut merged.into_iter(), self.len);
+    }
+}
+
+impl<K, V> FromIterator<(Range<K>, V)> for IntervalTree<K, V>
+where
+    K: Ord + Clone,
+{
+    fn from_iter<I: IntoIterator<Item = (Range<K>, V)>>(iter: I) -> Self {
+        let mut tree = IntervalTree::new();
+        for (range, value) in iter {
+            tree.insert(range, value);
+        }
+        tree
+    }
+}
+
+/// An iterator over the intervals of an `IntervalTree` that overlap a range.
+pub struct Overlapping<'a, K, V> {
+    stack: Vec<&'a Node<K, V>>,
+    start: Bound<K>,
+    end: Bound<K>,
+}
+
+impl<'a, K, V> Overlapping<'a, K, V>
+where
+    K: Ord,
+{
+    /// Whether something ending at `end` can reach past the query's start.
+    fn reaches_start(&self, end: &K) -> bool {
+        match &self.start {
+            Bound::Included(start) | Bound::Excluded(start) => end > start,
+            Bound::Unbounded => true,
+        }
+    }
+
+    /// Whether something starting at `start` begins before the query's end.
+    fn before_end(&self, start: &K) -> bool {
+        match &self.end {
+            Bound::Included(end) => start <= end,
+            Bound::Excluded(end) => start < end,
+            Bound::Unbounded => true,
+        }
+    }
+
+    fn push_left(&mut self, mut link: Option<&'a Node<K, V>>) {
+        while let Some(node) = link {
+            if !self.reaches_start(&node.max_end) {
+                return;
+            }
+            self.stack.push(node);
+            link = node.left.as_deref();
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Overlapping<'a, K, V>
+where
+    K: Ord,
+{
+    type Item = (&'a Range<K>, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(node) = self.stack.pop() {
+            // Everything left on the stack starts at or after this node.
+            if !self.before_end(&node.range.start) {
+                self.stack.clear();
+                return None;
+            }
+            self.push_left(node.right.as_deref());
+            if self.reaches_start(&node.range.end) {
+                return Some((&node.range, &node.value));
+            }
+        }
+        None
+    }
+}
+
+fn compare<K: Ord>(a: &Range<K>, b: &Range<K>) -> Ordering {
+    a.start.cmp(&b.start).then_with(|| a.end.cmp(&b.end))
+}
+
+fn height<K, V>(link: &Link<K, V>) -> usize {
+    link.as_ref().map_or(0, |node| node.height)
+}
+
+fn update<K: Ord + Clone, V>(node: &mut Node<K, V>) {
+    node.height = 1 + max(height(&node.left), height(&node.right));
+    let mut max_end = &node.range.end;
+    for child in [&node.left, &node.right].iter().copied().flatten() {
+        if child.max_end > *max_end {
+            max_end = &child.max_end;
+        }
+    }
+    node.max_end = max_end.clone();
+}
+
+fn rotate_right<K: Ord + Clone, V>(mut node: Box<Node<K, V>>) -> Box<Node<K, V>> {
+    let mut left = node.left.take().expect("rotate_right needs a left child");
+    node.left = left.right.take();
+    update(&mut node);
+    left.right = Some(node);
+    update(&mut left);
+    left
+}
+
+fn rotate_left<K: Ord + Clone, V>(mut node: Box<Node<K, V>>) -> Box<Node<K, V>> {
+    let mut right = node.right.take().expect("rotate_left needs a right child");
+    node.right = right.left.take();
+    update(&mut node);
+    right.left = Some(node);
+    update(&mut right);
+    right
+}
+
+fn rebalance<K: Ord + Clone, V>(mut node: Box<Node<K, V>>) -> Box<Node<K, V>> {
+    update(&mut node);
+    let (left, right) = (height(&node.left), height(&node.right));
+    if left > right + 1 {
+        let child = node.left.as_ref().unwrap();
+        if height(&child.right) > height(&child.left) {
+            node.left = Some(rotate_left(node.left.take().unwrap()));
+        }
+        rotate_right(node)
+    } else if right > left + 1 {
+        let child = node.right.as_ref().unwrap();
+        if height(&child.left) > height(&child.right) {
+            node.right = Some(rotate_right(node.right.take().unwrap()));
+        }
+        rotate_left(node)
+    } else {
+        node
+    }
+}
+
+fn insert<K: Ord + Clone, V>(
+    link: Link<K, V>,
+    range: Range<K>,
+    value: V,
+) -> (Box<Node<K, V>>, Option<V>) {
+    let mut node = match link {
+        None => {
+            let max_end = range.end.clone();
+            let node = Node {
+                range,
+                value,
+                max_end,
+                height: 1,
+                left: None,
+                right: None,
+            };
+            return (Box::new(node), None);
+        }
+        Some(node) => node,
+    };
+    let old = match compare(&range, &node.range) {
+        Ordering::Less => {
+            let (left, old) = insert(node.left.take(), range, value);
+            node.left = Some(left);
+            old
+        }
+        Ordering::Greater => {
+            let (right, old) = insert(node.right.take(), range, value);
+            node.right = Some(right);
+            old
+        }
+        Ordering::Equal => Some(mem::replace(&mut node.value, value)),
+    };
+    (rebalance(node), old)
+}
+
+fn remove<K: Ord + Clone, V>(link: Link<K, V>, range: &Range<K>) -> (Link<K, V>, Option<V>) {
+    let mut node = match link {
+        None => return (None, None),
+        Some(node) => node,
+    };
+    match compare(range, &node.range) {
+        Ordering::Less => {
+            let (left, removed) = remove(node.left.take(), range);
+            node.left = left;
+            (Some(rebalance(node)), removed)
+        }
+        Ordering::Greater => {
+            let (right, removed) = remove(node.right.take(), range);
+            node.right = right;
+            (Some(rebalance(node)), removed)
+        }
+        Ordering::Equal => {
+            let Node {
+                value, left, right, ..
+            } = *node;
+            let root = match (left, right) {
+                (None, right) => right,
+                (left, None) => left,
+                (left, Some(right)) => {
+                    let (right, mut successor) = remove_min(right);
+                    successor.left = left;
+                    successor.right = right;
+                    Some(rebalance(successor))
+                }
+            };
+            (root, Some(value))
+        }
+    }
+}
+
+fn remove_min<K: Ord + Clone, V>(mut node: Box<Node<K, V>>) -> (Link<K, V>, Box<Node<K, V>>) {
+    match node.left.take() {
+        None => (node.right.take(), node),
+        Some(left) => {
+            let (left, min) = remove_min(left);
+            node.left = left;
+            (Some(rebalance(node)), min)
+        }
+    }
+}
+
+fn into_sorted<K, V>(link: Link<K, V>, out: &mut Vec<(Range<K>, V)>) {
+    if let Some(node) = link {
+        let Node {
+            range,
+            value,
+            left,
+            right,
+            ..
+        } = *node;
+        into_sorted(left, out);
+        out.push((range, value));
+        into_sorted(right, out);
+    }
+}
+
+/// Builds a balanced tree from the next `len` sorted items of `items`.
+fn build<K, V, I>(items: &mut I, len: usize) -> Link<K, V>
+where
+    K: Ord + Clone,
+    I: Iterator<Item = (Range<K>, V)>,
+{
+    if len == 0 {
+        return None;
+    }
+    let left = build(items, len / 2);
+    let (range, value) = items.next()?;
+    let right = build(items, len - len / 2 - 1);
+    let mut node = Box::new(Node {
+        max_end: range.end.clone(),
+        range,
+        value,
+        height: 1,
+        left,
+        right,
+    });
+    update(&mut node);
+    Some(node)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Checks the AVL and max end invariants, returning the height.
+    fn check<K: Ord + Clone, V>(link: &Link<K, V>) -> usize {
+        match link {
+            None => 0,
+            Some(node) => {
+                let (left, right) = (check(&node.left), check(&node.right));
+                assert!(left.max(right) - left.min(right) <= 1);
+                assert_eq!(node.height, 1 + left.max(right));
+                let ends = [&node.left, &node.right];
+                let max_end = ends
+                    .iter()
+                    .flat_map(|child| child.as_ref().map(|c| &c.max_end))
+                    .fold(&node.range.end, |a, b| a.max(b));
+                assert!(node.max_end == *max_end);
+                node.height
+            }
+        }
+    }
+
+    fn naive(intervals: &[(i32, i32)], start: i32, end: i32) -> Vec<Range<i32>> {
+        let mut found: Vec<_> = intervals
+            .iter()
+            .filter(|&&(s, e)| s < end && start < e)
+            .map(|&(s, e)| s..e)
+            .collect();
+        found.sort_by(compare);
+        found
+    }
+
+    #[test]
+    fn empty_test() {
+        let tree: IntervalTree<i32, ()> = IntervalTree::new();
+        assert!(tree.is_empty());
+        assert_eq!(tree.overlapping(..).count(), 0);
+    }
+
+    #[test]
+    fn overlapping_matches_naive() {
+        let intervals: Vec<(i32, i32)> = (0..60)
+            .map(|i| {
+                let start = (i * 37) % 97;
+                (start, start + 1 + (i * 13) % 17)
+            })
+            .collect();
+        let tree: IntervalTree<i32, usize> = intervals
+            .iter()
+            .enumerate()
+            .map(|(i, &(s, e))| (s..e, i))
+            .collect();
+        check(&tree.root);
+        assert_eq!(tree.iter().count(), tree.len());
+        for start in (0..110).step_by(7) {
+            for end in start..start + 20 {
+                let found: Vec<_> = tree
+                    .overlapping(start..end)
+                    .map(|(r, _)| r.clone())
+                    .collect();
+                assert_eq!(found, naive(&intervals, start, end));
+            }
+        }
+    }
+
+    #[test]
+    fn stabbing_test() {
+        let tree: IntervalTree<i32, char> = vec![(0..10, 'a'), (5..6, 'b'), (6..8, 'c')]
+            .into_iter()
+            .collect();
+        let found: Vec<_> = tree.stabbing(&5).map(|(_, v)| *v).collect();
+        assert_eq!(found, vec!['a', 'b']);
+        let found: Vec<_> = tree.stabbing(&6).map(|(_, v)| *v).collect();
+        assert_eq!(found, vec!['a', 'c']);
+        assert_eq!(tree.stabbing(&10).count(), 0);
+    }
+
+    #[test]
+    fn remove_test() {
+        let mut tree = IntervalTree::new();
+        for i in 0..50 {
+            tree.insert(i..i + 3, i);
+        }
+        for i in (0..50).step_by(2) {
+            assert_eq!(tree.remove(&(i..i + 3)), Some(i));
+            check(&tree.root);
+            assert_eq!(tree.iter().count(), tree.len());
+        }
+        assert_eq!(tree.remove(&(0..3)), None);
+        assert_eq!(tree.len(), 25);
+        assert_eq!(tree.get(&(1..4)), Some(&1));
+        let found: Vec<_> = tree.stabbing(&10).map(|(_, v)| *v).collect();
+        assert_eq!(found, vec![9]);
+    }
+
+    #[test]
+    fn merge_adjacent_test() {
+        let mut tree = IntervalTree::new();
+        tree.insert(10..12, vec![3]);
+        tree.insert(0..2, vec![0]);
+        tree.insert(2..4, vec![1]);
+        tree.insert(1..3, vec![2]);
+        tree.merge_adjacent(|mut a, b| {
+            a.extend(b);
+            a
+        });
+        assert_eq!(tree.len(), 2);
+        check(&tree.root);
+        assert_eq!(tree.get(&(0..4)), Some(&vec![0, 2, 1]));
+        assert_eq!(tree.get(&(10..12)), Some(&vec![3]));
+    }
+
+    #[test]
+    #[should_panic(expected = "interval is empty or decreasing")]
+    fn insert_decreasing() {
+        IntervalTree::new().insert(Range { start: 5, end: 1 }, ());
+    }
+
+    #[test]
+    #[should_panic(expected = "interval is empty or decreasing")]
+    fn insert_empty() {
+        IntervalTree::new().insert(5..5, ());
+    }
+}
@@ -1,13 +1,21 @@
 #![deny(missing_docs)]
 
-use std::cmp::min;
-use std::fmt::Debug;
-use std::mem;
-use std::ops::{Deref, DerefMut, Index};
-
-impl<T> MinStack<T>
+use alloc::{vec, vec::Vec};
+use core::cmp::min;
+use core::fmt::{self, Debug};
+use core::marker::PhantomData;
+use core::mem;
+use core::ops::{Deref, DerefMut, Index};
+
+use super::alloc_vec::AllocVec;
+use super::allocator::Allocator;
+use super::small_vec::SmallVec;
+use super::storage::Storage;
+
+impl<T, S> MinStack<T, S>
 where
     T: Clone + Ord + Default,
+    S: Storage<(T, T)> + Default,
 {
     /// Moves all the elements of `other` into `Self`, leaving other empty.
     /// ## Panics
@@ -29,9 +37,10 @@ where
     }
 }
 
-impl<T> MinStack<T>
+impl<T, S> MinStack<T, S>
 where
     T: Clone + Ord,
+    S: Storage<(T, T)>,
 {
     /// Finds the minimum item of the stack in O(1) time.
     /// If the stack is empty, returns `None`.
@@ -91,8 +100,11 @@ where
     /// let stack: MinStack<i32> = MinStack::from(v);
     /// assert_eq!(stack, min_stack![1, 2, 3]);
     /// ```
-    pub fn from(vec: Vec<T>) -> Self {
-        let mut stack = MinStack::new();
+    pub fn from(vec: Vec<T>) -> Self
+    where
+        S: Default,
+    {
+        let mut stack = MinStack::default();
         for item in vec {
             stack.push(item);
         }
@@ -102,24 +114,60 @@ where
 
 /// A Stack data type that supports accessing the minimum item
 /// in the stack in O(1) time.
-#[derive(Default, Debug, Clone, PartialEq, Eq, PartialOrd)]
-pub struct MinStack<T: Ord>(Vec<(T, T)>);
+/// Items are kept in a `Vec` unless another `Storage` is chosen with `S`,
+/// such as an `AllocVec` from `new_in`.
+#[derive(Clone, PartialEq, Eq, PartialOrd)]
+pub struct MinStack<T: Ord, S = Vec<(T, T)>>(S, PhantomData<T>);
+
+/// A MinStack that keeps up to `N` items inline before allocating.
+/// ## Examples
+/// ```
+/// # use stdlib_rs::collections::min_stack::SmallMinStack;
+/// let mut stack: SmallMinStack<i32, 4> = SmallMinStack::default();
+/// stack.push(3);
+/// stack.push(1);
+/// assert_eq!(stack.min(), Some(1));
+/// assert_eq!(stack.capacity(), 4);
+/// ```
+pub type SmallMinStack<T, const N: usize> = MinStack<T, SmallVec<[(T, T); N]>>;
+
+impl<T, S> Default for MinStack<T, S>
+where
+    T: Ord,
+    S: Storage<(T, T)> + Default,
+{
+    fn default() -> Self {
+        MinStack(S::default(), PhantomData)
+    }
+}
+
+impl<T, S> Debug for MinStack<T, S>
+where
+    T: Ord + Debug,
+    S: Storage<(T, T)>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("MinStack").field(&self.0.deref()).finish()
+    }
+}
 
-impl<T> IntoIterator for MinStack<T>
+impl<T, S> IntoIterator for MinStack<T, S>
 where
     T: Ord,
+    S: Storage<(T, T)>,
 {
     type Item = (T, T);
-    type IntoIter = std::vec::IntoIter<Self::Item>;
+    type IntoIter = S::IntoIter;
 
     fn into_iter(self) -> Self::IntoIter {
         self.0.into_iter()
     }
 }
 
-impl<T> Deref for MinStack<T>
+impl<T, S> Deref for MinStack<T, S>
 where
     T: Ord,
+    S: Storage<(T, T)>,
 {
     type Target = [(T, T)];
 
@@ -128,18 +176,20 @@ where
     }
 }
 
-impl<T> DerefMut for MinStack<T>
+impl<T, S> DerefMut for MinStack<T, S>
 where
     T: Ord,
+    S: Storage<(T, T)>,
 {
     fn deref_mut(&mut self) -> &mut [(T, T)] {
         self.0.deref_mut()
     }
 }
 
-impl<T> Index<usize> for MinStack<T>
+impl<T, S> Index<usize> for MinStack<T, S>
 where
     T: Ord,
+    S: Storage<(T, T)>,
 {
     type Output = (T, T);
 
@@ -154,7 +204,60 @@ where
 {
     /// Creates a new MinStack.
     pub fn new() -> Self {
-        MinStack(vec![])
+        MinStack(vec![], PhantomData)
+    }
+
+    /// Creates a new MinStack with the given capacity.
+    /// ## Examples
+    /// ```
+    /// # use stdlib_rs::min_stack;
+    /// # use stdlib_rs::collections::min_stack::*;
+    /// let stack: MinStack<i32> = MinStack::with_capacity(10);
+    /// assert!(stack.capacity() >= 10);
+    /// ```
+    pub fn with_capacity(capacity: usize) -> MinStack<T> {
+        MinStack(Vec::with_capacity(capacity), PhantomData)
+    }
+}
+
+impl<T, A> MinStack<T, AllocVec<(T, T), A>>
+where
+    T: Ord,
+    A: Allocator,
+{
+    /// Creates a new MinStack that allocates from `alloc`.
+    /// ## Examples
+    /// ```
+    /// # use stdlib_rs::collections::arena::Bump;
+    /// # use stdlib_rs::collections::min_stack::MinStack;
+    /// let bump = Bump::new();
+    /// let mut stack = MinStack::new_in(&bump);
+    /// stack.push(2);
+    /// stack.push(1);
+    /// assert_eq!(stack.pop(), Some(1));
+    /// assert_eq!(stack.min(), Some(2));
+    /// ```
+    pub fn new_in(alloc: A) -> Self {
+        MinStack(AllocVec::new_in(alloc), PhantomData)
+    }
+
+    /// Creates a new MinStack with room for `capacity` items, allocated
+    /// from `alloc`.
+    pub fn with_capacity_in(capacity: usize, alloc: A) -> Self {
+        MinStack(AllocVec::with_capacity_in(capacity, alloc), PhantomData)
+    }
+}
+
+impl<T, S> MinStack<T, S>
+where
+    T: Ord,
+    S: Storage<(T, T)>,
+{
+    /// Creates a new MinStack that keeps its items in `storage`, which is
+    /// cleared first.
+    pub fn with_storage(mut storage: S) -> Self {
+        storage.clear();
+        MinStack(storage, PhantomData)
     }
 
     /// Extracts a slice containing the Min Stack.
@@ -186,20 +289,6 @@ where
         self.0.clear()
     }
 
-    /// Creates a new MinStack with the given capacity.
-    /// ## Examples
-    /// ```
-    /// # use stdlib_rs::min_stack;
-    /// # use stdlib_rs::collections::min_stack::*;
-    /// let stack: MinStack<i32> = MinStack::with_capacity(10);
-    /// assert!(stack.capacity() >= 10);
-    /// ```
-    pub fn with_capacity(capacity: usize) -> MinStack<T> {
-        let mut stack = MinStack::new();
-        stack.0 = Vec::with_capacity(capacity);
-        stack
-    }
-
     /// Reserves capacity for at least `additional` more elements to be inserted
     /// in the given `MinStack<T>`. The collection may reserve more space to
     /// avoid frequent reallocations. After calling reserve, capacity will be
@@ -299,7 +388,8 @@ macro_rules! min_stack [
 
 #[cfg(test)]
 mod tests {
-    use super::MinStack;
+    use super::super::arena::Bump;
+    use super::{MinStack, SmallMinStack};
 
     #[test]
     fn min_test_1() {
@@ -431,4 +521,33 @@ mod tests {
         let right = min_stack![1, 2];
         assert_ne!(left, right);
     }
+
+    #[test]
+    fn small_stack_stays_inline() {
+        let mut stack: SmallMinStack<i32, 3> = SmallMinStack::default();
+        for i in [5, 2, 7].iter() {
+            stack.push(*i);
+        }
+        assert!(!stack.0.spilled());
+        assert_eq!(stack.min(), Some(2));
+        stack.push(1);
+        assert!(stack.0.spilled());
+        assert_eq!(stack.min(), Some(1));
+        assert_eq!(stack.pop(), Some(1));
+        assert_eq!(stack.min(), Some(2));
+    }
+
+    #[test]
+    fn allocates_from_bump() {
+        let bump = Bump::new();
+        let mut stack = MinStack::with_capacity_in(2, &bump);
+        let used = bump.allocated_bytes();
+        for i in (0..100).rev() {
+            stack.push(i);
+        }
+        assert!(bump.allocated_bytes() > used);
+        assert_eq!(stack.min(), Some(0));
+        assert_eq!(stack.pop(), Some(0));
+        assert_eq!(stack.min(), Some(1));
+    }
 }
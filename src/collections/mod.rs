@@ -1,10 +1,85 @@
+use core::ops::{Bound, RangeBounds};
+
+/// A vector that allocates from a chosen `Allocator`.
+pub mod alloc_vec;
+/// The `Allocator` trait that collections allocate their memory through.
+/// Implemented by the global allocator and by `Bump` arenas.
+pub mod allocator;
+/// Arena allocators for node-based collections.
+/// Hands out references that are all freed when the arena is dropped.
+pub mod arena;
+/// Fixed-capacity stacks and queues backed by arrays.
+/// Never allocate, so they work with only `core`.
+pub mod array;
+/// Bit vectors and bit sets packed into words.
+/// Also provides a succinct rank/select index over a bit vector.
+pub mod bitset;
+/// A Fenwick tree (binary indexed tree) for prefix sums.
+/// Supports point updates and prefix sums in O(log n) time.
+pub mod fenwick;
 pub mod first;
+/// A gap buffer for cursor-local edits.
+/// Inserts and removes items at the cursor in O(1) time.
+pub mod gap_buffer;
+/// A graph stored as adjacency lists.
+/// Provides traversals, topological sorting, shortest paths, strongly
+/// connected components and minimum spanning trees.
+pub mod graph;
+/// A persistent hash map based on a hash array mapped trie.
+/// Inserts and removes in O(log32 n) time, returning new versions that share structure.
+pub mod hamt;
+/// An interval tree for overlap queries.
+/// Finds the intervals overlapping a range or a point in O(log n + k) time.
+pub mod interval_tree;
 /// A Minimum Stack data structure.
 /// A minimum stack has O(1) appends and O(1) pops.
 /// As well, the Minimum stack returns the minimum element in the stack in O(1) time.
 pub mod min_stack;
+/// A persistent vector with structural sharing.
+/// Pushes, pops, reads and writes in O(log32 n) time, returning new versions.
+pub mod pvec;
 /// A queue implemented with two stacks.
 pub mod queue_with_stack;
+/// A rope for editing large strings.
+/// Inserts, removes and slices text by char or line in O(log n) time.
+pub mod rope;
 pub mod second;
+/// A segment tree with lazy propagation.
+/// Supports range queries and range updates in O(log n) time.
+pub mod segment_tree;
+/// A slot map with generational keys.
+/// Inserts, removes and looks up values in O(1) time and detects stale keys.
+pub mod slot_map;
+/// A vector that stores a few items inline before allocating.
+pub mod small_vec;
+/// A sparse table for static range queries.
+/// Answers idempotent range queries like min, max and gcd in O(1) time.
+pub mod sparse_table;
 /// A stack implemented with two queues.
 pub mod stack_with_queue;
+/// Backing storage for the stacks and queues.
+pub mod storage;
+
+/// Converts `range` into a half-open `(start, end)` pair over `0..len`.
+/// ## Panics
+/// Panics if the range is decreasing or ends past `len`, like slice indexing.
+pub(crate) fn to_bounds<R: RangeBounds<usize>>(range: R, len: usize) -> (usize, usize) {
+    let start = match range.start_bound() {
+        Bound::Included(&start) => start,
+        Bound::Excluded(&start) => start + 1,
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&end) => end + 1,
+        Bound::Excluded(&end) => end,
+        Bound::Unbounded => len,
+    };
+    assert!(start <= end, "range start {} is after end {}", start, end);
+    assert!(
+        end <= len,
+        "range end {} is out of bounds for length {}",
+        end,
+        len
+    );
+    (start, end)
+}
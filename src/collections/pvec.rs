@@ -0,0 +1,452 @@
+#![deny(missing_docs)]
+
+use alloc::{rc::Rc, vec, vec::Vec};
+use core::fmt;
+use core::iter::FromIterator;
+use core::mem;
+use core::ops::Index;
+
+/// The number of index bits consumed by each level of the tree.
+const BITS: usize = 5;
+/// The number of children of a branch and items of a leaf.
+const WIDTH: usize = 1 << BITS;
+const MASK: usize = WIDTH - 1;
+
+#[derive(Clone)]
+enum Node<T> {
+    Branch(Vec<Rc<Node<T>>>),
+    Leaf(Vec<T>),
+}
+
+impl<T> Node<T> {
+    fn children(&self) -> &[Rc<Node<T>>] {
+        match self {
+            Node::Branch(children) => children,
+            Node::Leaf(_) => unreachable!("leaves have no children"),
+        }
+    }
+
+    fn items(&self) -> &[T] {
+        match self {
+            Node::Leaf(items) => items,
+            Node::Branch(_) => unreachable!("branches have no items"),
+        }
+    }
+
+    fn children_mut(&mut self) -> &mut Vec<Rc<Node<T>>> {
+        match self {
+            Node::Branch(children) => children,
+            Node::Leaf(_) => unreachable!("leaves have no children"),
+        }
+    }
+
+    fn items_mut(&mut self) -> &mut Vec<T> {
+        match self {
+            Node::Leaf(items) => items,
+            Node::Branch(_) => unreachable!("branches have no items"),
+        }
+    }
+}
+
+/// A persistent vector: every update returns a new version and leaves the
+/// old one untouched, sharing all but O(log32 n) nodes with it.
+///
+/// Items live in a 32-way trie, with the last (up to) 32 items kept in a
+/// separate tail so that pushes and pops at the back are usually O(1).
+/// Cloning a PVector is O(1).
+/// ## Examples
+/// ```
+/// # use stdlib_rs::collections::pvec::PVector;
+/// let v1: PVector<i32> = (0..100).collect();
+/// let v2 = v1.set(50, -1).push_back(100);
+/// assert_eq!(v1[50], 50);
+/// assert_eq!(v1.len(), 100);
+/// assert_eq!(v2[50], -1);
+/// assert_eq!(v2.len(), 101);
+/// ```
+pub struct PVector<T> {
+    len: usize,
+    // The number of index bits below the root, a multiple of `BITS`.
+    shift: usize,
+    root: Rc<Node<T>>,
+    tail: Rc<Vec<T>>,
+}
+
+impl<T> PVector<T> {
+    /// Creates a new, empty PVector.
+    pub fn new() -> Self {
+        PVector {
+            len: 0,
+            shift: BITS,
+            root: Rc::new(Node::Branch(vec![])),
+            tail: Rc::new(vec![]),
+        }
+    }
+
+    /// Returns the number of items in the vector.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the vector has no items.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns a reference to the item at `index`, or `None` if it is out
+    /// of bounds. Runs in O(log32 n) time.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index >= self.len {
+            return None;
+        }
+        Some(&self.chunk(index)[index & MASK])
+    }
+
+    /// Returns a reference to the last item, or `None` if it is empty.
+    pub fn last(&self) -> Option<&T> {
+        self.tail.last()
+    }
+
+    /// Iterates over the items from front to back.
+    pub fn iter(&self) -> impl Iterator<Item = &T> + '_ {
+        (0..self.len)
+            .step_by(WIDTH)
+            .flat_map(move |start| self.chunk(start).iter())
+    }
+
+    /// The index of the first item in the tail.
+    fn tail_offset(&self) -> usize {
+        if self.len < WIDTH {
+            0
+        } else {
+            ((self.len - 1) >> BITS) << BITS
+        }
+    }
+
+    /// Returns the leaf or tail that holds `index`.
+    fn chunk(&self, index: usize) -> &[T] {
+        if index >= self.tail_offset() {
+            return &self.tail;
+        }
+        let mut node = &self.root;
+        let mut level = self.shift;
+        while level > 0 {
+            node = &node.children()[(index >> level) & MASK];
+            level -= BITS;
+        }
+        node.items()
+    }
+}
+
+impl<T: Clone> PVector<T> {
+    /// Returns a new version with `value` added to the back.
+    pub fn push_back(&self, value: T) -> Self {
+        let mut next = self.clone();
+        next.push_mut(value);
+        next
+    }
+
+    /// Returns a new version with the item at `index` replaced by `value`.
+    /// ## Panics
+    /// Panics if `index` is out of bounds.
+    pub fn set(&self, index: usize, value: T) -> Self {
+        let mut next = self.clone();
+        next.set_mut(index, value);
+        next
+    }
+
+    /// Returns a new version without the last item, along with that item,
+    /// or `None` if it is empty.
+    pub fn pop_back(&self) -> Option<(Self, T)> {
+        let mut next = self.clone();
+        let value = next.pop_mut()?;
+        Some((next, value))
+    }
+
+    /// Returns a transient copy of the vector, which is updated in place
+    /// and turned back into a PVector once a batch of changes is done.
+    /// Nodes shared with `self` are copied the first time they are changed.
+    /// ## Examples
+    /// ```
+    /// # use stdlib_rs::collections::pvec::PVector;
+    /// let empty = PVector::new();
+    /// let mut batch = empty.transient();
+    /// for i in 0..1000 {
+    ///     batch.push_back(i);
+    /// }
+    /// batch.set(0, -1);
+    /// let full = batch.persistent();
+    /// assert_eq!(full.len(), 1000);
+    /// assert_eq!(full[0], -1);
+    /// assert!(empty.is_empty());
+    /// ```
+    pub fn transient(&self) -> TransientVector<T> {
+        TransientVector(self.clone())
+    }
+
+    // The updates below change `self` in place; `Rc::make_mut` copies a node
+    // only if another version still refers to it.
+
+    fn push_mut(&mut self, value: T) {
+        if self.len - self.tail_offset() < WIDTH {
+            Rc::make_mut(&mut self.tail).push(value);
+        } else {
+            let full = mem::replace(&mut self.tail, Rc::new(vec![value]));
+            let leaf = Rc::new(Node::Leaf(
+                Rc::try_unwrap(full).unwrap_or_else(|full| (*full).clone()),
+            ));
+            if (self.len >> BITS) > (1 << self.shift) {
+                // The tree is full, so it grows a level.
+                let old = mem::replace(&mut self.root, Rc::new(Node::Branch(vec![])));
+                self.root = Rc::new(Node::Branch(vec![old, new_path(self.shift, leaf)]));
+                self.shift += BITS;
+            } else {
+                push_leaf(&mut self.root, self.shift, self.len - 1, leaf);
+            }
+        }
+        self.len += 1;
+    }
+
+    fn set_mut(&mut self, index: usize, value: T) {
+        assert!(
+            index < self.len,
+            "index {} is out of bounds for length {}",
+            index,
+            self.len
+        );
+        if index >= self.tail_offset() {
+            Rc::make_mut(&mut self.tail)[index & MASK] = value;
+            return;
+        }
+        let mut node = &mut self.root;
+        let mut level = self.shift;
+        while level > 0 {
+            node = &mut Rc::make_mut(node).children_mut()[(index >> level) & MASK];
+            level -= BITS;
+        }
+        Rc::make_mut(node).items_mut()[index & MASK] = value;
+    }
+
+    fn pop_mut(&mut self) -> Option<T> {
+        let value = Rc::make_mut(&mut self.tail).pop()?;
+        if self.tail.is_empty() && self.len > 1 {
+            // The last leaf of the tree becomes the tail.
+            self.tail = Rc::new(self.chunk(self.len - 2).to_vec());
+            pop_leaf(&mut self.root, self.shift, self.len - 2);
+            if self.shift > BITS && self.root.children().len() == 1 {
+                self.root = self.root.children()[0].clone();
+                self.shift -= BITS;
+            }
+        }
+        self.len -= 1;
+        Some(value)
+    }
+}
+
+/// Wraps `node` in single-child branches until it sits `level` bits deep.
+fn new_path<T>(level: usize, node: Rc<Node<T>>) -> Rc<Node<T>> {
+    if level == 0 {
+        node
+    } else {
+        Rc::new(Node::Branch(vec![new_path(level - BITS, node)]))
+    }
+}
+
+/// Adds `leaf`, whose last item is at `index`, to the end of the tree.
+fn push_leaf<T: Clone>(node: &mut Rc<Node<T>>, level: usize, index: usize, leaf: Rc<Node<T>>) {
+    let children = Rc::make_mut(node).children_mut();
+    let sub = (index >> level) & MASK;
+    if level == BITS {
+        children.push(leaf);
+    } else if sub < children.len() {
+        push_leaf(&mut children[sub], level - BITS, index, leaf);
+    } else {
+        children.push(new_path(level - BITS, leaf));
+    }
+}
+
+/// Removes the last leaf of the tree, whose last item is at `index`, and
+/// returns `true` if `node` is left empty.
+fn pop_leaf<T: Clone>(node: &mut Rc<Node<T>>, level: usize, index: usize) -> bool {
+    let children = Rc::make_mut(node).children_mut();
+    if level > BITS {
+        let sub = (index >> level) & MASK;
+        if pop_leaf(&mut children[sub], level - BITS, index) {
+            children.pop();
+        }
+    } else {
+        children.pop();
+    }
+    children.is_empty()
+}
+
+impl<T> Clone for PVector<T> {
+    fn clone(&self) -> Self {
+        PVector {
+            len: self.len,
+            shift: self.shift,
+            root: self.root.clone(),
+            tail: self.tail.clone(),
+        }
+    }
+}
+
+impl<T> Default for PVector<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Index<usize> for PVector<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        match self.get(index) {
+            Some(value) => value,
+            None => panic!("index {} is out of bounds for length {}", index, self.len),
+        }
+    }
+}
+
+impl<T: PartialEq> PartialEq for PVector<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for PVector<T> {}
+
+impl<T: fmt::Debug> fmt::Debug for PVector<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: Clone> FromIterator<T> for PVector<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut batch = PVector::new().transient();
+        batch.extend(iter);
+        batch.persistent()
+    }
+}
+
+/// A `PVector` that is updated in place, for building or changing a vector
+/// in a batch without creating a new version for every update.
+/// Created by `PVector::transient`.
+pub struct TransientVector<T>(PVector<T>);
+
+impl<T: Clone> TransientVector<T> {
+    /// Adds `value` to the back.
+    pub fn push_back(&mut self, value: T) {
+        self.0.push_mut(value)
+    }
+
+    /// Replaces the item at `index` with `value`.
+    /// ## Panics
+    /// Panics if `index` is out of bounds.
+    pub fn set(&mut self, index: usize, value: T) {
+        self.0.set_mut(index, value)
+    }
+
+    /// Removes the last item and returns it, or `None` if it is empty.
+    pub fn pop_back(&mut self) -> Option<T> {
+        self.0.pop_mut()
+    }
+
+    /// Returns a reference to the item at `index`, or `None` if it is out
+    /// of bounds.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.0.get(index)
+    }
+
+    /// Returns the number of items.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if there are no items.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Finishes the batch and returns the updated vector.
+    pub fn persistent(self) -> PVector<T> {
+        self.0
+    }
+}
+
+impl<T: Clone> Extend<T> for TransientVector<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push_back(value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn versions_are_independent() {
+        let mut versions = vec![PVector::new()];
+        for i in 0..2000 {
+            let next = versions.last().unwrap().push_back(i);
+            versions.push(next);
+        }
+        for (len, version) in versions.iter().enumerate() {
+            assert_eq!(version.len(), len);
+            assert!(version.iter().copied().eq(0..len));
+        }
+        assert_eq!(versions[2000].get(2000), None);
+    }
+
+    #[test]
+    fn set_shares_untouched_versions() {
+        let v: PVector<usize> = (0..1100).collect();
+        let w = v.set(0, 7).set(1040, 8).set(1099, 9);
+        assert_eq!((v[0], v[1040], v[1099]), (0, 1040, 1099));
+        assert_eq!((w[0], w[1040], w[1099]), (7, 8, 9));
+        assert_eq!(v.iter().zip(w.iter()).filter(|(a, b)| a != b).count(), 3);
+    }
+
+    #[test]
+    fn pop_back_to_empty() {
+        let v: PVector<String> = (0..1100).map(|i| i.to_string()).collect();
+        let mut current = v.clone();
+        for i in (0..1100).rev() {
+            let (next, value) = current.pop_back().unwrap();
+            assert_eq!(value, i.to_string());
+            assert_eq!(next.len(), i);
+            assert_eq!(
+                next.last(),
+                i.checked_sub(1).map(|j| j.to_string()).as_ref()
+            );
+            current = next;
+        }
+        assert!(current.pop_back().is_none());
+        assert_eq!(v.len(), 1100);
+        assert_eq!(current.push_back("a".to_string())[0], "a");
+    }
+
+    #[test]
+    fn transient_matches_vec() {
+        let base: PVector<u32> = (0..40).collect();
+        let mut batch = base.transient();
+        let mut expected: Vec<u32> = (0..40).collect();
+        for i in 0..3000u32 {
+            if i % 3 == 2 {
+                assert_eq!(batch.pop_back(), expected.pop());
+            } else {
+                batch.push_back(i);
+                expected.push(i);
+            }
+            let index = (i as usize * 7) % expected.len();
+            batch.set(index, i);
+            expected[index] = i;
+        }
+        let done = batch.persistent();
+        assert!(done.iter().eq(expected.iter()));
+        assert!(base.iter().copied().eq(0..40));
+    }
+}
@@ -1,33 +1,126 @@
 #![deny(missing_docs)]
 
-use std::iter::FromIterator;
-use std::mem;
+use alloc::{vec, vec::Vec};
+use core::iter::FromIterator;
+use core::marker::PhantomData;
 
-#[derive(Default, Debug, Eq, PartialEq)]
+use super::alloc_vec::AllocVec;
+use super::allocator::Allocator;
+use super::small_vec::SmallVec;
+use super::storage::{QueueStorage, Storage};
+
+#[derive(Debug, Eq, PartialEq)]
 /// A queue created with two stacks.
-pub struct Queue<T>(Vec<T>, Vec<T>);
+/// The stacks are `Vec`s unless another `Storage` is chosen with `S`, such
+/// as an `AllocVec` from `new_in`.
+pub struct Queue<T, S = Vec<T>>(S, S, PhantomData<T>);
+
+/// A Queue whose stacks each keep up to `N` items inline before allocating.
+pub type SmallQueue<T, const N: usize> = Queue<T, SmallVec<[T; N]>>;
 
 impl<T> Queue<T> {
     /// Creates a new Queue.
     pub fn new() -> Self {
-        Queue(vec![], vec![])
+        Queue(vec![], vec![], PhantomData)
+    }
+}
+
+impl<T, A: Allocator + Clone> Queue<T, AllocVec<T, A>> {
+    /// Creates a new Queue whose stacks allocate from `alloc`.
+    /// ## Examples
+    /// ```
+    /// # use stdlib_rs::collections::arena::Bump;
+    /// # use stdlib_rs::collections::queue_with_stack::Queue;
+    /// let bump = Bump::new();
+    /// let mut queue = Queue::new_in(&bump);
+    /// queue.push(1);
+    /// queue.push(2);
+    /// assert_eq!(queue.pop(), Some(1));
+    /// ```
+    pub fn new_in(alloc: A) -> Self {
+        Queue(
+            AllocVec::new_in(alloc.clone()),
+            AllocVec::new_in(alloc),
+            PhantomData,
+        )
     }
 
+    /// Creates a new Queue with room for `capacity` items in each stack,
+    /// allocated from `alloc`.
+    pub fn with_capacity_in(capacity: usize, alloc: A) -> Self {
+        Queue(
+            AllocVec::with_capacity_in(capacity, alloc.clone()),
+            AllocVec::with_capacity_in(capacity, alloc),
+            PhantomData,
+        )
+    }
+}
+
+impl<T, S: Storage<T> + Default> Default for Queue<T, S> {
+    fn default() -> Self {
+        Queue(S::default(), S::default(), PhantomData)
+    }
+}
+
+impl<T, S: Storage<T>> Queue<T, S> {
     /// Adds an item to the end of the queue in O(1) time.
     pub fn push(&mut self, item: T) {
         self.0.push(item);
     }
 
-    /// Removes the first item from the queue in O(n) time.
+    /// Removes the first item from the queue in amortized O(1) time.
+    /// ## Examples
+    /// ```
+    /// # use stdlib_rs::collections::queue_with_stack::SmallQueue;
+    /// let mut queue: SmallQueue<i32, 4> = SmallQueue::default();
+    /// queue.push(1);
+    /// queue.push(2);
+    /// assert_eq!(queue.pop(), Some(1));
+    /// ```
     pub fn pop(&mut self) -> Option<T> {
-        self.move_to_second_stack();
+        if self.1.is_empty() {
+            self.move_to_second_stack();
+        }
         self.1.pop()
     }
 
+    /// Returns `true` if the queue has no items.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty() && self.1.is_empty()
+    }
+
+    /// Returns the number of items in the queue.
+    pub fn len(&self) -> usize {
+        self.0.len() + self.1.len()
+    }
+
+    // Reverses the first stack onto the second, so the oldest item is on top.
     fn move_to_second_stack(&mut self) {
-        let v = mem::take(&mut self.0);
-        let iter = v.into_iter().rev();
-        self.1.extend(iter);
+        while let Some(item) = self.0.pop() {
+            self.1.push(item);
+        }
+    }
+}
+
+impl<T, S: Storage<T>> QueueStorage<T> for Queue<T, S> {
+    fn push_back(&mut self, item: T) {
+        self.push(item)
+    }
+
+    fn pop_front(&mut self) -> Option<T> {
+        self.pop()
+    }
+
+    fn len(&self) -> usize {
+        Queue::len(self)
+    }
+}
+
+impl<T, S: Storage<T>> Extend<T> for Queue<T, S> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for i in iter {
+            self.push(i);
+        }
     }
 }
 
@@ -41,12 +134,17 @@ impl<T> FromIterator<T> for Queue<T> {
     }
 }
 
-impl<T> IntoIterator for Queue<T> {
+impl<T, S> IntoIterator for Queue<T, S>
+where
+    S: Storage<T>,
+    S::IntoIter: DoubleEndedIterator,
+{
     type Item = T;
-    type IntoIter = std::iter::Chain<std::vec::IntoIter<T>, std::vec::IntoIter<T>>;
+    type IntoIter = core::iter::Chain<core::iter::Rev<S::IntoIter>, S::IntoIter>;
 
+    /// Iterates over the items from the front of the queue to the back.
     fn into_iter(self) -> Self::IntoIter {
-        self.0.into_iter().chain(self.1.into_iter())
+        self.1.into_iter().rev().chain(self.0)
     }
 }
 
@@ -76,6 +174,7 @@ macro_rules! queue [
 
 #[cfg(test)]
 mod tests {
+    use super::super::arena::Bump;
     use super::*;
 
     #[test]
@@ -89,7 +188,7 @@ mod tests {
         let mut queue = queue![];
         queue.push(10);
 
-        assert_eq!(queue, Queue(vec![10], vec![]));
+        assert_eq!(queue, Queue(vec![10], vec![], PhantomData));
     }
 
     #[test]
@@ -129,4 +228,40 @@ mod tests {
             assert_eq!(Some(i), iter.next());
         }
     }
+
+    #[test]
+    fn small_queue_test() {
+        let mut queue: SmallQueue<i32, 4> = SmallQueue::default();
+        queue.extend(1..4);
+        for i in 1..4 {
+            assert_eq!(queue.pop(), Some(i));
+        }
+        assert_eq!(queue.pop(), None);
+        assert!(!queue.0.spilled() && !queue.1.spilled());
+    }
+
+    #[test]
+    fn interleaved_push_pop() {
+        let mut queue = queue![1, 2];
+        assert_eq!(queue.pop(), Some(1));
+        queue.push(3);
+        assert_eq!(queue.len(), 2);
+        assert_eq!(queue.pop(), Some(2));
+        queue.push(4);
+        assert_eq!(queue.into_iter().collect::<Vec<_>>(), vec![3, 4]);
+    }
+
+    #[test]
+    fn allocates_from_bump() {
+        let bump = Bump::new();
+        let mut queue = Queue::with_capacity_in(4, &bump);
+        assert!(bump.allocated_bytes() > 0);
+        queue.extend(0..10);
+        assert_eq!(queue.pop(), Some(0));
+        queue.push(10);
+        assert_eq!(
+            queue.into_iter().collect::<Vec<_>>(),
+            (1..=10).collect::<Vec<_>>()
+        );
+    }
 }
@@ -0,0 +1,582 @@
+#![deny(missing_docs)]
+
+use alloc::{
+    format,
+    rc::Rc,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::fmt;
+use core::ops::RangeBounds;
+
+use super::to_bounds;
+
+/// The largest number of bytes a leaf holds when the rope is built.
+const MAX_LEAF: usize = 1024;
+
+#[derive(Clone, Copy, Default)]
+struct Metrics {
+    bytes: usize,
+    chars: usize,
+    newlines: usize,
+}
+
+impl Metrics {
+    fn of(text: &str) -> Metrics {
+        Metrics {
+            bytes: text.len(),
+            chars: text.chars().count(),
+            newlines: text.bytes().filter(|&b| b == b'\n').count(),
+        }
+    }
+
+    fn add(self, other: Metrics) -> Metrics {
+        Metrics {
+            bytes: self.bytes + other.bytes,
+            chars: self.chars + other.chars,
+            newlines: self.newlines + other.newlines,
+        }
+    }
+}
+
+enum Node {
+    Leaf(String, Metrics),
+    Branch {
+        left: Rc<Node>,
+        right: Rc<Node>,
+        metrics: Metrics,
+        height: usize,
+    },
+}
+
+impl Node {
+    fn leaf(text: String) -> Rc<Node> {
+        let metrics = Metrics::of(&text);
+        Rc::new(Node::Leaf(text, metrics))
+    }
+
+    fn branch(left: Rc<Node>, right: Rc<Node>) -> Rc<Node> {
+        Rc::new(Node::Branch {
+            metrics: left.metrics().add(right.metrics()),
+            height: 1 + left.height().max(right.height()),
+            left,
+            right,
+        })
+    }
+
+    fn metrics(&self) -> Metrics {
+        match self {
+            Node::Leaf(_, metrics) | Node::Branch { metrics, .. } => *metrics,
+        }
+    }
+
+    fn height(&self) -> usize {
+        match self {
+            Node::Leaf(..) => 0,
+            Node::Branch { height, .. } => *height,
+        }
+    }
+
+    fn children(&self) -> (&Rc<Node>, &Rc<Node>) {
+        match self {
+            Node::Branch { left, right, .. } => (left, right),
+            Node::Leaf(..) => unreachable!("leaves have no children"),
+        }
+    }
+}
+
+/// Builds a balanced node from two subtrees whose heights differ by at most two.
+fn balance(left: Rc<Node>, right: Rc<Node>) -> Rc<Node> {
+    if left.height() > right.height() + 1 {
+        let (ll, lr) = left.children();
+        if lr.height() > ll.height() {
+            let (lrl, lrr) = lr.children();
+            Node::branch(
+                Node::branch(ll.clone(), lrl.clone()),
+                Node::branch(lrr.clone(), right),
+            )
+        } else {
+            Node::branch(ll.clone(), Node::branch(lr.clone(), right))
+        }
+    } else if right.height() > left.height() + 1 {
+        let (rl, rr) = right.children();
+        if rl.height() > rr.height() {
+            let (rll, rlr) = rl.children();
+            Node::branch(
+                Node::branch(left, rll.clone()),
+                Node::branch(rlr.clone(), rr.clone()),
+            )
+        } else {
+            Node::branch(Node::branch(left, rl.clone()), rr.clone())
+        }
+    } else {
+        Node::branch(left, right)
+    }
+}
+
+/// Concatenates two trees, keeping the result balanced.
+fn join(left: Rc<Node>, right: Rc<Node>) -> Rc<Node> {
+    if left.metrics().bytes == 0 {
+        return right;
+    }
+    if right.metrics().bytes == 0 {
+        return left;
+    }
+    if let (Node::Leaf(a, _), Node::Leaf(b, _)) = (&*left, &*right) {
+        if a.len() + b.len() <= MAX_LEAF {
+            return Node::leaf(format!("{}{}", a, b));
+        }
+    }
+    if left.height() > right.height() + 1 {
+        let (ll, lr) = left.children();
+        balance(ll.clone(), join(lr.clone(), right))
+    } else if right.height() > left.height() + 1 {
+        let (rl, rr) = right.children();
+        balance(join(left, rl.clone()), rr.clone())
+    } else {
+        Node::branch(left, right)
+    }
+}
+
+/// Splits a tree into the first `index` chars and the rest.
+fn split(node: &Rc<Node>, index: usize) -> (Rc<Node>, Rc<Node>) {
+    match &**node {
+        Node::Leaf(text, metrics) => {
+            if index == 0 {
+                (Node::leaf(String::new()), node.clone())
+            } else if index == metrics.chars {
+                (node.clone(), Node::leaf(String::new()))
+            } else {
+                let at = byte_offset(text, index);
+                (
+                    Node::leaf(text[..at].to_string()),
+                    Node::leaf(text[at..].to_string()),
+                )
+            }
+        }
+        Node::Branch { left, right, .. } => {
+            let left_chars = left.metrics().chars;
+            if index <= left_chars {
+                let (a, b) = split(left, index);
+                (a, join(b, right.clone()))
+            } else {
+                let (a, b) = split(right, index - left_chars);
+                (join(left.clone(), a), b)
+            }
+        }
+    }
+}
+
+/// Builds a balanced tree from a run of leaves.
+fn build(leaves: &[String]) -> Rc<Node> {
+    match leaves.len() {
+        0 => Node::leaf(String::new()),
+        1 => Node::leaf(leaves[0].clone()),
+        n => Node::branch(build(&leaves[..n / 2]), build(&leaves[n / 2..])),
+    }
+}
+
+/// Returns the byte offset of the char at `index` in `text`.
+fn byte_offset(text: &str, index: usize) -> usize {
+    text.char_indices()
+        .nth(index)
+        .map_or(text.len(), |(at, _)| at)
+}
+
+/// A string stored as a balanced tree of UTF-8 chunks, which supports
+/// O(log n) edits and lookups by char and by line anywhere in the text.
+///
+/// Subtrees are shared between ropes, so cloning a rope is O(1) and edits
+/// only copy the O(log n) nodes on the path to the change.
+#[derive(Clone)]
+pub struct Rope(Rc<Node>);
+
+impl Default for Rope {
+    fn default() -> Self {
+        Rope::new()
+    }
+}
+
+impl Rope {
+    /// Creates a new, empty Rope.
+    pub fn new() -> Self {
+        Rope(Node::leaf(String::new()))
+    }
+
+    /// Returns the length of the rope in bytes.
+    pub fn len_bytes(&self) -> usize {
+        self.0.metrics().bytes
+    }
+
+    /// Returns the length of the rope in chars.
+    pub fn len_chars(&self) -> usize {
+        self.0.metrics().chars
+    }
+
+    /// Returns the number of lines in the rope, which is one more than the
+    /// number of `'\n'`s in it.
+    pub fn len_lines(&self) -> usize {
+        self.0.metrics().newlines + 1
+    }
+
+    /// Returns `true` if the rope holds no text.
+    pub fn is_empty(&self) -> bool {
+        self.len_bytes() == 0
+    }
+
+    /// Inserts `text` before the char at `index` in O(log n) time.
+    /// ## Panics
+    /// Panics if `index` is greater than the number of chars in the rope.
+    /// ## Examples
+    /// ```
+    /// # use stdlib_rs::collections::rope::Rope;
+    /// let mut rope = Rope::from("Hello world");
+    /// rope.insert(5, ",");
+    /// rope.insert(12, "!");
+    /// assert_eq!(rope.to_string(), "Hello, world!");
+    /// ```
+    pub fn insert(&mut self, index: usize, text: &str) {
+        assert!(
+            index <= self.len_chars(),
+            "index {} is out of bounds",
+            index
+        );
+        let (left, right) = split(&self.0, index);
+        let middle = Rope::from(text).0;
+        self.0 = join(join(left, middle), right);
+    }
+
+    /// Removes the chars in `range` in O(log n) time.
+    /// ## Panics
+    /// Panics if the range is out of bounds.
+    /// ## Examples
+    /// ```
+    /// # use stdlib_rs::collections::rope::Rope;
+    /// let mut rope = Rope::from("Hello, world!");
+    /// rope.remove(5..12);
+    /// assert_eq!(rope.to_string(), "Hello!");
+    /// ```
+    pub fn remove<R: RangeBounds<usize>>(&mut self, range: R) {
+        let (start, end) = to_bounds(range, self.len_chars());
+        let (left, rest) = split(&self.0, start);
+        let (_, right) = split(&rest, end - start);
+        self.0 = join(left, right);
+    }
+
+    /// Returns the chars in `range` as a new rope in O(log n) time.
+    /// ## Panics
+    /// Panics if the range is out of bounds.
+    /// ## Examples
+    /// ```
+    /// # use stdlib_rs::collections::rope::Rope;
+    /// let rope = Rope::from("Hello, world!");
+    /// assert_eq!(rope.slice(7..12).to_string(), "world");
+    /// ```
+    pub fn slice<R: RangeBounds<usize>>(&self, range: R) -> Rope {
+        let (start, end) = to_bounds(range, self.len_chars());
+        let (_, rest) = split(&self.0, start);
+        let (middle, _) = split(&rest, end - start);
+        Rope(middle)
+    }
+
+    /// Appends `other` to the end of the rope in O(log n) time.
+    pub fn append(&mut self, other: &Rope) {
+        self.0 = join(self.0.clone(), other.0.clone());
+    }
+
+    /// Returns the char at `index` in O(log n) time, or `None` if it is out
+    /// of bounds.
+    pub fn char(&self, index: usize) -> Option<char> {
+        let mut node = &self.0;
+        let mut index = index;
+        loop {
+            match &**node {
+                Node::Leaf(text, _) => return text.chars().nth(index),
+                Node::Branch { left, right, .. } => {
+                    let left_chars = left.metrics().chars;
+                    if index < left_chars {
+                        node = left;
+                    } else {
+                        index -= left_chars;
+                        node = right;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the index of the line holding the char at `index` in
+    /// O(log n) time.
+    /// ## Panics
+    /// Panics if `index` is greater than the number of chars in the rope.
+    /// ## Examples
+    /// ```
+    /// # use stdlib_rs::collections::rope::Rope;
+    /// let rope = Rope::from("one\ntwo\nthree");
+    /// assert_eq!(rope.char_to_line(2), 0);
+    /// assert_eq!(rope.char_to_line(4), 1);
+    /// assert_eq!(rope.char_to_line(13), 2);
+    /// ```
+    pub fn char_to_line(&self, index: usize) -> usize {
+        assert!(
+            index <= self.len_chars(),
+            "index {} is out of bounds",
+            index
+        );
+        let mut node = &self.0;
+        let mut index = index;
+        let mut line = 0;
+        loop {
+            match &**node {
+                Node::Leaf(text, _) => {
+                    return line + text.chars().take(index).filter(|&c| c == '\n').count()
+                }
+                Node::Branch { left, right, .. } => {
+                    let metrics = left.metrics();
+                    if index <= metrics.chars {
+                        node = left;
+                    } else {
+                        index -= metrics.chars;
+                        line += metrics.newlines;
+                        node = right;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the index of the first char of line `line` in O(log n) time.
+    /// ## Panics
+    /// Panics if `line` is not less than the number of lines in the rope.
+    /// ## Examples
+    /// ```
+    /// # use stdlib_rs::collections::rope::Rope;
+    /// let rope = Rope::from("one\ntwo\nthree");
+    /// assert_eq!(rope.line_to_char(0), 0);
+    /// assert_eq!(rope.line_to_char(2), 8);
+    /// ```
+    pub fn line_to_char(&self, line: usize) -> usize {
+        assert!(line < self.len_lines(), "line {} is out of bounds", line);
+        if line == 0 {
+            return 0;
+        }
+        // Find the char just past the `line`th newline.
+        let mut node = &self.0;
+        let mut line = line;
+        let mut index = 0;
+        loop {
+            match &**node {
+                Node::Leaf(text, _) => {
+                    let newline = text
+                        .chars()
+                        .enumerate()
+                        .filter(|&(_, c)| c == '\n')
+                        .nth(line - 1)
+                        .map(|(at, _)| at)
+                        .expect("line metrics are out of sync");
+                    return index + newline + 1;
+                }
+                Node::Branch { left, right, .. } => {
+                    let metrics = left.metrics();
+                    if line <= metrics.newlines {
+                        node = left;
+                    } else {
+                        line -= metrics.newlines;
+                        index += metrics.chars;
+                        node = right;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns line `line` as a new rope, including its trailing `'\n'`.
+    /// ## Panics
+    /// Panics if `line` is not less than the number of lines in the rope.
+    /// ## Examples
+    /// ```
+    /// # use stdlib_rs::collections::rope::Rope;
+    /// let rope = Rope::from("one\ntwo\nthree");
+    /// assert_eq!(rope.line(1).to_string(), "two\n");
+    /// assert_eq!(rope.line(2).to_string(), "three");
+    /// ```
+    pub fn line(&self, line: usize) -> Rope {
+        let start = self.line_to_char(line);
+        let end = if line + 1 < self.len_lines() {
+            self.line_to_char(line + 1)
+        } else {
+            self.len_chars()
+        };
+        self.slice(start..end)
+    }
+
+    /// Iterates over the chunks of text that make up the rope, in order.
+    pub fn chunks(&self) -> Chunks<'_> {
+        Chunks(vec![&self.0])
+    }
+
+    /// Iterates over the chars of the rope.
+    pub fn chars(&self) -> impl Iterator<Item = char> + '_ {
+        self.chunks().flat_map(str::chars)
+    }
+}
+
+impl From<&str> for Rope {
+    fn from(text: &str) -> Self {
+        let mut leaves = vec![];
+        let mut rest = text;
+        while !rest.is_empty() {
+            let mut at = rest.len().min(MAX_LEAF);
+            while !rest.is_char_boundary(at) {
+                at -= 1;
+            }
+            leaves.push(rest[..at].to_string());
+            rest = &rest[at..];
+        }
+        Rope(build(&leaves))
+    }
+}
+
+impl fmt::Display for Rope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for chunk in self.chunks() {
+            f.write_str(chunk)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Rope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.to_string(), f)
+    }
+}
+
+impl PartialEq for Rope {
+    fn eq(&self, other: &Rope) -> bool {
+        self.len_bytes() == other.len_bytes() && self.chars().eq(other.chars())
+    }
+}
+
+impl Eq for Rope {}
+
+/// An iterator over the chunks of a `Rope`.
+pub struct Chunks<'a>(Vec<&'a Rc<Node>>);
+
+impl<'a> Iterator for Chunks<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        while let Some(node) = self.0.pop() {
+            match &**node {
+                Node::Leaf(text, _) if text.is_empty() => {}
+                Node::Leaf(text, _) => return Some(text),
+                Node::Branch { left, right, .. } => {
+                    self.0.push(right);
+                    self.0.push(left);
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(node: &Rc<Node>) {
+        if let Node::Branch { left, right, .. } = &**node {
+            let (a, b) = (left.height(), right.height());
+            assert!(a.max(b) - a.min(b) <= 1);
+            check(left);
+            check(right);
+        }
+    }
+
+    fn text(len: usize) -> String {
+        (0..len)
+            .map(|i| match i % 7 {
+                0 => '\n',
+                1 => 'é',
+                _ => (b'a' + (i % 26) as u8) as char,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn empty_test() {
+        let rope = Rope::new();
+        assert!(rope.is_empty());
+        assert_eq!(rope.len_lines(), 1);
+        assert_eq!(rope.chunks().count(), 0);
+        assert_eq!(rope.to_string(), "");
+    }
+
+    #[test]
+    fn from_str_test() {
+        let expected = text(5000);
+        let rope = Rope::from(expected.as_str());
+        check(&rope.0);
+        assert!(rope.chunks().count() > 1);
+        assert_eq!(rope.to_string(), expected);
+        assert_eq!(rope.len_bytes(), expected.len());
+        assert_eq!(rope.len_chars(), 5000);
+    }
+
+    #[test]
+    fn edits_match_string() {
+        let mut expected: Vec<char> = text(3000).chars().collect();
+        let mut rope = Rope::from(text(3000).as_str());
+        for i in 0..200 {
+            let at = (i * 7919) % (expected.len() + 1);
+            if i % 3 == 0 {
+                let end = (at + i).min(expected.len());
+                rope.remove(at..end);
+                expected.drain(at..end);
+            } else {
+                let insert = text(i % 50);
+                rope.insert(at, &insert);
+                expected.splice(at..at, insert.chars());
+            }
+            check(&rope.0);
+        }
+        assert_eq!(rope.to_string(), expected.iter().collect::<String>());
+        for i in (0..expected.len()).step_by(37) {
+            assert_eq!(rope.char(i), Some(expected[i]));
+        }
+        assert_eq!(rope.char(expected.len()), None);
+    }
+
+    #[test]
+    fn lines_test() {
+        let expected = text(4000);
+        let rope = Rope::from(expected.as_str());
+        let lines: Vec<&str> = expected.split_inclusive('\n').collect();
+        assert_eq!(rope.len_lines(), expected.matches('\n').count() + 1);
+        let mut start = 0;
+        for (i, line) in lines.iter().enumerate() {
+            assert_eq!(rope.line_to_char(i), start);
+            assert_eq!(rope.char_to_line(start), i);
+            assert_eq!(rope.line(i).to_string(), *line);
+            start += line.chars().count();
+        }
+    }
+
+    #[test]
+    fn slice_shares_structure() {
+        let rope = Rope::from(text(5000).as_str());
+        let slice = rope.slice(1000..4000);
+        let expected: String = text(5000).chars().skip(1000).take(3000).collect();
+        assert_eq!(slice.to_string(), expected);
+        assert_eq!(rope.len_chars(), 5000);
+    }
+
+    #[test]
+    fn append_test() {
+        let mut rope = Rope::from("Hello, ");
+        rope.append(&Rope::from("world"));
+        assert_eq!(rope, Rope::from("Hello, world"));
+    }
+}
@@ -1,17 +1,26 @@
-use std::mem;
-
-pub struct List {
-    head: Link,
+use core::marker::PhantomData;
+use core::mem;
+use core::ptr::NonNull;
+
+use super::allocator::{AllocBox, Allocator, Global};
+use super::arena::{Bump, TypedArena};
+
+/// A singly linked stack of `i32`s, with one allocation per node.
+/// Nodes come from the global allocator unless another `Allocator` is
+/// chosen with `A`.
+pub struct List<A: Allocator = Global> {
+    head: Link<A>,
+    alloc: A,
 }
 
-enum Link {
+enum Link<A: Allocator> {
     Empty,
-    More(Box<Node>),
+    More(AllocBox<Node<A>, A>),
 }
 
-struct Node {
+struct Node<A: Allocator> {
     elem: i32,
-    next: Link,
+    next: Link<A>,
 }
 
 impl Default for List {
@@ -22,14 +31,27 @@ impl Default for List {
 
 impl List {
     pub fn new() -> Self {
-        List { head: Link::Empty }
+        Self::new_in(Global)
+    }
+}
+
+impl<A: Allocator + Clone> List<A> {
+    /// Creates a new List that allocates its nodes from `alloc`.
+    pub fn new_in(alloc: A) -> Self {
+        List {
+            head: Link::Empty,
+            alloc,
+        }
     }
 
     pub fn push(&mut self, elem: i32) {
-        let new_node = Box::new(Node {
-            elem,
-            next: mem::replace(&mut self.head, Link::Empty),
-        });
+        let new_node = AllocBox::new_in(
+            Node {
+                elem,
+                next: mem::replace(&mut self.head, Link::Empty),
+            },
+            self.alloc.clone(),
+        );
         self.head = Link::More(new_node);
     }
 
@@ -37,6 +59,7 @@ impl List {
         match mem::replace(&mut self.head, Link::Empty) {
             Link::Empty => None,
             Link::More(node) => {
+                let node = AllocBox::into_inner(node);
                 self.head = node.next;
                 Some(node.elem)
             }
@@ -44,7 +67,7 @@ impl List {
     }
 }
 
-impl Drop for List {
+impl<A: Allocator> Drop for List<A> {
     fn drop(&mut self) {
         let mut cur_link = mem::replace(&mut self.head, Link::Empty);
         while let Link::More(mut boxed_node) = cur_link {
@@ -53,9 +76,74 @@ impl Drop for List {
     }
 }
 
+/// A node of an `ArenaList`, allocated from the list's arena.
+pub struct ArenaNode {
+    elem: i32,
+    // Points into the same arena, which outlives the list.
+    next: Option<NonNull<ArenaNode>>,
+}
+
+enum Backing<'a> {
+    Typed(&'a TypedArena<ArenaNode>),
+    Bump(&'a Bump),
+}
+
+/// A List whose nodes are allocated from an arena instead of one `Box` each.
+/// Popped nodes are not reused; their memory is freed with the arena.
+///
+/// The list only borrows the arena, so any number of lists can share one
+/// arena and be dropped in any order before it.
+pub struct ArenaList<'a> {
+    head: Option<NonNull<ArenaNode>>,
+    arena: Backing<'a>,
+    _nodes: PhantomData<&'a ArenaNode>,
+}
+
+impl<'a> ArenaList<'a> {
+    /// Creates a new List that allocates its nodes from `arena`.
+    pub fn new_in(arena: &'a TypedArena<ArenaNode>) -> Self {
+        ArenaList {
+            head: None,
+            arena: Backing::Typed(arena),
+            _nodes: PhantomData,
+        }
+    }
+
+    /// Creates a new List that allocates its nodes from `bump`.
+    pub fn new_in_bump(bump: &'a Bump) -> Self {
+        ArenaList {
+            head: None,
+            arena: Backing::Bump(bump),
+            _nodes: PhantomData,
+        }
+    }
+
+    pub fn push(&mut self, elem: i32) {
+        let node = ArenaNode {
+            elem,
+            next: self.head.take(),
+        };
+        self.head = Some(NonNull::from(match self.arena {
+            Backing::Typed(arena) => arena.alloc(node),
+            Backing::Bump(bump) => bump.alloc(node),
+        }));
+    }
+
+    pub fn pop(&mut self) -> Option<i32> {
+        self.head.take().map(|node| {
+            // The arena is borrowed for `'a`, so its nodes are still alive,
+            // and only this list points at them.
+            let node = unsafe { node.as_ref() };
+            self.head = node.next;
+            node.elem
+        })
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use super::List;
+    use super::super::arena::{Bump, TypedArena};
+    use super::{ArenaList, List};
 
     #[test]
     fn basics() {
@@ -85,4 +173,61 @@ mod test {
         assert_eq!(list.pop(), Some(1));
         assert_eq!(list.pop(), None);
     }
+
+    #[test]
+    fn arena_basics() {
+        let arena = TypedArena::new();
+        let bump = Bump::new();
+        for mut list in [ArenaList::new_in(&arena), ArenaList::new_in_bump(&bump)] {
+            assert_eq!(list.pop(), None);
+
+            for i in 0..1000 {
+                list.push(i);
+            }
+            for i in (500..1000).rev() {
+                assert_eq!(list.pop(), Some(i));
+            }
+            list.push(-1);
+            assert_eq!(list.pop(), Some(-1));
+            for i in (0..500).rev() {
+                assert_eq!(list.pop(), Some(i));
+            }
+            assert_eq!(list.pop(), None);
+        }
+        assert_eq!(arena.len(), 1001);
+    }
+
+    #[test]
+    fn lists_share_an_arena() {
+        let arena = TypedArena::new();
+        {
+            let mut a = ArenaList::new_in(&arena);
+            a.push(1);
+            let mut b = ArenaList::new_in(&arena);
+            b.push(2);
+            {
+                let mut c = ArenaList::new_in(&arena);
+                c.push(3);
+            }
+            assert_eq!(b.pop(), Some(2));
+            assert_eq!(a.pop(), Some(1));
+        }
+        // Every list is gone, so the arena is free to move.
+        let moved = arena;
+        assert_eq!(moved.len(), 3);
+    }
+
+    #[test]
+    fn allocator_basics() {
+        let bump = Bump::new();
+        let mut list = List::new_in(&bump);
+        for i in 0..100 {
+            list.push(i);
+        }
+        assert!(bump.allocated_bytes() >= 100 * 8);
+        for i in (0..100).rev() {
+            assert_eq!(list.pop(), Some(i));
+        }
+        assert_eq!(list.pop(), None);
+    }
 }
@@ -0,0 +1,473 @@
+#![deny(missing_docs)]
+
+use alloc::{vec, vec::Vec};
+use core::marker::PhantomData;
+use core::ops::{Add, RangeBounds};
+
+use super::to_bounds;
+
+/// Describes the values stored in a `SegmentTree` and the lazy updates
+/// that can be applied to whole ranges of them.
+///
+/// `combine` must be associative with `identity` as its identity, and
+/// `apply` must distribute over `combine` so that pending updates can be
+/// pushed down the tree lazily.
+pub trait Monoid {
+    /// The type of the elements and of range query results.
+    type Value: Clone;
+    /// The type of a pending range update.
+    type Update: Clone;
+
+    /// The result of querying an empty range.
+    fn identity() -> Self::Value;
+
+    /// Combines the results of two adjacent ranges, `left` coming first.
+    fn combine(left: &Self::Value, right: &Self::Value) -> Self::Value;
+
+    /// Applies `update` to `value`, the combined result of `len` elements.
+    fn apply(update: &Self::Update, value: &Self::Value, len: usize) -> Self::Value;
+
+    /// Merges two updates into one, where `later` is applied after `earlier`.
+    fn compose(later: &Self::Update, earlier: &Self::Update) -> Self::Update;
+}
+
+/// A range update for the `Sum`, `Min` and `Max` monoids.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangeUpdate<T> {
+    /// Adds the value to every element in the range.
+    Add(T),
+    /// Replaces every element in the range with the value.
+    Assign(T),
+}
+
+impl<T> RangeUpdate<T>
+where
+    T: Copy + Add<Output = T>,
+{
+    fn compose(later: &Self, earlier: &Self) -> Self {
+        match (later, earlier) {
+            (RangeUpdate::Assign(x), _) => RangeUpdate::Assign(*x),
+            (RangeUpdate::Add(a), RangeUpdate::Add(b)) => RangeUpdate::Add(*b + *a),
+            (RangeUpdate::Add(a), RangeUpdate::Assign(x)) => RangeUpdate::Assign(*x + *a),
+        }
+    }
+}
+
+/// Types with a smallest and a largest value, used as the identities of
+/// the `Max` and `Min` monoids.
+pub trait Bounded {
+    /// The smallest value of the type.
+    fn min_value() -> Self;
+    /// The largest value of the type.
+    fn max_value() -> Self;
+}
+
+macro_rules! impl_bounded {
+    ($($t:ty),*) => {
+        $(impl Bounded for $t {
+            fn min_value() -> Self {
+                <$t>::MIN
+            }
+
+            fn max_value() -> Self {
+                <$t>::MAX
+            }
+        })*
+    };
+}
+
+impl_bounded!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+/// Range sums with `RangeUpdate` updates.
+#[derive(Debug)]
+pub struct Sum<T>(PhantomData<T>);
+
+/// Range minimums with `RangeUpdate` updates.
+#[derive(Debug)]
+pub struct Min<T>(PhantomData<T>);
+
+/// Range maximums with `RangeUpdate` updates.
+#[derive(Debug)]
+pub struct Max<T>(PhantomData<T>);
+
+/// Adds `value` to itself `n` times using O(log n) additions.
+fn times<T>(value: T, mut n: usize) -> T
+where
+    T: Copy + Default + Add<Output = T>,
+{
+    let mut acc = T::default();
+    let mut base = value;
+    while n > 0 {
+        if n & 1 == 1 {
+            acc = acc + base;
+        }
+        n >>= 1;
+        if n > 0 {
+            base = base + base;
+        }
+    }
+    acc
+}
+
+impl<T> Monoid for Sum<T>
+where
+    T: Copy + Default + Add<Output = T>,
+{
+    type Value = T;
+    type Update = RangeUpdate<T>;
+
+    fn identity() -> T {
+        T::default()
+    }
+
+    fn combine(left: &T, right: &T) -> T {
+        *left + *right
+    }
+
+    fn apply(update: &RangeUpdate<T>, value: &T, len: usize) -> T {
+        match update {
+            RangeUpdate::Add(a) => *value + times(*a, len),
+            RangeUpdate::Assign(x) => times(*x, len),
+        }
+    }
+
+    fn compose(later: &RangeUpdate<T>, earlier: &RangeUpdate<T>) -> RangeUpdate<T> {
+        RangeUpdate::compose(later, earlier)
+    }
+}
+
+impl<T> Monoid for Min<T>
+where
+    T: Copy + Ord + Bounded + Add<Output = T>,
+{
+    type Value = T;
+    type Update = RangeUpdate<T>;
+
+    fn identity() -> T {
+        T::max_value()
+    }
+
+    fn combine(left: &T, right: &T) -> T {
+        *left.min(right)
+    }
+
+    fn apply(update: &RangeUpdate<T>, value: &T, _len: usize) -> T {
+        match update {
+            RangeUpdate::Add(a) => *value + *a,
+            RangeUpdate::Assign(x) => *x,
+        }
+    }
+
+    fn compose(later: &RangeUpdate<T>, earlier: &RangeUpdate<T>) -> RangeUpdate<T> {
+        RangeUpdate::compose(later, earlier)
+    }
+}
+
+impl<T> Monoid for Max<T>
+where
+    T: Copy + Ord + Bounded + Add<Output = T>,
+{
+    type Value = T;
+    type Update = RangeUpdate<T>;
+
+    fn identity() -> T {
+        T::min_value()
+    }
+
+    fn combine(left: &T, right: &T) -> T {
+        *left.max(right)
+    }
+
+    fn apply(update: &RangeUpdate<T>, value: &T, _len: usize) -> T {
+        match update {
+            RangeUpdate::Add(a) => *value + *a,
+            RangeUpdate::Assign(x) => *x,
+        }
+    }
+
+    fn compose(later: &RangeUpdate<T>, earlier: &RangeUpdate<T>) -> RangeUpdate<T> {
+        RangeUpdate::compose(later, earlier)
+    }
+}
+
+/// A segment tree over an array of `M::Value`s that answers range queries
+/// and applies range updates in O(log n) time, deferring updates to
+/// subtrees until they are needed.
+pub struct SegmentTree<M: Monoid> {
+    len: usize,
+    values: Vec<M::Value>,
+    pending: Vec<Option<M::Update>>,
+}
+
+impl<M: Monoid> SegmentTree<M> {
+    /// Creates a new SegmentTree of `len` elements, all set to `M::identity()`.
+    /// For `Min` and `Max` that is the type's largest or smallest value,
+    /// which range updates change like any other element.
+    pub fn new(len: usize) -> Self {
+        SegmentTree::from(vec![M::identity(); len])
+    }
+
+    /// Returns the number of elements in the tree.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the tree has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Combines the elements in `range` in O(log n) time.
+    /// Returns `M::identity()` for an empty range.
+    /// ## Panics
+    /// Panics if the range is out of bounds.
+    /// ## Examples
+    /// ```
+    /// # use stdlib_rs::collections::segment_tree::*;
+    /// let tree: SegmentTree<Min<i32>> = vec![5, 3, 8, 1, 9].into();
+    /// assert_eq!(tree.query(0..3), 3);
+    /// assert_eq!(tree.query(..), 1);
+    /// ```
+    pub fn query<R: RangeBounds<usize>>(&self, range: R) -> M::Value {
+        let (start, end) = to_bounds(range, self.len);
+        if start == end {
+            return M::identity();
+        }
+        self.query_node(1, 0, self.len, start, end)
+    }
+
+    /// Applies `update` to every element in `range` in O(log n) time.
+    /// ## Panics
+    /// Panics if the range is out of bounds.
+    /// ## Examples
+    /// ```
+    /// # use stdlib_rs::collections::segment_tree::*;
+    /// let mut tree: SegmentTree<Sum<i64>> = vec![1, 2, 3, 4].into();
+    /// tree.update(1..3, RangeUpdate::Add(10));
+    /// assert_eq!(tree.query(..), 30);
+    /// tree.update(.., RangeUpdate::Assign(0));
+    /// assert_eq!(tree.query(..), 0);
+    /// ```
+    pub fn update<R: RangeBounds<usize>>(&mut self, range: R, update: M::Update) {
+        let (start, end) = to_bounds(range, self.len);
+        if start == end {
+            return;
+        }
+        self.update_node(1, 0, self.len, start, end, &update);
+    }
+
+    /// Returns the element at `index` in O(log n) time.
+    /// ## Panics
+    /// Panics if `index` is out of bounds.
+    pub fn get(&self, index: usize) -> M::Value {
+        self.query(index..=index)
+    }
+
+    /// Replaces the element at `index` with `value` in O(log n) time.
+    /// ## Panics
+    /// Panics if `index` is out of bounds.
+    pub fn set(&mut self, index: usize, value: M::Value) {
+        assert!(index < self.len, "index {} is out of bounds", index);
+        self.set_node(1, 0, self.len, index, value);
+    }
+
+    fn build(&mut self, node: usize, lo: usize, hi: usize, items: &[M::Value]) {
+        if hi - lo == 1 {
+            self.values[node] = items[lo].clone();
+            return;
+        }
+        let mid = (lo + hi) / 2;
+        self.build(2 * node, lo, mid, items);
+        self.build(2 * node + 1, mid, hi, items);
+        self.pull(node);
+    }
+
+    fn query_node(&self, node: usize, lo: usize, hi: usize, start: usize, end: usize) -> M::Value {
+        if start <= lo && hi <= end {
+            return self.values[node].clone();
+        }
+        let mid = (lo + hi) / 2;
+        let result = if end <= mid {
+            self.query_node(2 * node, lo, mid, start, end)
+        } else if start >= mid {
+            self.query_node(2 * node + 1, mid, hi, start, end)
+        } else {
+            M::combine(
+                &self.query_node(2 * node, lo, mid, start, end),
+                &self.query_node(2 * node + 1, mid, hi, start, end),
+            )
+        };
+        // Updates pending on this node have not reached the children yet.
+        match &self.pending[node] {
+            Some(update) => {
+                let covered = end.min(hi) - start.max(lo);
+                M::apply(update, &result, covered)
+            }
+            None => result,
+        }
+    }
+
+    fn update_node(
+        &mut self,
+        node: usize,
+        lo: usize,
+        hi: usize,
+        start: usize,
+        end: usize,
+        update: &M::Update,
+    ) {
+        if start <= lo && hi <= end {
+            self.apply(node, hi - lo, update);
+            return;
+        }
+        self.push(node, lo, hi);
+        let mid = (lo + hi) / 2;
+        if start < mid {
+            self.update_node(2 * node, lo, mid, start, end, update);
+        }
+        if end > mid {
+            self.update_node(2 * node + 1, mid, hi, start, end, update);
+        }
+        self.pull(node);
+    }
+
+    fn set_node(&mut self, node: usize, lo: usize, hi: usize, index: usize, value: M::Value) {
+        if hi - lo == 1 {
+            self.values[node] = value;
+            self.pending[node] = None;
+            return;
+        }
+        self.push(node, lo, hi);
+        let mid = (lo + hi) / 2;
+        if index < mid {
+            self.set_node(2 * node, lo, mid, index, value);
+        } else {
+            self.set_node(2 * node + 1, mid, hi, index, value);
+        }
+        self.pull(node);
+    }
+
+    fn apply(&mut self, node: usize, len: usize, update: &M::Update) {
+        self.values[node] = M::apply(update, &self.values[node], len);
+        self.pending[node] = Some(match &self.pending[node] {
+            Some(earlier) => M::compose(update, earlier),
+            None => update.clone(),
+        });
+    }
+
+    fn push(&mut self, node: usize, lo: usize, hi: usize) {
+        if let Some(update) = self.pending[node].take() {
+            let mid = (lo + hi) / 2;
+            self.apply(2 * node, mid - lo, &update);
+            self.apply(2 * node + 1, hi - mid, &update);
+        }
+    }
+
+    fn pull(&mut self, node: usize) {
+        self.values[node] = M::combine(&self.values[2 * node], &self.values[2 * node + 1]);
+    }
+}
+
+impl<M: Monoid> From<Vec<M::Value>> for SegmentTree<M> {
+    /// Builds a SegmentTree from a vector in O(n) time.
+    fn from(vec: Vec<M::Value>) -> Self {
+        let len = vec.len();
+        let size = 4 * len.max(1);
+        let mut tree = SegmentTree {
+            len,
+            values: vec![M::identity(); size],
+            pending: vec![None; size],
+        };
+        if len > 0 {
+            tree.build(1, 0, len, &vec);
+        }
+        tree
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_test() {
+        let tree: SegmentTree<Sum<i32>> = SegmentTree::new(0);
+        assert!(tree.is_empty());
+        assert_eq!(tree.query(..), 0);
+    }
+
+    #[test]
+    fn times_test() {
+        assert_eq!(times(3, 0), 0);
+        assert_eq!(times(3, 1), 3);
+        assert_eq!(times(3, 7), 21);
+        assert_eq!(times(i32::MAX, 1), i32::MAX);
+    }
+
+    #[test]
+    fn sum_matches_naive() {
+        let mut naive: Vec<i64> = (0..13).collect();
+        let mut tree: SegmentTree<Sum<i64>> = naive.clone().into();
+        let updates = [
+            (2, 9, RangeUpdate::Add(3)),
+            (0, 5, RangeUpdate::Assign(-1)),
+            (4, 13, RangeUpdate::Add(2)),
+            (7, 8, RangeUpdate::Assign(100)),
+            (1, 12, RangeUpdate::Add(-4)),
+        ];
+        for &(start, end, update) in updates.iter() {
+            tree.update(start..end, update);
+            for item in naive[start..end].iter_mut() {
+                *item = match update {
+                    RangeUpdate::Add(a) => *item + a,
+                    RangeUpdate::Assign(x) => x,
+                };
+            }
+            for start in 0..13 {
+                for end in start..=13 {
+                    assert_eq!(tree.query(start..end), naive[start..end].iter().sum());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn min_max_test() {
+        let mut min: SegmentTree<Min<i32>> = vec![5, 3, 8, 1, 9].into();
+        let mut max: SegmentTree<Max<i32>> = vec![5, 3, 8, 1, 9].into();
+        min.update(2..4, RangeUpdate::Add(5));
+        max.update(2..4, RangeUpdate::Add(5));
+        assert_eq!(min.query(..), 3);
+        assert_eq!(max.query(..), 13);
+        min.update(..2, RangeUpdate::Assign(7));
+        max.update(3.., RangeUpdate::Assign(0));
+        assert_eq!(min.query(..), 6);
+        assert_eq!(max.query(..), 13);
+        assert_eq!(max.query(3..), 0);
+    }
+
+    #[test]
+    fn extreme_values_test() {
+        // Elements equal to the identity are still elements.
+        let mut min: SegmentTree<Min<i32>> = vec![i32::MAX, i32::MAX, 5].into();
+        let mut max: SegmentTree<Max<i32>> = vec![i32::MIN, i32::MIN, 5].into();
+        min.update(..2, RangeUpdate::Add(-1));
+        max.update(..2, RangeUpdate::Add(1));
+        assert_eq!(min.query(..2), i32::MAX - 1);
+        assert_eq!(max.query(..2), i32::MIN + 1);
+        min.update(1.., RangeUpdate::Assign(i32::MAX));
+        min.update(1.., RangeUpdate::Add(-2));
+        assert_eq!(min.query(1..), i32::MAX - 2);
+        assert_eq!(min.get(0), i32::MAX - 1);
+    }
+
+    #[test]
+    fn get_and_set_test() {
+        let mut tree: SegmentTree<Sum<i32>> = vec![1, 2, 3, 4].into();
+        tree.update(.., RangeUpdate::Add(1));
+        assert_eq!(tree.get(2), 4);
+        tree.set(2, 0);
+        assert_eq!(tree.get(2), 0);
+        assert_eq!(tree.query(..), 10);
+    }
+}
@@ -0,0 +1,477 @@
+#![deny(missing_docs)]
+
+use alloc::{vec, vec::Vec};
+use core::iter::FromIterator;
+use core::mem;
+use core::ops::{Index, IndexMut};
+
+/// A handle to a value in a `SlotMap`.
+/// A key stops working once its value is removed, even if the slot it
+/// pointed at is reused by a later insert.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Key {
+    index: u32,
+    generation: u32,
+}
+
+#[derive(Debug, Clone)]
+struct Slot {
+    // Bumped every time the slot is freed, so old keys stop matching. A slot
+    // that reaches `u32::MAX` is never used again.
+    generation: u32,
+    // The index into the dense arrays if occupied, else the next free slot.
+    next: u32,
+    occupied: bool,
+}
+
+/// A map that hands out generational `Key`s for the values inserted into it.
+/// Insertion, removal and lookup are O(1), and the values are kept
+/// contiguous so they can be iterated over as a slice.
+#[derive(Debug, Clone)]
+pub struct SlotMap<T> {
+    slots: Vec<Slot>,
+    keys: Vec<Key>,
+    values: Vec<T>,
+    free: Option<u32>,
+}
+
+impl<T> Default for SlotMap<T> {
+    fn default() -> Self {
+        SlotMap {
+            slots: vec![],
+            keys: vec![],
+            values: vec![],
+            free: None,
+        }
+    }
+}
+
+impl<T> SlotMap<T> {
+    /// Creates a new SlotMap.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new SlotMap with room for `capacity` values.
+    pub fn with_capacity(capacity: usize) -> Self {
+        SlotMap {
+            slots: Vec::with_capacity(capacity),
+            keys: Vec::with_capacity(capacity),
+            values: Vec::with_capacity(capacity),
+            free: None,
+        }
+    }
+
+    /// Returns the number of values in the map.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns `true` if the map has no values.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Inserts a value in O(1) time and returns its key.
+    /// ## Panics
+    /// Panics if the map would hold more than `u32::MAX` slots.
+    /// ## Examples
+    /// ```
+    /// # use stdlib_rs::collections::slot_map::SlotMap;
+    /// let mut map = SlotMap::new();
+    /// let a = map.insert("a");
+    /// let b = map.insert("b");
+    /// assert_eq!(map[a], "a");
+    /// assert_eq!(map.get(b), Some(&"b"));
+    /// ```
+    pub fn insert(&mut self, value: T) -> Key {
+        let dense = self.values.len() as u32;
+        let index = match self.free {
+            Some(index) => {
+                let slot = &mut self.slots[index as usize];
+                self.free = if slot.next == index {
+                    None
+                } else {
+                    Some(slot.next)
+                };
+                slot.next = dense;
+                slot.occupied = true;
+                index
+            }
+            None => {
+                assert!(self.slots.len() < u32::MAX as usize, "SlotMap is full");
+                self.slots.push(Slot {
+                    generation: 0,
+                    next: dense,
+                    occupied: true,
+                });
+                (self.slots.len() - 1) as u32
+            }
+        };
+        let key = Key {
+            index,
+            generation: self.slots[index as usize].generation,
+        };
+        self.keys.push(key);
+        self.values.push(value);
+        key
+    }
+
+    /// Removes the value for `key` in O(1) time and returns it, or `None`
+    /// if the key is stale.
+    /// ## Examples
+    /// ```
+    /// # use stdlib_rs::collections::slot_map::SlotMap;
+    /// let mut map = SlotMap::new();
+    /// let key = map.insert(1);
+    /// assert_eq!(map.remove(key), Some(1));
+    /// assert_eq!(map.remove(key), None);
+    /// let reused = map.insert(2);
+    /// assert_eq!(map.get(key), None);
+    /// assert_eq!(map.get(reused), Some(&2));
+    /// ```
+    pub fn remove(&mut self, key: Key) -> Option<T> {
+        let dense = self.dense_index(key)?;
+        let slot = &mut self.slots[key.index as usize];
+        slot.generation += 1;
+        slot.occupied = false;
+        // A slot whose generation has run out is retired rather than
+        // reused, since wrapping around would revive its oldest keys.
+        if slot.generation < u32::MAX {
+            // A free slot pointing at itself ends the free list.
+            slot.next = self.free.unwrap_or(key.index);
+            self.free = Some(key.index);
+        }
+
+        self.keys.swap_remove(dense);
+        if let Some(moved) = self.keys.get(dense) {
+            self.slots[moved.index as usize].next = dense as u32;
+        }
+        Some(self.values.swap_remove(dense))
+    }
+
+    /// Returns `true` if `key` refers to a value in the map.
+    pub fn contains_key(&self, key: Key) -> bool {
+        self.dense_index(key).is_some()
+    }
+
+    /// Returns a reference to the value for `key`, or `None` if the key is stale.
+    pub fn get(&self, key: Key) -> Option<&T> {
+        let dense = self.dense_index(key)?;
+        Some(&self.values[dense])
+    }
+
+    /// Returns a mutable reference to the value for `key`, or `None` if the
+    /// key is stale.
+    pub fn get_mut(&mut self, key: Key) -> Option<&mut T> {
+        let dense = self.dense_index(key)?;
+        Some(&mut self.values[dense])
+    }
+
+    /// Removes all the values, invalidating every key.
+    pub fn clear(&mut self) {
+        while let Some(&key) = self.keys.last() {
+            self.remove(key);
+        }
+    }
+
+    /// Returns the values as a contiguous slice, in no particular order.
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    /// Returns the values as a contiguous mutable slice, in no particular order.
+    pub fn values_mut(&mut self) -> &mut [T] {
+        &mut self.values
+    }
+
+    /// Returns the keys in the same order as `values`.
+    pub fn keys(&self) -> &[Key] {
+        &self.keys
+    }
+
+    /// Iterates over the keys and values, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (Key, &T)> {
+        self.keys.iter().copied().zip(self.values.iter())
+    }
+
+    /// Iterates over the keys and mutable values, in no particular order.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Key, &mut T)> {
+        self.keys.iter().copied().zip(self.values.iter_mut())
+    }
+
+    fn dense_index(&self, key: Key) -> Option<usize> {
+        let slot = self.slots.get(key.index as usize)?;
+        if slot.occupied && slot.generation == key.generation {
+            Some(slot.next as usize)
+        } else {
+            None
+        }
+    }
+}
+
+impl<T> Index<Key> for SlotMap<T> {
+    type Output = T;
+
+    /// ## Panics
+    /// Panics if the key is stale.
+    fn index(&self, key: Key) -> &T {
+        self.get(key).expect("stale SlotMap key")
+    }
+}
+
+impl<T> IndexMut<Key> for SlotMap<T> {
+    /// ## Panics
+    /// Panics if the key is stale.
+    fn index_mut(&mut self, key: Key) -> &mut T {
+        self.get_mut(key).expect("stale SlotMap key")
+    }
+}
+
+/// A map that attaches extra values to the keys of a `SlotMap`.
+/// Lookups are O(1) and ignore values stored for stale keys.
+#[derive(Debug, Clone)]
+pub struct SecondaryMap<V> {
+    slots: Vec<Option<(u32, V)>>,
+    len: usize,
+}
+
+impl<V> Default for SecondaryMap<V> {
+    fn default() -> Self {
+        SecondaryMap {
+            slots: vec![],
+            len: 0,
+        }
+    }
+}
+
+impl<V> SecondaryMap<V> {
+    /// Creates a new SecondaryMap.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of values in the map.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the map has no values.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Inserts `value` for `key`, returning the old value for the same key.
+    /// A value left behind by an older key in the same slot is dropped.
+    /// If the slot already holds a value for a newer key, `key` is stale:
+    /// the map is left unchanged and `None` is returned.
+    /// ## Examples
+    /// ```
+    /// # use stdlib_rs::collections::slot_map::{SecondaryMap, SlotMap};
+    /// let mut entities = SlotMap::new();
+    /// let mut names = SecondaryMap::new();
+    /// let player = entities.insert((0.0, 0.0));
+    /// names.insert(player, "player");
+    /// assert_eq!(names.get(player), Some(&"player"));
+    /// entities.remove(player);
+    /// let enemy = entities.insert((1.0, 1.0));
+    /// assert_eq!(names.get(enemy), None);
+    /// ```
+    pub fn insert(&mut self, key: Key, value: V) -> Option<V> {
+        let index = key.index as usize;
+        if index >= self.slots.len() {
+            self.slots.resize_with(index + 1, || None);
+        }
+        let slot = &mut self.slots[index];
+        match slot {
+            Some((generation, _)) if *generation > key.generation => None,
+            Some((generation, old)) if *generation == key.generation => {
+                Some(mem::replace(old, value))
+            }
+            Some(_) => {
+                *slot = Some((key.generation, value));
+                None
+            }
+            None => {
+                *slot = Some((key.generation, value));
+                self.len += 1;
+                None
+            }
+        }
+    }
+
+    /// Removes the value for `key` and returns it, if there is one.
+    pub fn remove(&mut self, key: Key) -> Option<V> {
+        let slot = self.slots.get_mut(key.index as usize)?;
+        match slot {
+            Some((generation, _)) if *generation == key.generation => {
+                self.len -= 1;
+                slot.take().map(|(_, value)| value)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if there is a value for `key`.
+    pub fn contains_key(&self, key: Key) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Returns a reference to the value for `key`.
+    pub fn get(&self, key: Key) -> Option<&V> {
+        match self.slots.get(key.index as usize)? {
+            Some((generation, value)) if *generation == key.generation => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns a mutable reference to the value for `key`.
+    pub fn get_mut(&mut self, key: Key) -> Option<&mut V> {
+        match self.slots.get_mut(key.index as usize)? {
+            Some((generation, value)) if *generation == key.generation => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Iterates over the keys and values, ordered by slot.
+    /// Values stored for keys that have since been removed from their
+    /// `SlotMap` are included; filter with `SlotMap::contains_key` if needed.
+    pub fn iter(&self) -> impl Iterator<Item = (Key, &V)> {
+        self.slots.iter().enumerate().filter_map(|(index, slot)| {
+            slot.as_ref().map(|(generation, value)| {
+                let key = Key {
+                    index: index as u32,
+                    generation: *generation,
+                };
+                (key, value)
+            })
+        })
+    }
+}
+
+impl<V> FromIterator<(Key, V)> for SecondaryMap<V> {
+    fn from_iter<I: IntoIterator<Item = (Key, V)>>(iter: I) -> Self {
+        let mut map = SecondaryMap::new();
+        for (key, value) in iter {
+            map.insert(key, value);
+        }
+        map
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_test() {
+        let map: SlotMap<i32> = SlotMap::new();
+        assert!(map.is_empty());
+        assert_eq!(map.values(), &[] as &[i32]);
+    }
+
+    #[test]
+    fn insert_remove_loop() {
+        let mut map = SlotMap::new();
+        let mut live = vec![];
+        for i in 0..100 {
+            live.push((map.insert(i), i));
+            if i % 3 == 0 {
+                let (key, value) = live.remove(live.len() / 2);
+                assert_eq!(map.remove(key), Some(value));
+                assert!(!map.contains_key(key));
+            }
+        }
+        assert_eq!(map.len(), live.len());
+        for &(key, value) in live.iter() {
+            assert_eq!(map[key], value);
+        }
+        let mut values = map.values().to_vec();
+        values.sort_unstable();
+        let mut expected: Vec<_> = live.iter().map(|&(_, value)| value).collect();
+        expected.sort_unstable();
+        assert_eq!(values, expected);
+    }
+
+    #[test]
+    fn exhausted_slot_is_retired() {
+        let mut map = SlotMap::new();
+        let first = map.insert(0);
+        map.remove(first);
+        map.slots[first.index as usize].generation = u32::MAX - 1;
+        let last = map.insert(1);
+        assert_eq!(last.index, first.index);
+        assert_eq!(map.remove(last), Some(1));
+        // The slot is out of generations, so new values go elsewhere.
+        let fresh = map.insert(2);
+        assert_ne!(fresh.index, first.index);
+        assert_eq!(map.slots.len(), 2);
+        assert_eq!(map.get(first), None);
+        assert_eq!(map.get(last), None);
+        assert_eq!(map[fresh], 2);
+    }
+
+    #[test]
+    fn stale_key_test() {
+        let mut map = SlotMap::new();
+        let old = map.insert('a');
+        map.remove(old);
+        let new = map.insert('b');
+        assert_eq!(old.index, new.index);
+        assert_eq!(map.get(old), None);
+        assert_eq!(map.get_mut(old), None);
+        assert_eq!(map.remove(old), None);
+        assert_eq!(map[new], 'b');
+    }
+
+    #[test]
+    fn iter_mut_test() {
+        let mut map: SlotMap<i32> = SlotMap::new();
+        let keys: Vec<Key> = (0..5).map(|i| map.insert(i)).collect();
+        for (_, value) in map.iter_mut() {
+            *value *= 10;
+        }
+        for (i, &key) in keys.iter().enumerate() {
+            assert_eq!(map[key], i as i32 * 10);
+        }
+        map.clear();
+        assert!(map.is_empty());
+        assert!(keys.iter().all(|&key| !map.contains_key(key)));
+    }
+
+    #[test]
+    fn secondary_map_test() {
+        let mut map = SlotMap::new();
+        let a = map.insert(1);
+        let b = map.insert(2);
+        let mut secondary = SecondaryMap::new();
+        assert_eq!(secondary.insert(a, "a"), None);
+        assert_eq!(secondary.insert(a, "A"), Some("a"));
+        secondary.insert(b, "b");
+        assert_eq!(secondary.len(), 2);
+        map.remove(a);
+        let c = map.insert(3);
+        assert_eq!(secondary.get(c), None);
+        assert_eq!(secondary.insert(c, "c"), None);
+        assert_eq!(secondary.get(a), None);
+        assert_eq!(secondary.remove(b), Some("b"));
+        assert_eq!(
+            secondary.iter().map(|(_, v)| *v).collect::<Vec<_>>(),
+            vec!["c"]
+        );
+    }
+
+    #[test]
+    fn secondary_map_rejects_stale_keys() {
+        let mut map = SlotMap::new();
+        let old = map.insert(1);
+        map.remove(old);
+        let new = map.insert(2);
+        let mut secondary = SecondaryMap::new();
+        secondary.insert(new, "new");
+        assert_eq!(secondary.insert(old, "old"), None);
+        assert_eq!(secondary.get(new), Some(&"new"));
+        assert_eq!(secondary.get(old), None);
+        assert_eq!(secondary.len(), 1);
+    }
+}
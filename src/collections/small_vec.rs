@@ -0,0 +1,412 @@
+#![deny(missing_docs)]
+
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::iter::FromIterator;
+use core::mem::{self, ManuallyDrop, MaybeUninit};
+use core::ops::{Deref, DerefMut};
+use core::ptr;
+use core::slice;
+
+use super::storage::Storage;
+
+/// Fixed-size arrays that a `SmallVec` can store its items in.
+pub trait Array {
+    /// The type of the array's items.
+    type Item;
+    /// The number of items the array holds.
+    const CAPACITY: usize;
+}
+
+impl<T, const N: usize> Array for [T; N] {
+    type Item = T;
+    const CAPACITY: usize = N;
+}
+
+enum Data<A: Array> {
+    // The first `len` items of the array are initialized.
+    Inline(MaybeUninit<A>, usize),
+    Heap(Vec<A::Item>),
+}
+
+/// A vector that stores up to `A::CAPACITY` items inline, and only moves
+/// them to the heap once more are pushed.
+/// ## Examples
+/// ```
+/// # use stdlib_rs::collections::small_vec::SmallVec;
+/// let mut v: SmallVec<[i32; 2]> = SmallVec::new();
+/// v.push(1);
+/// v.push(2);
+/// assert!(!v.spilled());
+/// v.push(3);
+/// assert!(v.spilled());
+/// assert_eq!(&v[..], &[1, 2, 3]);
+/// ```
+pub struct SmallVec<A: Array>(Data<A>);
+
+impl<A: Array> SmallVec<A> {
+    /// Creates a new, empty SmallVec that does not allocate.
+    pub fn new() -> Self {
+        SmallVec(Data::Inline(MaybeUninit::uninit(), 0))
+    }
+
+    /// Creates a new SmallVec with room for `capacity` items, which only
+    /// allocates if `capacity` does not fit inline.
+    pub fn with_capacity(capacity: usize) -> Self {
+        if capacity > A::CAPACITY {
+            SmallVec(Data::Heap(Vec::with_capacity(capacity)))
+        } else {
+            Self::new()
+        }
+    }
+
+    /// Returns `true` if the items have moved to the heap.
+    pub fn spilled(&self) -> bool {
+        matches!(self.0, Data::Heap(_))
+    }
+
+    /// Returns the number of items the SmallVec can hold without allocating.
+    pub fn capacity(&self) -> usize {
+        match &self.0 {
+            Data::Inline(..) => A::CAPACITY,
+            Data::Heap(vec) => vec.capacity(),
+        }
+    }
+
+    /// Adds an item to the end, moving to the heap if the inline array is full.
+    pub fn push(&mut self, item: A::Item) {
+        if let Data::Inline(_, len) = self.0 {
+            if len == A::CAPACITY {
+                self.spill(len + 1);
+            }
+        }
+        match &mut self.0 {
+            Data::Inline(array, len) => {
+                unsafe { ptr::write(first(array).add(*len), item) };
+                *len += 1;
+            }
+            Data::Heap(vec) => vec.push(item),
+        }
+    }
+
+    /// Removes the last item and returns it, or `None` if it is empty.
+    pub fn pop(&mut self) -> Option<A::Item> {
+        match &mut self.0 {
+            Data::Inline(array, len) => {
+                if *len == 0 {
+                    return None;
+                }
+                *len -= 1;
+                Some(unsafe { ptr::read(first(array).add(*len)) })
+            }
+            Data::Heap(vec) => vec.pop(),
+        }
+    }
+
+    /// Shortens the SmallVec to `len` items, dropping the rest.
+    pub fn truncate(&mut self, len: usize) {
+        while self.len() > len {
+            self.pop();
+        }
+    }
+
+    /// Removes all the items.
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    /// Reserves room for at least `additional` more items, moving to the
+    /// heap if they do not fit inline.
+    pub fn reserve(&mut self, additional: usize) {
+        match &mut self.0 {
+            Data::Inline(_, len) => {
+                let needed = *len + additional;
+                if needed > A::CAPACITY {
+                    self.spill(needed);
+                }
+            }
+            Data::Heap(vec) => vec.reserve(additional),
+        }
+    }
+
+    /// Converts the SmallVec into a `Vec`, allocating if it was inline.
+    pub fn into_vec(mut self) -> Vec<A::Item> {
+        match mem::replace(&mut self.0, Data::Inline(MaybeUninit::uninit(), 0)) {
+            Data::Inline(array, len) => {
+                let mut vec = Vec::with_capacity(len);
+                let mut array = array;
+                unsafe {
+                    ptr::copy_nonoverlapping(first(&mut array), vec.as_mut_ptr(), len);
+                    vec.set_len(len);
+                }
+                vec
+            }
+            Data::Heap(vec) => vec,
+        }
+    }
+
+    /// Moves the inline items into a `Vec` with room for `capacity` items.
+    fn spill(&mut self, capacity: usize) {
+        if let Data::Inline(array, len) = &mut self.0 {
+            let mut vec = Vec::with_capacity(capacity);
+            unsafe {
+                ptr::copy_nonoverlapping(first(array), vec.as_mut_ptr(), *len);
+                vec.set_len(*len);
+            }
+            // The items now belong to the vector.
+            *len = 0;
+            self.0 = Data::Heap(vec);
+        }
+    }
+}
+
+fn first<A: Array>(array: &mut MaybeUninit<A>) -> *mut A::Item {
+    array.as_mut_ptr() as *mut A::Item
+}
+
+impl<A: Array> Drop for SmallVec<A> {
+    fn drop(&mut self) {
+        if let Data::Inline(array, len) = &mut self.0 {
+            unsafe { ptr::drop_in_place(ptr::slice_from_raw_parts_mut(first(array), *len)) };
+        }
+    }
+}
+
+impl<A: Array> Default for SmallVec<A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<A: Array> Deref for SmallVec<A> {
+    type Target = [A::Item];
+
+    fn deref(&self) -> &[A::Item] {
+        match &self.0 {
+            Data::Inline(array, len) => unsafe {
+                slice::from_raw_parts(array.as_ptr() as *const A::Item, *len)
+            },
+            Data::Heap(vec) => vec,
+        }
+    }
+}
+
+impl<A: Array> DerefMut for SmallVec<A> {
+    fn deref_mut(&mut self) -> &mut [A::Item] {
+        match &mut self.0 {
+            Data::Inline(array, len) => unsafe { slice::from_raw_parts_mut(first(array), *len) },
+            Data::Heap(vec) => vec,
+        }
+    }
+}
+
+impl<A: Array> Clone for SmallVec<A>
+where
+    A::Item: Clone,
+{
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<A: Array> fmt::Debug for SmallVec<A>
+where
+    A::Item: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<A: Array> PartialEq for SmallVec<A>
+where
+    A::Item: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl<A: Array> Eq for SmallVec<A> where A::Item: Eq {}
+
+impl<A: Array> PartialOrd for SmallVec<A>
+where
+    A::Item: PartialOrd,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self[..].partial_cmp(&other[..])
+    }
+}
+
+impl<A: Array> Hash for SmallVec<A>
+where
+    A::Item: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self[..].hash(state)
+    }
+}
+
+impl<A: Array> Extend<A::Item> for SmallVec<A> {
+    fn extend<I: IntoIterator<Item = A::Item>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for item in iter {
+            self.push(item);
+        }
+    }
+}
+
+impl<A: Array> FromIterator<A::Item> for SmallVec<A> {
+    fn from_iter<I: IntoIterator<Item = A::Item>>(iter: I) -> Self {
+        let mut vec = SmallVec::new();
+        vec.extend(iter);
+        vec
+    }
+}
+
+impl<A: Array> IntoIterator for SmallVec<A> {
+    type Item = A::Item;
+    type IntoIter = IntoIter<A>;
+
+    fn into_iter(self) -> IntoIter<A> {
+        let mut this = ManuallyDrop::new(self);
+        match mem::replace(&mut this.0, Data::Inline(MaybeUninit::uninit(), 0)) {
+            Data::Inline(array, len) => IntoIter(Iter::Inline(array, 0..len)),
+            Data::Heap(vec) => IntoIter(Iter::Heap(vec.into_iter())),
+        }
+    }
+}
+
+enum Iter<A: Array> {
+    // The items in the range have not been yielded yet.
+    Inline(MaybeUninit<A>, core::ops::Range<usize>),
+    Heap(alloc::vec::IntoIter<A::Item>),
+}
+
+/// An iterator that moves the items out of a `SmallVec`.
+pub struct IntoIter<A: Array>(Iter<A>);
+
+impl<A: Array> Iterator for IntoIter<A> {
+    type Item = A::Item;
+
+    fn next(&mut self) -> Option<A::Item> {
+        match &mut self.0 {
+            Iter::Inline(array, range) => {
+                let index = range.next()?;
+                Some(unsafe { ptr::read(first(array).add(index)) })
+            }
+            Iter::Heap(iter) => iter.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match &self.0 {
+            Iter::Inline(_, range) => range.size_hint(),
+            Iter::Heap(iter) => iter.size_hint(),
+        }
+    }
+}
+
+impl<A: Array> DoubleEndedIterator for IntoIter<A> {
+    fn next_back(&mut self) -> Option<A::Item> {
+        match &mut self.0 {
+            Iter::Inline(array, range) => {
+                let index = range.next_back()?;
+                Some(unsafe { ptr::read(first(array).add(index)) })
+            }
+            Iter::Heap(iter) => iter.next_back(),
+        }
+    }
+}
+
+impl<A: Array> Drop for IntoIter<A> {
+    fn drop(&mut self) {
+        for _ in self {}
+    }
+}
+
+impl<A: Array> Storage<A::Item> for SmallVec<A> {
+    fn push(&mut self, item: A::Item) {
+        SmallVec::push(self, item)
+    }
+
+    fn pop(&mut self) -> Option<A::Item> {
+        SmallVec::pop(self)
+    }
+
+    fn clear(&mut self) {
+        SmallVec::clear(self)
+    }
+
+    fn capacity(&self) -> usize {
+        SmallVec::capacity(self)
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        SmallVec::reserve(self, additional)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::rc::Rc;
+
+    #[test]
+    fn inline_then_spill() {
+        let mut v: SmallVec<[String; 3]> = SmallVec::new();
+        for i in 0..3 {
+            v.push(i.to_string());
+        }
+        assert!(!v.spilled());
+        assert_eq!(v.capacity(), 3);
+        v.push("3".to_string());
+        assert!(v.spilled());
+        assert_eq!(v.pop(), Some("3".to_string()));
+        assert_eq!(v.into_vec(), vec!["0", "1", "2"]);
+    }
+
+    #[test]
+    fn drops_every_item() {
+        let counter = Rc::new(());
+        {
+            let mut v: SmallVec<[Rc<()>; 4]> = SmallVec::new();
+            v.extend((0..3).map(|_| counter.clone()));
+            let mut iter = v.clone().into_iter();
+            iter.next();
+            assert_eq!(Rc::strong_count(&counter), 6);
+        }
+        assert_eq!(Rc::strong_count(&counter), 1);
+    }
+
+    #[test]
+    fn into_iter_both_ends() {
+        let v: SmallVec<[i32; 8]> = (1..=5).collect();
+        let mut iter = v.into_iter();
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(iter.next_back(), Some(5));
+        assert_eq!(iter.collect::<Vec<_>>(), vec![2, 3, 4]);
+    }
+
+    #[test]
+    fn reserve_spills() {
+        let mut v: SmallVec<[u8; 4]> = SmallVec::with_capacity(2);
+        assert!(!v.spilled());
+        v.push(1);
+        v.reserve(10);
+        assert!(v.spilled());
+        assert!(v.capacity() >= 11);
+        assert_eq!(&v[..], &[1]);
+    }
+
+    #[test]
+    fn zero_capacity() {
+        let mut v: SmallVec<[i32; 0]> = SmallVec::new();
+        v.push(1);
+        assert!(v.spilled());
+        assert_eq!(v, (1..=1).collect());
+    }
+}
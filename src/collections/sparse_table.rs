@@ -0,0 +1,250 @@
+#![deny(missing_docs)]
+
+use alloc::{vec, vec::Vec};
+use core::ops::RangeBounds;
+
+use super::to_bounds;
+
+/// A table of precomputed answers for every power-of-two sized window of a
+/// static array, which answers range queries in O(1) time.
+///
+/// `F` must be associative and idempotent (`f(x, x) == x`), like `min`,
+/// `max` or `gcd`, since queries combine two overlapping windows.
+pub struct SparseTable<T, F> {
+    levels: Vec<Vec<T>>,
+    op: F,
+}
+
+impl<T, F> SparseTable<T, F>
+where
+    T: Clone,
+    F: Fn(&T, &T) -> T,
+{
+    /// Builds a SparseTable over `items` in O(n log n) time.
+    /// ## Examples
+    /// ```
+    /// # use stdlib_rs::collections::sparse_table::SparseTable;
+    /// let table = SparseTable::new(&[5, 3, 8, 1, 9], |a: &i32, b: &i32| *a.min(b));
+    /// assert_eq!(table.query(0..3), Some(3));
+    /// assert_eq!(table.query(2..), Some(1));
+    /// assert_eq!(table.query(2..2), None);
+    /// ```
+    pub fn new(items: &[T], op: F) -> Self {
+        let mut levels = vec![items.to_vec()];
+        let mut width = 1;
+        while 2 * width <= items.len() {
+            let prev = levels.last().unwrap();
+            let next = (0..=items.len() - 2 * width)
+                .map(|i| op(&prev[i], &prev[i + width]))
+                .collect();
+            levels.push(next);
+            width *= 2;
+        }
+        SparseTable { levels, op }
+    }
+
+    /// Returns the number of elements in the table.
+    pub fn len(&self) -> usize {
+        self.levels[0].len()
+    }
+
+    /// Returns `true` if the table has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.levels[0].is_empty()
+    }
+
+    /// Combines the elements in `range` in O(1) time.
+    /// If the range is empty, returns `None`.
+    /// ## Panics
+    /// Panics if the range is out of bounds.
+    pub fn query<R: RangeBounds<usize>>(&self, range: R) -> Option<T> {
+        let (start, end) = to_bounds(range, self.len());
+        if start == end {
+            return None;
+        }
+        let level = log2(end - start);
+        let row = &self.levels[level];
+        Some((self.op)(&row[start], &row[end - (1 << level)]))
+    }
+}
+
+fn log2(n: usize) -> usize {
+    (usize::BITS - 1 - n.leading_zeros()) as usize
+}
+
+type DepthNode = (usize, usize);
+
+/// Answers lowest common ancestor queries on a rooted tree in O(1) time,
+/// using a `SparseTable` over the depths of an Euler tour of the tree.
+pub struct Lca {
+    first: Vec<Option<usize>>,
+    depth: Vec<usize>,
+    tour: SparseTable<DepthNode, fn(&DepthNode, &DepthNode) -> DepthNode>,
+}
+
+impl Lca {
+    /// Builds an Lca from a tree given as adjacency lists, where
+    /// `adjacency[u]` holds the neighbors of node `u`, in O(n log n) time.
+    /// Edges may be listed in one or both directions.
+    /// ## Panics
+    /// Panics if `root` is not a node of the tree.
+    /// ## Examples
+    /// ```
+    /// # use stdlib_rs::collections::sparse_table::Lca;
+    /// //     0
+    /// //    / \
+    /// //   1   2
+    /// //  / \
+    /// // 3   4
+    /// let lca = Lca::new(&[vec![1, 2], vec![3, 4], vec![], vec![], vec![]], 0);
+    /// assert_eq!(lca.lca(3, 4), Some(1));
+    /// assert_eq!(lca.lca(3, 2), Some(0));
+    /// assert_eq!(lca.distance(3, 2), Some(3));
+    /// ```
+    pub fn new(adjacency: &[Vec<usize>], root: usize) -> Self {
+        assert!(root < adjacency.len(), "root {} is not in the tree", root);
+        let mut first = vec![None; adjacency.len()];
+        let mut depth = vec![0; adjacency.len()];
+        let mut tour = vec![];
+        // Each frame is a node and the index of the next neighbor to visit.
+        let mut stack = vec![(root, 0)];
+        first[root] = Some(0);
+        tour.push((0, root));
+        while let Some(&mut (node, ref mut next)) = stack.last_mut() {
+            if let Some(&child) = adjacency[node].get(*next) {
+                *next += 1;
+                if first[child].is_none() {
+                    depth[child] = depth[node] + 1;
+                    first[child] = Some(tour.len());
+                    tour.push((depth[child], child));
+                    stack.push((child, 0));
+                }
+            } else {
+                stack.pop();
+                if let Some(&(parent, _)) = stack.last() {
+                    tour.push((depth[parent], parent));
+                }
+            }
+        }
+        let min: fn(&DepthNode, &DepthNode) -> DepthNode = |a, b| *a.min(b);
+        Lca {
+            first,
+            depth,
+            tour: SparseTable::new(&tour, min),
+        }
+    }
+
+    /// Returns the lowest common ancestor of `u` and `v` in O(1) time.
+    /// If either node is not reachable from the root, returns `None`.
+    /// ## Panics
+    /// Panics if `u` or `v` is not a node of the tree.
+    pub fn lca(&self, u: usize, v: usize) -> Option<usize> {
+        self.check(u);
+        self.check(v);
+        let (a, b) = (self.first[u]?, self.first[v]?);
+        let (start, end) = if a <= b { (a, b) } else { (b, a) };
+        self.tour.query(start..=end).map(|(_, node)| node)
+    }
+
+    /// Returns the depth of `u`, where the root has depth 0.
+    /// If `u` is not reachable from the root, returns `None`.
+    /// ## Panics
+    /// Panics if `u` is not a node of the tree.
+    pub fn depth(&self, u: usize) -> Option<usize> {
+        self.check(u);
+        self.first[u].map(|_| self.depth[u])
+    }
+
+    /// Returns the number of edges on the path between `u` and `v`.
+    /// If either node is not reachable from the root, returns `None`.
+    /// ## Panics
+    /// Panics if `u` or `v` is not a node of the tree.
+    pub fn distance(&self, u: usize, v: usize) -> Option<usize> {
+        let ancestor = self.lca(u, v)?;
+        Some(self.depth[u] + self.depth[v] - 2 * self.depth[ancestor])
+    }
+
+    fn check(&self, node: usize) {
+        assert!(node < self.first.len(), "node {} is not in the tree", node);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gcd(a: &u64, b: &u64) -> u64 {
+        if *b == 0 {
+            *a
+        } else {
+            gcd(b, &(a % b))
+        }
+    }
+
+    #[test]
+    fn empty_test() {
+        let table = SparseTable::new(&[], |a: &i32, b: &i32| *a.max(b));
+        assert!(table.is_empty());
+        assert_eq!(table.query(..), None);
+    }
+
+    #[test]
+    fn min_max_match_naive() {
+        let items: Vec<i32> = (0..37).map(|i| (i * 17) % 23 - 11).collect();
+        let min = SparseTable::new(&items, |a: &i32, b: &i32| *a.min(b));
+        let max = SparseTable::new(&items, |a: &i32, b: &i32| *a.max(b));
+        for start in 0..items.len() {
+            for end in start + 1..=items.len() {
+                assert_eq!(
+                    min.query(start..end),
+                    items[start..end].iter().min().copied()
+                );
+                assert_eq!(
+                    max.query(start..end),
+                    items[start..end].iter().max().copied()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn gcd_test() {
+        let table = SparseTable::new(&[12, 18, 24, 7, 14], gcd);
+        assert_eq!(table.query(0..3), Some(6));
+        assert_eq!(table.query(3..), Some(7));
+        assert_eq!(table.query(..), Some(1));
+    }
+
+    #[test]
+    fn lca_test() {
+        // Undirected edges of:
+        //       0
+        //     / | \
+        //    1  2  3
+        //   /|     |
+        //  4 5     6
+        //          |
+        //          7
+        let edges = [(0, 1), (0, 2), (0, 3), (1, 4), (1, 5), (3, 6), (6, 7)];
+        let mut adjacency = vec![vec![]; 9];
+        for &(u, v) in edges.iter() {
+            adjacency[u].push(v);
+            adjacency[v].push(u);
+        }
+        let lca = Lca::new(&adjacency, 0);
+        assert_eq!(lca.lca(4, 5), Some(1));
+        assert_eq!(lca.lca(4, 7), Some(0));
+        assert_eq!(lca.lca(6, 7), Some(6));
+        assert_eq!(lca.lca(2, 2), Some(2));
+        assert_eq!(lca.depth(7), Some(3));
+        assert_eq!(lca.distance(5, 7), Some(5));
+        assert_eq!(lca.lca(8, 0), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "node 5 is not in the tree")]
+    fn lca_out_of_bounds() {
+        let lca = Lca::new(&[vec![1, 2], vec![3, 4], vec![], vec![], vec![]], 0);
+        lca.lca(0, 5);
+    }
+}
@@ -1,34 +1,88 @@
 #![deny(missing_docs)]
-use std::collections::VecDeque;
+use alloc::collections::VecDeque;
+use core::marker::PhantomData;
+use core::mem;
+
+use super::alloc_vec::AllocVec;
+use super::allocator::Allocator;
+use super::queue_with_stack::Queue;
+use super::storage::QueueStorage;
 
 /// A Stack implemented with two queues.
+/// The queues are `VecDeque`s unless another `QueueStorage` is chosen with
+/// `Q`, such as a `Queue` from `new_in`.
 #[derive(Default, Debug, PartialEq, Eq, PartialOrd, Ord)]
-pub struct Stack<T>(VecDeque<T>, VecDeque<T>);
+pub struct Stack<T, Q = VecDeque<T>>(Q, Q, PhantomData<T>);
 
 impl<T> Stack<T> {
     /// Creates a new Stack.
     pub fn new() -> Stack<T> {
-        Stack(VecDeque::default(), VecDeque::default())
+        Stack(VecDeque::default(), VecDeque::default(), PhantomData)
+    }
+}
+
+impl<T, A: Allocator + Clone> Stack<T, Queue<T, AllocVec<T, A>>> {
+    /// Creates a new Stack whose queues allocate from `alloc`.
+    /// ## Examples
+    /// ```
+    /// # use stdlib_rs::collections::arena::Bump;
+    /// # use stdlib_rs::collections::stack_with_queue::Stack;
+    /// let bump = Bump::new();
+    /// let mut stack = Stack::new_in(&bump);
+    /// stack.push(1);
+    /// stack.push(2);
+    /// assert_eq!(stack.pop(), Some(2));
+    /// ```
+    pub fn new_in(alloc: A) -> Self {
+        Stack(
+            Queue::new_in(alloc.clone()),
+            Queue::new_in(alloc),
+            PhantomData,
+        )
+    }
+
+    /// Creates a new Stack with room for `capacity` items in each queue,
+    /// allocated from `alloc`.
+    pub fn with_capacity_in(capacity: usize, alloc: A) -> Self {
+        Stack(
+            Queue::with_capacity_in(capacity, alloc.clone()),
+            Queue::with_capacity_in(capacity, alloc),
+            PhantomData,
+        )
     }
+}
+
+impl<T, Q: QueueStorage<T>> Stack<T, Q> {
     /// Add an item to the top of the stack in O(1) time.
     pub fn push(&mut self, item: T) {
         self.0.push_back(item);
     }
-    /// Remove and return the top item of the stack in O(1) time.
+    /// Remove and return the top item of the stack in O(n) time.
     pub fn pop(&mut self) -> Option<T> {
-        // pop all the items from the first queue
-        // push to the second queue
-        // pop from the second queue.
+        // move all but the last item from the first queue to the second,
+        // pop the last item, then swap the queues.
         self.move_to_second();
-        self.1.pop_front()
+        let item = self.0.pop_front();
+        mem::swap(&mut self.0, &mut self.1);
+        item
+    }
+
+    /// Returns `true` if the stack has no items.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the number of items in the stack.
+    pub fn len(&self) -> usize {
+        self.0.len()
     }
 
     fn move_to_second(&mut self) {
-        let mut temp = VecDeque::new();
-        while let Some(item) = self.0.pop_front() {
-            temp.push_back(item);
+        while self.0.len() > 1 {
+            if let Some(item) = self.0.pop_front() {
+                self.1.push_back(item);
+            }
         }
-        self.1 = temp.into_iter().rev().collect();
     }
 }
 
@@ -50,7 +104,7 @@ impl<T> Stack<T> {
 #[macro_export]
 macro_rules! stack [
     ($($e:expr),*) => ({
-        let mut _temp  = Stack::default();
+        let mut _temp  = Stack::new();
         $(_temp.push($e);)*
         _temp
     })
@@ -58,6 +112,7 @@ macro_rules! stack [
 
 #[cfg(test)]
 mod tests {
+    use super::super::arena::Bump;
     use super::*;
 
     #[test]
@@ -72,4 +127,32 @@ mod tests {
         let mut stack = stack![1, 2, 3];
         assert_eq!(stack.pop(), Some(3));
     }
+
+    #[test]
+    fn interleaved_push_pop() {
+        let mut stack = stack![1, 2, 3];
+        assert_eq!(stack.pop(), Some(3));
+        stack.push(4);
+        assert_eq!(stack.len(), 3);
+        assert_eq!(stack.pop(), Some(4));
+        assert_eq!(stack.pop(), Some(2));
+        assert_eq!(stack.pop(), Some(1));
+        assert_eq!(stack.pop(), None);
+        assert!(stack.is_empty());
+    }
+
+    #[test]
+    fn allocates_from_bump() {
+        let bump = Bump::new();
+        let mut stack = Stack::new_in(&bump);
+        for i in 0..10 {
+            stack.push(i);
+        }
+        assert!(bump.allocated_bytes() > 0);
+        assert_eq!(stack.pop(), Some(9));
+        stack.push(10);
+        assert_eq!(stack.len(), 10);
+        assert_eq!(stack.pop(), Some(10));
+        assert_eq!(stack.pop(), Some(8));
+    }
 }
@@ -0,0 +1,86 @@
+#![deny(missing_docs)]
+
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+use core::ops::{Deref, DerefMut};
+
+/// Contiguous, growable storage that the stacks and queues in this crate
+/// can keep their items in.
+/// Implemented by `Vec`, by `SmallVec`, which avoids allocating while
+/// there are only a few items, and by `AllocVec`, which allocates from a
+/// chosen `Allocator`.
+pub trait Storage<T>: Deref<Target = [T]> + DerefMut + Extend<T> + IntoIterator<Item = T> {
+    /// Adds an item to the end.
+    fn push(&mut self, item: T);
+
+    /// Removes the last item and returns it, or `None` if it is empty.
+    fn pop(&mut self) -> Option<T>;
+
+    /// Removes all the items.
+    fn clear(&mut self);
+
+    /// Returns the number of items the storage can hold without reallocating.
+    fn capacity(&self) -> usize;
+
+    /// Reserves room for at least `additional` more items.
+    fn reserve(&mut self, additional: usize);
+}
+
+impl<T> Storage<T> for Vec<T> {
+    fn push(&mut self, item: T) {
+        Vec::push(self, item)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        Vec::pop(self)
+    }
+
+    fn clear(&mut self) {
+        Vec::clear(self)
+    }
+
+    fn capacity(&self) -> usize {
+        Vec::capacity(self)
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        Vec::reserve(self, additional)
+    }
+}
+
+/// First-in, first-out storage that the stacks built from queues can keep
+/// their items in.
+/// Implemented by `VecDeque` and by this crate's `Queue`.
+pub trait QueueStorage<T> {
+    /// Adds an item to the back.
+    fn push_back(&mut self, item: T);
+
+    /// Removes the front item and returns it, or `None` if it is empty.
+    fn pop_front(&mut self) -> Option<T>;
+
+    /// Returns the number of items.
+    fn len(&self) -> usize;
+
+    /// Returns `true` if there are no items.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T> QueueStorage<T> for VecDeque<T> {
+    fn push_back(&mut self, item: T) {
+        VecDeque::push_back(self, item)
+    }
+
+    fn pop_front(&mut self) -> Option<T> {
+        VecDeque::pop_front(self)
+    }
+
+    fn len(&self) -> usize {
+        VecDeque::len(self)
+    }
+
+    fn is_empty(&self) -> bool {
+        VecDeque::is_empty(self)
+    }
+}
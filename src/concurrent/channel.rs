@@ -0,0 +1,283 @@
+#![deny(missing_docs)]
+
+use std::error::Error;
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::atomic::{fence, AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Mutex, PoisonError};
+use std::thread::{self, Thread};
+use std::time::{Duration, Instant};
+
+/// Returned by `send` when the receiver has been dropped, with the value
+/// that could not be sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SendError<T>(pub T);
+
+/// Returned by `try_send` when a value cannot be sent right away.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrySendError<T> {
+    /// The channel is full.
+    Full(T),
+    /// The receiver has been dropped.
+    Disconnected(T),
+}
+
+/// Returned by `recv` when the channel is empty and every sender has been
+/// dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecvError;
+
+/// Returned by `try_recv` when no value can be received right away.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TryRecvError {
+    /// The channel is empty, but a sender may still send.
+    Empty,
+    /// The channel is empty and every sender has been dropped.
+    Disconnected,
+}
+
+/// Returned by `recv_timeout` when no value arrives in time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecvTimeoutError {
+    /// The timeout passed with the channel still empty.
+    Timeout,
+    /// The channel is empty and every sender has been dropped.
+    Disconnected,
+}
+
+impl<T> fmt::Display for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "sending on a disconnected channel")
+    }
+}
+
+impl<T> fmt::Display for TrySendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrySendError::Full(_) => write!(f, "sending on a full channel"),
+            TrySendError::Disconnected(_) => write!(f, "sending on a disconnected channel"),
+        }
+    }
+}
+
+impl fmt::Display for RecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "receiving on a disconnected channel")
+    }
+}
+
+impl fmt::Display for TryRecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TryRecvError::Empty => write!(f, "receiving on an empty channel"),
+            TryRecvError::Disconnected => write!(f, "receiving on a disconnected channel"),
+        }
+    }
+}
+
+impl fmt::Display for RecvTimeoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecvTimeoutError::Timeout => write!(f, "timed out waiting on a channel"),
+            RecvTimeoutError::Disconnected => write!(f, "receiving on a disconnected channel"),
+        }
+    }
+}
+
+impl<T: fmt::Debug> Error for SendError<T> {}
+impl<T: fmt::Debug> Error for TrySendError<T> {}
+impl Error for RecvError {}
+impl Error for TryRecvError {}
+impl Error for RecvTimeoutError {}
+
+/// A bounded ring buffer that a channel keeps its values in.
+pub(crate) trait Ring<T> {
+    fn with_capacity(capacity: usize) -> Self;
+
+    /// Adds a value to the back, or gives it back if the ring is full.
+    /// ## Safety
+    /// Callers must respect the ring's limit on concurrent producers.
+    unsafe fn push(&self, value: T) -> Result<(), T>;
+
+    /// Removes the value at the front, if there is one.
+    /// ## Safety
+    /// Only one thread may pop at a time.
+    unsafe fn pop(&self) -> Option<T>;
+
+    fn is_empty(&self) -> bool;
+
+    fn is_full(&self) -> bool;
+}
+
+/// Threads parked until the other side of a channel makes progress.
+struct WaitList {
+    count: AtomicUsize,
+    threads: Mutex<Vec<Thread>>,
+}
+
+impl WaitList {
+    fn new() -> WaitList {
+        WaitList {
+            count: AtomicUsize::new(0),
+            threads: Mutex::new(vec![]),
+        }
+    }
+
+    /// Parks the current thread until it is notified or `deadline` passes,
+    /// unless `ready` returns `true` once the thread has been registered.
+    fn wait(&self, deadline: Option<Instant>, ready: impl FnOnce() -> bool) {
+        let current = thread::current();
+        self.lock().push(current.clone());
+        self.count.fetch_add(1, Ordering::SeqCst);
+        // Pairs with the fence in `notify_all`: either this thread sees the
+        // progress in `ready`, or the notifier sees this thread waiting.
+        fence(Ordering::SeqCst);
+        if !ready() {
+            match deadline {
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if deadline > now {
+                        thread::park_timeout(deadline - now);
+                    }
+                }
+                None => thread::park(),
+            }
+        }
+        self.count.fetch_sub(1, Ordering::SeqCst);
+        self.lock().retain(|thread| thread.id() != current.id());
+    }
+
+    fn notify_all(&self) {
+        fence(Ordering::SeqCst);
+        if self.count.load(Ordering::Relaxed) > 0 {
+            for thread in self.lock().iter() {
+                thread.unpark();
+            }
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<Thread>> {
+        self.threads.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// The state shared by the two sides of a channel.
+/// Blocking calls park the thread instead of spinning, and waiting threads
+/// are only tracked while they wait, so sending and receiving stay lock-free
+/// when neither side has to block.
+pub(crate) struct Chan<T, R> {
+    ring: R,
+    senders: AtomicUsize,
+    receiver: AtomicBool,
+    receiving: WaitList,
+    sending: WaitList,
+    // The ring decides whether the channel is `Send` and `Sync`.
+    _marker: PhantomData<fn(T) -> T>,
+}
+
+impl<T, R: Ring<T>> Chan<T, R> {
+    pub(crate) fn new(capacity: usize) -> Chan<T, R> {
+        assert!(capacity > 0, "channel capacity must be positive");
+        Chan {
+            ring: R::with_capacity(capacity),
+            senders: AtomicUsize::new(1),
+            receiver: AtomicBool::new(true),
+            receiving: WaitList::new(),
+            sending: WaitList::new(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// ## Safety
+    /// Callers must respect the ring's limit on concurrent producers.
+    pub(crate) unsafe fn try_send(&self, value: T) -> Result<(), TrySendError<T>> {
+        if !self.receiver.load(Ordering::SeqCst) {
+            return Err(TrySendError::Disconnected(value));
+        }
+        match self.ring.push(value) {
+            Ok(()) => {
+                self.receiving.notify_all();
+                Ok(())
+            }
+            Err(value) => Err(TrySendError::Full(value)),
+        }
+    }
+
+    /// ## Safety
+    /// Callers must respect the ring's limit on concurrent producers.
+    pub(crate) unsafe fn send(&self, mut value: T) -> Result<(), SendError<T>> {
+        loop {
+            match self.try_send(value) {
+                Ok(()) => return Ok(()),
+                Err(TrySendError::Disconnected(value)) => return Err(SendError(value)),
+                Err(TrySendError::Full(back)) => value = back,
+            }
+            self.sending.wait(None, || {
+                !self.ring.is_full() || !self.receiver.load(Ordering::SeqCst)
+            });
+        }
+    }
+
+    /// ## Safety
+    /// Only one thread may receive at a time.
+    pub(crate) unsafe fn try_recv(&self) -> Result<T, TryRecvError> {
+        if let Some(value) = self.ring.pop() {
+            self.sending.notify_all();
+            return Ok(value);
+        }
+        if self.senders.load(Ordering::SeqCst) > 0 {
+            return Err(TryRecvError::Empty);
+        }
+        // A sender may have sent a last value just before dropping.
+        self.ring.pop().ok_or(TryRecvError::Disconnected)
+    }
+
+    /// ## Safety
+    /// Only one thread may receive at a time.
+    pub(crate) unsafe fn recv_deadline(
+        &self,
+        deadline: Option<Instant>,
+    ) -> Result<T, RecvTimeoutError> {
+        loop {
+            match self.try_recv() {
+                Ok(value) => return Ok(value),
+                Err(TryRecvError::Disconnected) => return Err(RecvTimeoutError::Disconnected),
+                Err(TryRecvError::Empty) => {}
+            }
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                return Err(RecvTimeoutError::Timeout);
+            }
+            self.receiving.wait(deadline, || {
+                !self.ring.is_empty() || self.senders.load(Ordering::SeqCst) == 0
+            });
+        }
+    }
+
+    /// ## Safety
+    /// Only one thread may receive at a time.
+    pub(crate) unsafe fn recv(&self) -> Result<T, RecvError> {
+        self.recv_deadline(None).map_err(|_| RecvError)
+    }
+
+    /// ## Safety
+    /// Only one thread may receive at a time.
+    pub(crate) unsafe fn recv_timeout(&self, timeout: Duration) -> Result<T, RecvTimeoutError> {
+        // A deadline too far off to represent is the same as none.
+        self.recv_deadline(Instant::now().checked_add(timeout))
+    }
+
+    pub(crate) fn add_sender(&self) {
+        self.senders.fetch_add(1, Ordering::SeqCst);
+    }
+
+    pub(crate) fn drop_sender(&self) {
+        if self.senders.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.receiving.notify_all();
+        }
+    }
+
+    pub(crate) fn drop_receiver(&self) {
+        self.receiver.store(false, Ordering::SeqCst);
+        self.sending.notify_all();
+    }
+}
@@ -0,0 +1,315 @@
+#![deny(missing_docs)]
+
+use std::cell::{Cell, RefCell};
+use std::marker::PhantomData;
+use std::mem;
+use std::ptr;
+use std::sync::atomic::{fence, AtomicBool, AtomicPtr, AtomicUsize, Ordering};
+use std::sync::{Mutex, PoisonError};
+
+/// How many pins a thread makes between attempts to free its garbage.
+const COLLECT_EVERY: usize = 64;
+/// How much garbage a thread keeps before trying to free it early.
+const MAX_GARBAGE: usize = 64;
+
+/// The global epoch, which advances once every pinned thread has seen it.
+static EPOCH: AtomicUsize = AtomicUsize::new(0);
+/// Every thread that has ever pinned, as a list that is only pushed to.
+static PARTICIPANTS: AtomicPtr<Participant> = AtomicPtr::new(ptr::null_mut());
+/// Garbage left behind by threads that exited before it could be freed.
+static ORPHANS: Mutex<Vec<(usize, Deferred)>> = Mutex::new(Vec::new());
+
+/// A thread's entry in `PARTICIPANTS`. Entries are never freed; the entry of
+/// an exited thread is reused by the next thread that registers.
+struct Participant {
+    // The epoch the thread is pinned in, shifted left by one, with the low
+    // bit set while it is pinned.
+    epoch: AtomicUsize,
+    in_use: AtomicBool,
+    next: *mut Participant,
+}
+
+/// Memory to be freed later, by calling `free` on `ptr`.
+struct Deferred {
+    ptr: *mut (),
+    free: unsafe fn(*mut ()),
+}
+
+// `ptr` is only touched by `free`, on whichever thread collects it.
+unsafe impl Send for Deferred {}
+
+struct Handle {
+    participant: &'static Participant,
+    // Deferred frees, each with the epoch it was retired in.
+    garbage: RefCell<Vec<(usize, Deferred)>>,
+    depth: Cell<usize>,
+    pins: Cell<usize>,
+}
+
+thread_local! {
+    static HANDLE: Handle = Handle::register();
+}
+
+impl Handle {
+    fn register() -> Handle {
+        let mut current = PARTICIPANTS.load(Ordering::Acquire);
+        while let Some(participant) = unsafe { current.as_ref() } {
+            if participant
+                .in_use
+                .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+                .is_ok()
+            {
+                return Handle::new(participant);
+            }
+            current = participant.next;
+        }
+        let participant = Box::leak(Box::new(Participant {
+            epoch: AtomicUsize::new(0),
+            in_use: AtomicBool::new(true),
+            next: ptr::null_mut(),
+        }));
+        let mut head = PARTICIPANTS.load(Ordering::Relaxed);
+        loop {
+            participant.next = head;
+            match PARTICIPANTS.compare_exchange_weak(
+                head,
+                participant,
+                Ordering::Release,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => return Handle::new(participant),
+                Err(now) => head = now,
+            }
+        }
+    }
+
+    fn new(participant: &'static Participant) -> Handle {
+        Handle {
+            participant,
+            garbage: RefCell::new(vec![]),
+            depth: Cell::new(0),
+            pins: Cell::new(0),
+        }
+    }
+
+    fn defer(&self, deferred: Deferred) {
+        fence(Ordering::SeqCst);
+        let epoch = EPOCH.load(Ordering::Relaxed);
+        let full = {
+            let mut garbage = self.garbage.borrow_mut();
+            garbage.push((epoch, deferred));
+            garbage.len() >= MAX_GARBAGE
+        };
+        if full {
+            self.collect();
+        }
+    }
+
+    /// Tries to advance the epoch, then frees the garbage that is old enough.
+    fn collect(&self) {
+        let epoch = try_advance();
+        let mut ready = vec![];
+        take_ready(&mut self.garbage.borrow_mut(), epoch, &mut ready);
+        if let Ok(mut orphans) = ORPHANS.try_lock() {
+            take_ready(&mut orphans, epoch, &mut ready);
+        }
+        for deferred in ready {
+            unsafe { (deferred.free)(deferred.ptr) };
+        }
+    }
+}
+
+impl Drop for Handle {
+    fn drop(&mut self) {
+        let garbage = mem::take(self.garbage.get_mut());
+        ORPHANS
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .extend(garbage);
+        self.participant.epoch.store(0, Ordering::Release);
+        self.participant.in_use.store(false, Ordering::Release);
+    }
+}
+
+/// Moves the garbage that no pinned thread can still see into `ready`.
+/// Garbage retired in epoch `e` is safe to free once the epoch reaches
+/// `e + 2`, since every thread pinned in `e` or earlier has unpinned by then.
+fn take_ready(garbage: &mut Vec<(usize, Deferred)>, epoch: usize, ready: &mut Vec<Deferred>) {
+    let mut i = 0;
+    while i < garbage.len() {
+        if epoch.wrapping_sub(garbage[i].0) >= 2 {
+            ready.push(garbage.swap_remove(i).1);
+        } else {
+            i += 1;
+        }
+    }
+}
+
+/// Advances the global epoch if every pinned thread is pinned in it, and
+/// returns the epoch.
+fn try_advance() -> usize {
+    let epoch = EPOCH.load(Ordering::Relaxed);
+    fence(Ordering::SeqCst);
+    let mut current = PARTICIPANTS.load(Ordering::Acquire);
+    while let Some(participant) = unsafe { current.as_ref() } {
+        let pinned = participant.epoch.load(Ordering::Relaxed);
+        if pinned & 1 == 1 && pinned >> 1 != epoch {
+            return epoch;
+        }
+        current = participant.next;
+    }
+    fence(Ordering::Acquire);
+    match EPOCH.compare_exchange(epoch, epoch + 1, Ordering::Release, Ordering::Relaxed) {
+        Ok(_) => epoch + 1,
+        Err(now) => now,
+    }
+}
+
+/// Pins the current thread, so that memory retired through any `Guard`
+/// is not freed while the returned guard is alive.
+///
+/// Lock-free structures pin before reading shared pointers, and retire
+/// nodes they unlink with `Guard::defer_destroy` instead of freeing them.
+/// Pins nest, and are cheap: a store and a fence.
+/// ## Examples
+/// ```
+/// # use stdlib_rs::concurrent::epoch;
+/// # use std::sync::atomic::{AtomicPtr, Ordering};
+/// let shared = AtomicPtr::new(Box::into_raw(Box::new(1)));
+/// let guard = epoch::pin();
+/// let old = shared.swap(Box::into_raw(Box::new(2)), Ordering::AcqRel);
+/// // Other pinned threads may still be reading `old`.
+/// unsafe { guard.defer_destroy(old) };
+/// drop(guard);
+/// # unsafe { drop(Box::from_raw(shared.load(Ordering::Relaxed))) };
+/// ```
+pub fn pin() -> Guard {
+    HANDLE.with(|handle| {
+        let depth = handle.depth.get();
+        handle.depth.set(depth + 1);
+        if depth == 0 {
+            let epoch = EPOCH.load(Ordering::Relaxed);
+            handle
+                .participant
+                .epoch
+                .store(epoch << 1 | 1, Ordering::Relaxed);
+            fence(Ordering::SeqCst);
+            let pins = handle.pins.get().wrapping_add(1);
+            handle.pins.set(pins);
+            if pins % COLLECT_EVERY == 0 {
+                handle.collect();
+            }
+        }
+    });
+    Guard {
+        _not_send: PhantomData,
+    }
+}
+
+/// Keeps the current thread pinned until it is dropped. Created by `pin`.
+pub struct Guard {
+    _not_send: PhantomData<*mut ()>,
+}
+
+impl Guard {
+    /// Frees `ptr` once no thread that was pinned before this call can
+    /// still be reading it.
+    /// `T` must be `Send`, since the box may be dropped on whichever thread
+    /// advances the epoch, or after this thread has exited.
+    /// ## Safety
+    /// `ptr` must come from `Box::into_raw`, must already be unreachable for
+    /// threads that pin from now on, and must not be freed any other way.
+    /// ## Examples
+    /// Values that must stay on their thread are rejected:
+    /// ```compile_fail
+    /// # use stdlib_rs::concurrent::epoch;
+    /// # use std::rc::Rc;
+    /// let guard = epoch::pin();
+    /// let ptr = Box::into_raw(Box::new(Rc::new(1)));
+    /// unsafe { guard.defer_destroy(ptr) };
+    /// ```
+    pub unsafe fn defer_destroy<T: Send>(&self, ptr: *mut T) {
+        unsafe fn free<T>(ptr: *mut ()) {
+            drop(Box::from_raw(ptr as *mut T));
+        }
+        HANDLE.with(|handle| {
+            handle.defer(Deferred {
+                ptr: ptr as *mut (),
+                free: free::<T>,
+            })
+        });
+    }
+
+    /// Tries to advance the epoch and free the current thread's garbage
+    /// now, instead of waiting for the next periodic collection.
+    pub fn flush(&self) {
+        HANDLE.with(Handle::collect);
+    }
+}
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        let _ = HANDLE.try_with(|handle| {
+            let depth = handle.depth.get() - 1;
+            handle.depth.set(depth);
+            if depth == 0 {
+                handle.participant.epoch.store(0, Ordering::Release);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::thread;
+
+    struct Counted(Arc<AtomicUsize>);
+
+    impl Drop for Counted {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn deferred_frees_run() {
+        let freed = Arc::new(AtomicUsize::new(0));
+        {
+            let guard = pin();
+            for _ in 0..100 {
+                let ptr = Box::into_raw(Box::new(Counted(freed.clone())));
+                unsafe { guard.defer_destroy(ptr) };
+            }
+        }
+        for _ in 0..100_000 {
+            if freed.load(Ordering::SeqCst) == 100 {
+                break;
+            }
+            pin().flush();
+            thread::yield_now();
+        }
+        assert_eq!(freed.load(Ordering::SeqCst), 100);
+    }
+
+    #[test]
+    fn pinned_thread_blocks_frees() {
+        let freed = Arc::new(AtomicUsize::new(0));
+        let outer = pin();
+        let epoch = EPOCH.load(Ordering::SeqCst);
+        let ptr = Box::into_raw(Box::new(Counted(freed.clone())));
+        unsafe { outer.defer_destroy(ptr) };
+        // Another thread cannot move the epoch two steps past this one.
+        thread::spawn(|| {
+            for _ in 0..1000 {
+                pin().flush();
+            }
+        })
+        .join()
+        .unwrap();
+        assert!(EPOCH.load(Ordering::SeqCst).wrapping_sub(epoch) <= 1);
+        assert_eq!(freed.load(Ordering::SeqCst), 0);
+        drop(outer);
+    }
+}
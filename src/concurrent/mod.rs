@@ -0,0 +1,28 @@
+/// Errors and shared machinery for the bounded channels.
+pub mod channel;
+/// Epoch-based memory reclamation for the lock-free collections.
+/// Defers freeing unlinked nodes until no thread can still be reading them.
+pub mod epoch;
+/// A bounded multi-producer, single-consumer ring channel.
+/// Blocks by parking the thread, with try and timeout variants.
+pub mod mpsc;
+/// A lock-free Michael-Scott queue.
+/// Pushes and pops from any number of threads without locking.
+pub mod ms_queue;
+/// A hash map split into independently locked shards.
+/// Threads working on different shards never wait for each other.
+pub mod sharded_hash_map;
+/// A bounded single-producer, single-consumer ring channel.
+/// Blocks by parking the thread, with try and timeout variants.
+pub mod spsc;
+/// A thread-safe minimum stack.
+/// Reads the minimum through an atomic pointer without blocking pushes.
+pub mod sync_min_stack;
+/// A lock-free Treiber stack.
+/// Pushes and pops from any number of threads without locking.
+pub mod treiber_stack;
+
+pub use ms_queue::MsQueue;
+pub use sharded_hash_map::ShardedHashMap;
+pub use sync_min_stack::SyncMinStack;
+pub use treiber_stack::TreiberStack;
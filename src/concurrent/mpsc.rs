@@ -0,0 +1,320 @@
+#![deny(missing_docs)]
+
+use std::cell::{Cell, UnsafeCell};
+use std::marker::PhantomData;
+use std::mem::MaybeUninit;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::channel::{
+    Chan, RecvError, RecvTimeoutError, Ring, SendError, TryRecvError, TrySendError,
+};
+
+struct Slot<T> {
+    // `free(p)` when the slot is ready for the value at position `p`, and
+    // `full(p)` once it holds that value.
+    sequence: AtomicUsize,
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+/// A ring buffer with many producers and one consumer (Vyukov's bounded
+/// queue), where producers claim positions and each slot's sequence number
+/// says whether it is free or full.
+pub(crate) struct MpscRing<T> {
+    slots: Box<[Slot<T>]>,
+    // The next position to read, written only by the consumer.
+    head: AtomicUsize,
+    // The next position to claim for writing.
+    tail: AtomicUsize,
+}
+
+unsafe impl<T: Send> Send for MpscRing<T> {}
+unsafe impl<T: Send> Sync for MpscRing<T> {}
+
+impl<T> MpscRing<T> {
+    fn slot(&self, position: usize) -> &Slot<T> {
+        &self.slots[position % self.slots.len()]
+    }
+}
+
+// Sequence numbers are doubled so that a full slot never looks free for the
+// next lap, even when the ring has a single slot.
+fn free(position: usize) -> usize {
+    position.wrapping_mul(2)
+}
+
+fn full(position: usize) -> usize {
+    free(position).wrapping_add(1)
+}
+
+impl<T> Ring<T> for MpscRing<T> {
+    fn with_capacity(capacity: usize) -> Self {
+        MpscRing {
+            slots: (0..capacity)
+                .map(|i| Slot {
+                    sequence: AtomicUsize::new(free(i)),
+                    value: UnsafeCell::new(MaybeUninit::uninit()),
+                })
+                .collect(),
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+        }
+    }
+
+    unsafe fn push(&self, value: T) -> Result<(), T> {
+        let mut position = self.tail.load(Ordering::Relaxed);
+        loop {
+            let slot = self.slot(position);
+            let lag = slot
+                .sequence
+                .load(Ordering::Acquire)
+                .wrapping_sub(free(position)) as isize;
+            if lag < 0 {
+                // The slot still holds a value from a lap ago.
+                return Err(value);
+            }
+            if lag > 0 {
+                // Another producer claimed this position.
+                position = self.tail.load(Ordering::Relaxed);
+                continue;
+            }
+            match self.tail.compare_exchange_weak(
+                position,
+                position.wrapping_add(1),
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => {
+                    (*slot.value.get()).write(value);
+                    slot.sequence.store(full(position), Ordering::Release);
+                    return Ok(());
+                }
+                Err(now) => position = now,
+            }
+        }
+    }
+
+    unsafe fn pop(&self) -> Option<T> {
+        let position = self.head.load(Ordering::Relaxed);
+        let slot = self.slot(position);
+        if slot.sequence.load(Ordering::Acquire) != full(position) {
+            return None;
+        }
+        let value = (*slot.value.get()).as_ptr().read();
+        // Free the slot for the producer one lap ahead.
+        slot.sequence.store(
+            free(position.wrapping_add(self.slots.len())),
+            Ordering::Release,
+        );
+        self.head.store(position.wrapping_add(1), Ordering::Release);
+        Some(value)
+    }
+
+    fn is_empty(&self) -> bool {
+        let position = self.head.load(Ordering::Acquire);
+        self.slot(position).sequence.load(Ordering::Acquire) != full(position)
+    }
+
+    fn is_full(&self) -> bool {
+        let position = self.tail.load(Ordering::Acquire);
+        let sequence = self.slot(position).sequence.load(Ordering::Acquire);
+        (sequence.wrapping_sub(free(position)) as isize) < 0
+    }
+}
+
+impl<T> Drop for MpscRing<T> {
+    fn drop(&mut self) {
+        while unsafe { self.pop() }.is_some() {}
+    }
+}
+
+/// Creates a bounded multi-producer, single-consumer channel that holds up
+/// to `capacity` values in a ring buffer.
+/// Clone the `Sender` to send from more threads. Sending and receiving
+/// never lock unless a side has to block.
+/// ## Panics
+/// Panics if `capacity` is zero.
+/// ## Examples
+/// ```
+/// # use stdlib_rs::concurrent::mpsc;
+/// # use std::thread;
+/// let (sender, receiver) = mpsc::channel(8);
+/// for id in 0..4 {
+///     let sender = sender.clone();
+///     thread::spawn(move || sender.send(id).unwrap());
+/// }
+/// drop(sender);
+/// let mut ids: Vec<i32> = std::iter::from_fn(|| receiver.recv().ok()).collect();
+/// ids.sort();
+/// assert_eq!(ids, vec![0, 1, 2, 3]);
+/// ```
+pub fn channel<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    let chan = Arc::new(Chan::new(capacity));
+    (
+        Sender { chan: chan.clone() },
+        Receiver {
+            chan,
+            _not_sync: PhantomData,
+        },
+    )
+}
+
+/// The sending half of an `mpsc` channel, which can be cloned and shared
+/// between threads.
+pub struct Sender<T> {
+    chan: Arc<Chan<T, MpscRing<T>>>,
+}
+
+/// The receiving half of an `mpsc` channel. It can be moved to another
+/// thread, but not shared or cloned.
+pub struct Receiver<T> {
+    chan: Arc<Chan<T, MpscRing<T>>>,
+    _not_sync: PhantomData<Cell<()>>,
+}
+
+impl<T> Sender<T> {
+    /// Sends `value` if there is room, without blocking.
+    pub fn try_send(&self, value: T) -> Result<(), TrySendError<T>> {
+        // Any number of threads may push to an `MpscRing`.
+        unsafe { self.chan.try_send(value) }
+    }
+
+    /// Sends `value`, parking the thread while the channel is full.
+    /// Fails if the receiver has been dropped.
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        unsafe { self.chan.send(value) }
+    }
+}
+
+impl<T> Receiver<T> {
+    /// Receives a value if one is ready, without blocking.
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        // `Receiver` is neither `Clone` nor `Sync`, so it is the only consumer.
+        unsafe { self.chan.try_recv() }
+    }
+
+    /// Receives a value, parking the thread while the channel is empty.
+    /// Fails once the channel is empty and every sender has been dropped.
+    pub fn recv(&self) -> Result<T, RecvError> {
+        unsafe { self.chan.recv() }
+    }
+
+    /// Receives a value, parking the thread for at most `timeout` while the
+    /// channel is empty.
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<T, RecvTimeoutError> {
+        unsafe { self.chan.recv_timeout(timeout) }
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        self.chan.add_sender();
+        Sender {
+            chan: self.chan.clone(),
+        }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        self.chan.drop_sender();
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.chan.drop_receiver();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn try_send_and_try_recv() {
+        let (sender, receiver) = channel(2);
+        let other = sender.clone();
+        assert_eq!(sender.try_send(1), Ok(()));
+        assert_eq!(other.try_send(2), Ok(()));
+        assert_eq!(other.try_send(3), Err(TrySendError::Full(3)));
+        assert_eq!(receiver.try_recv(), Ok(1));
+        assert_eq!(receiver.try_recv(), Ok(2));
+        assert_eq!(receiver.try_recv(), Err(TryRecvError::Empty));
+    }
+
+    #[test]
+    fn disconnects_after_last_sender() {
+        let (sender, receiver) = channel(4);
+        let other = sender.clone();
+        sender.send(1).unwrap();
+        drop(sender);
+        assert_eq!(receiver.recv_timeout(Duration::from_millis(10)), Ok(1));
+        assert_eq!(
+            receiver.recv_timeout(Duration::from_millis(10)),
+            Err(RecvTimeoutError::Timeout)
+        );
+        drop(other);
+        assert_eq!(receiver.recv(), Err(RecvError));
+    }
+
+    #[test]
+    fn stress_many_producers() {
+        const PRODUCERS: usize = 6;
+        const PER_PRODUCER: usize = 20_000;
+        let (sender, receiver) = channel(16);
+        let producers: Vec<_> = (0..PRODUCERS)
+            .map(|p| {
+                let sender = sender.clone();
+                thread::spawn(move || {
+                    for i in 0..PER_PRODUCER {
+                        sender.send((p, i)).unwrap();
+                    }
+                })
+            })
+            .collect();
+        drop(sender);
+        let mut next = [0; PRODUCERS];
+        while let Ok((p, i)) = receiver.recv() {
+            // Each producer's values arrive in the order it sent them.
+            assert_eq!(next[p], i);
+            next[p] += 1;
+        }
+        assert_eq!(next, [PER_PRODUCER; PRODUCERS]);
+        for producer in producers {
+            producer.join().unwrap();
+        }
+    }
+
+    #[test]
+    fn dropping_receiver_wakes_senders() {
+        let (sender, receiver) = channel(1);
+        sender.send(0).unwrap();
+        let blocked: Vec<_> = (1..4)
+            .map(|i| {
+                let sender = sender.clone();
+                thread::spawn(move || sender.send(i))
+            })
+            .collect();
+        thread::sleep(Duration::from_millis(20));
+        drop(receiver);
+        for (i, handle) in (1..4).zip(blocked) {
+            assert_eq!(handle.join().unwrap(), Err(SendError(i)));
+        }
+    }
+
+    #[test]
+    fn drops_unreceived_values() {
+        let counter = Arc::new(());
+        let (sender, receiver) = channel(8);
+        for _ in 0..5 {
+            sender.send(counter.clone()).unwrap();
+        }
+        drop(receiver.recv());
+        assert_eq!(Arc::strong_count(&counter), 5);
+        drop((sender, receiver));
+        assert_eq!(Arc::strong_count(&counter), 1);
+    }
+}
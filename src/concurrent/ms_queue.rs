@@ -0,0 +1,250 @@
+#![deny(missing_docs)]
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::mem::MaybeUninit;
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, Ordering};
+
+use super::epoch;
+
+struct Node<T> {
+    // Uninitialized in the sentinel, which is always the node at `head`.
+    value: MaybeUninit<T>,
+    next: AtomicPtr<Node<T>>,
+}
+
+// A node only reaches another thread through `epoch::Guard::defer_destroy`,
+// as a retired sentinel whose value was moved out, and `MaybeUninit` never
+// drops its value, so freeing it there never touches a `T`.
+unsafe impl<T> Send for Node<T> {}
+
+impl<T> Node<T> {
+    fn new(value: MaybeUninit<T>) -> *mut Node<T> {
+        Box::into_raw(Box::new(Node {
+            value,
+            next: AtomicPtr::new(ptr::null_mut()),
+        }))
+    }
+}
+
+/// A lock-free, multi-producer multi-consumer FIFO queue (the Michael-Scott
+/// queue) that any number of threads can push to and pop from at once.
+/// The thread-safe counterpart of `collections::queue_with_stack::Queue`.
+///
+/// Popped nodes are freed through `epoch`, so a thread still reading a
+/// node never sees it freed or reused.
+/// ## Examples
+/// ```
+/// # use stdlib_rs::concurrent::MsQueue;
+/// # use std::sync::Arc;
+/// # use std::thread;
+/// let queue = Arc::new(MsQueue::new());
+/// let producer = {
+///     let queue = queue.clone();
+///     thread::spawn(move || {
+///         for i in 0..100 {
+///             queue.push(i);
+///         }
+///     })
+/// };
+/// producer.join().unwrap();
+/// let items: Vec<i32> = std::iter::from_fn(|| queue.pop()).collect();
+/// assert_eq!(items, (0..100).collect::<Vec<_>>());
+/// ```
+pub struct MsQueue<T> {
+    head: AtomicPtr<Node<T>>,
+    tail: AtomicPtr<Node<T>>,
+    _marker: PhantomData<T>,
+}
+
+unsafe impl<T: Send> Send for MsQueue<T> {}
+unsafe impl<T: Send> Sync for MsQueue<T> {}
+
+impl<T> MsQueue<T> {
+    /// Creates a new, empty MsQueue.
+    pub fn new() -> Self {
+        let sentinel = Node::new(MaybeUninit::uninit());
+        MsQueue {
+            head: AtomicPtr::new(sentinel),
+            tail: AtomicPtr::new(sentinel),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Adds an item to the back of the queue.
+    pub fn push(&self, value: T) {
+        let node = Node::new(MaybeUninit::new(value));
+        let _guard = epoch::pin();
+        loop {
+            let tail = self.tail.load(Ordering::Acquire);
+            // Pinned, so `tail` cannot be freed until the guard is dropped.
+            let next = unsafe { &(*tail).next };
+            let after = next.load(Ordering::Acquire);
+            if !after.is_null() {
+                // Another push linked a node but has not moved `tail` yet.
+                let _ =
+                    self.tail
+                        .compare_exchange(tail, after, Ordering::Release, Ordering::Relaxed);
+                continue;
+            }
+            if next
+                .compare_exchange(after, node, Ordering::Release, Ordering::Relaxed)
+                .is_ok()
+            {
+                let _ =
+                    self.tail
+                        .compare_exchange(tail, node, Ordering::Release, Ordering::Relaxed);
+                return;
+            }
+        }
+    }
+
+    /// Removes the item at the front of the queue and returns it, or `None`
+    /// if it is empty.
+    pub fn pop(&self) -> Option<T> {
+        let guard = epoch::pin();
+        loop {
+            let head = self.head.load(Ordering::Acquire);
+            let next = unsafe { (*head).next.load(Ordering::Acquire) };
+            if next.is_null() {
+                return None;
+            }
+            // Keep `tail` from falling behind `head`, where it would point
+            // at a retired node.
+            let tail = self.tail.load(Ordering::Acquire);
+            if tail == head {
+                let _ =
+                    self.tail
+                        .compare_exchange(tail, next, Ordering::Release, Ordering::Relaxed);
+            }
+            if self
+                .head
+                .compare_exchange(head, next, Ordering::AcqRel, Ordering::Relaxed)
+                .is_ok()
+            {
+                // `next` is the new sentinel; only this thread reads its value.
+                unsafe {
+                    let value = ptr::read((*next).value.as_ptr());
+                    guard.defer_destroy(head);
+                    return Some(value);
+                }
+            }
+        }
+    }
+
+    /// Returns `true` if the queue was empty when checked.
+    pub fn is_empty(&self) -> bool {
+        let _guard = epoch::pin();
+        let head = self.head.load(Ordering::Acquire);
+        unsafe { (*head).next.load(Ordering::Acquire).is_null() }
+    }
+}
+
+impl<T> Default for MsQueue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for MsQueue<T> {
+    fn drop(&mut self) {
+        // No other thread can reach the nodes once the queue is dropped.
+        let sentinel = unsafe { Box::from_raw(*self.head.get_mut()) };
+        let mut current = sentinel.next.load(Ordering::Relaxed);
+        while !current.is_null() {
+            let mut node = unsafe { Box::from_raw(current) };
+            unsafe { ptr::drop_in_place(node.value.as_mut_ptr()) };
+            current = node.next.load(Ordering::Relaxed);
+        }
+    }
+}
+
+impl<T> fmt::Debug for MsQueue<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MsQueue")
+            .field("is_empty", &self.is_empty())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn fifo_order() {
+        let queue = MsQueue::new();
+        assert!(queue.is_empty());
+        assert_eq!(queue.pop(), None);
+        queue.push(1);
+        queue.push(2);
+        assert_eq!(queue.pop(), Some(1));
+        queue.push(3);
+        assert_eq!(queue.pop(), Some(2));
+        assert_eq!(queue.pop(), Some(3));
+        assert_eq!(queue.pop(), None);
+    }
+
+    #[test]
+    fn stress_mpmc() {
+        const PRODUCERS: usize = 4;
+        const CONSUMERS: usize = 4;
+        const PER_PRODUCER: usize = 20_000;
+        let queue = Arc::new(MsQueue::new());
+        let producers: Vec<_> = (0..PRODUCERS)
+            .map(|p| {
+                let queue = queue.clone();
+                thread::spawn(move || {
+                    for i in 0..PER_PRODUCER {
+                        queue.push((p, i));
+                    }
+                })
+            })
+            .collect();
+        let consumers: Vec<_> = (0..CONSUMERS)
+            .map(|_| {
+                let queue = queue.clone();
+                thread::spawn(move || {
+                    let mut seen = vec![];
+                    let mut last = [None; PRODUCERS];
+                    while seen.len() < PRODUCERS * PER_PRODUCER / CONSUMERS {
+                        if let Some((p, i)) = queue.pop() {
+                            // Each producer's items come out in order.
+                            assert!(last[p].is_none_or(|l| l < i));
+                            last[p] = Some(i);
+                            seen.push(p * PER_PRODUCER + i);
+                        }
+                    }
+                    seen
+                })
+            })
+            .collect();
+        for producer in producers {
+            producer.join().unwrap();
+        }
+        let mut all: Vec<usize> = consumers
+            .into_iter()
+            .flat_map(|consumer| consumer.join().unwrap())
+            .collect();
+        all.sort_unstable();
+        assert_eq!(all, (0..PRODUCERS * PER_PRODUCER).collect::<Vec<_>>());
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn drops_remaining_items() {
+        let counter = Arc::new(());
+        {
+            let queue = MsQueue::new();
+            for _ in 0..10 {
+                queue.push(counter.clone());
+            }
+            drop(queue.pop());
+            assert_eq!(Arc::strong_count(&counter), 10);
+        }
+        assert_eq!(Arc::strong_count(&counter), 1);
+    }
+}
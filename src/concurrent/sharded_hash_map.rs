@@ -0,0 +1,234 @@
+#![deny(missing_docs)]
+
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::sync::{PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::thread;
+
+use crate::crypto::fnv::Fnv64Hasher;
+
+/// A hash map that can be shared between threads, split into shards that
+/// each have their own `RwLock`.
+///
+/// Keys are assigned to shards by their `Fnv64Hasher` hash, so threads that
+/// touch different shards never wait for each other, and readers of the
+/// same shard only wait for writers.
+/// ## Examples
+/// ```
+/// # use stdlib_rs::concurrent::ShardedHashMap;
+/// # use std::sync::Arc;
+/// # use std::thread;
+/// let hits = Arc::new(ShardedHashMap::new());
+/// let handles: Vec<_> = (0..4)
+///     .map(|_| {
+///         let hits = hits.clone();
+///         thread::spawn(move || {
+///             for page in ["/", "/about"].iter() {
+///                 hits.upsert(page.to_string(), 1, |count| *count += 1);
+///             }
+///         })
+///     })
+///     .collect();
+/// for handle in handles {
+///     handle.join().unwrap();
+/// }
+/// assert_eq!(hits.get(&"/".to_string()), Some(4));
+/// assert_eq!(hits.len(), 2);
+/// ```
+pub struct ShardedHashMap<K, V> {
+    shards: Box<[RwLock<HashMap<K, V>>]>,
+}
+
+impl<K: Hash + Eq, V> ShardedHashMap<K, V> {
+    /// Creates a new, empty ShardedHashMap with four shards for each
+    /// available CPU.
+    pub fn new() -> Self {
+        let cpus = thread::available_parallelism().map_or(1, |n| n.get());
+        Self::with_shards(4 * cpus)
+    }
+
+    /// Creates a new, empty ShardedHashMap with `shards` shards.
+    /// ## Panics
+    /// Panics if `shards` is zero.
+    pub fn with_shards(shards: usize) -> Self {
+        assert!(shards > 0, "a ShardedHashMap needs at least one shard");
+        ShardedHashMap {
+            shards: (0..shards).map(|_| RwLock::new(HashMap::new())).collect(),
+        }
+    }
+
+    /// Returns the number of shards.
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Inserts a key-value pair, returning the value it replaced.
+    pub fn insert(&self, key: K, value: V) -> Option<V> {
+        self.write(&key).insert(key, value)
+    }
+
+    /// Removes `key`, returning its value.
+    pub fn remove(&self, key: &K) -> Option<V> {
+        self.write(key).remove(key)
+    }
+
+    /// Returns `true` if the map has an entry for `key`.
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.read(key).contains_key(key)
+    }
+
+    /// Calls `f` on the value for `key` while its shard is read-locked, and
+    /// returns the result, or `None` if the key is not in the map.
+    pub fn with<R>(&self, key: &K, f: impl FnOnce(&V) -> R) -> Option<R> {
+        self.read(key).get(key).map(f)
+    }
+
+    /// Calls `f` on the value for `key` while its shard is write-locked, and
+    /// returns the result, or `None` if the key is not in the map.
+    pub fn update<R>(&self, key: &K, f: impl FnOnce(&mut V) -> R) -> Option<R> {
+        self.write(key).get_mut(key).map(f)
+    }
+
+    /// Calls `f` on the value for `key`, or inserts `default` if there is
+    /// none, as one step under the shard's write lock.
+    pub fn upsert(&self, key: K, default: V, f: impl FnOnce(&mut V)) {
+        let mut shard = self.write(&key);
+        match shard.get_mut(&key) {
+            Some(value) => f(value),
+            None => {
+                shard.insert(key, default);
+            }
+        }
+    }
+
+    /// Returns the number of entries. Shards are counted one at a time, so
+    /// concurrent inserts and removes may or may not be included.
+    pub fn len(&self) -> usize {
+        (0..self.shards.len())
+            .map(|i| self.read_shard(i).len())
+            .sum()
+    }
+
+    /// Returns `true` if every shard was empty when checked.
+    pub fn is_empty(&self) -> bool {
+        (0..self.shards.len()).all(|i| self.read_shard(i).is_empty())
+    }
+
+    /// Removes every entry.
+    pub fn clear(&self) {
+        for shard in self.shards.iter() {
+            shard
+                .write()
+                .unwrap_or_else(PoisonError::into_inner)
+                .clear();
+        }
+    }
+
+    /// Consumes the map, returning its entries in one `HashMap`.
+    pub fn into_inner(self) -> HashMap<K, V> {
+        let mut all = HashMap::new();
+        for shard in self.shards.into_vec() {
+            all.extend(shard.into_inner().unwrap_or_else(PoisonError::into_inner));
+        }
+        all
+    }
+
+    fn shard_index(&self, key: &K) -> usize {
+        let mut hasher = Fnv64Hasher::default();
+        key.hash(&mut hasher);
+        (hasher.finish() % self.shards.len() as u64) as usize
+    }
+
+    fn read_shard(&self, index: usize) -> RwLockReadGuard<'_, HashMap<K, V>> {
+        self.shards[index]
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn read(&self, key: &K) -> RwLockReadGuard<'_, HashMap<K, V>> {
+        self.read_shard(self.shard_index(key))
+    }
+
+    fn write(&self, key: &K) -> RwLockWriteGuard<'_, HashMap<K, V>> {
+        self.shards[self.shard_index(key)]
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<K: Hash + Eq, V: Clone> ShardedHashMap<K, V> {
+    /// Returns a copy of the value for `key`, or `None` if it is not in
+    /// the map.
+    pub fn get(&self, key: &K) -> Option<V> {
+        self.with(key, V::clone)
+    }
+}
+
+impl<K: Hash + Eq, V> Default for ShardedHashMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Hash + Eq, V> fmt::Debug for ShardedHashMap<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ShardedHashMap")
+            .field("shards", &self.shards.len())
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn single_thread() {
+        let map = ShardedHashMap::with_shards(3);
+        assert!(map.is_empty());
+        assert_eq!(map.insert("a", 1), None);
+        assert_eq!(map.insert("a", 2), Some(1));
+        assert_eq!(map.update(&"a", |v| *v *= 10), Some(()));
+        assert_eq!(map.get(&"a"), Some(20));
+        assert_eq!(map.with(&"b", |v| *v), None);
+        assert_eq!(map.remove(&"a"), Some(20));
+        assert!(!map.contains_key(&"a"));
+    }
+
+    #[test]
+    fn spreads_keys_over_shards() {
+        let map = ShardedHashMap::with_shards(8);
+        for i in 0..1000 {
+            map.insert(i, i);
+        }
+        for shard in map.shards.iter() {
+            assert!(shard.read().unwrap().len() > 50);
+        }
+        assert_eq!(map.into_inner().len(), 1000);
+    }
+
+    #[test]
+    fn concurrent_counters() {
+        let map = Arc::new(ShardedHashMap::new());
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let map = map.clone();
+                thread::spawn(move || {
+                    for i in 0..5000 {
+                        map.upsert(i % 100, 1, |count| *count += 1);
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(map.len(), 100);
+        for key in 0..100 {
+            assert_eq!(map.get(&key), Some(400));
+        }
+    }
+}
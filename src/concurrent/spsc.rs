@@ -0,0 +1,261 @@
+#![deny(missing_docs)]
+
+use std::cell::{Cell, UnsafeCell};
+use std::marker::PhantomData;
+use std::mem::MaybeUninit;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::channel::{
+    Chan, RecvError, RecvTimeoutError, Ring, SendError, TryRecvError, TrySendError,
+};
+
+/// A ring buffer with one producer and one consumer, where each side only
+/// writes its own index.
+pub(crate) struct SpscRing<T> {
+    buffer: Box<[UnsafeCell<MaybeUninit<T>>]>,
+    // The next slot to read, written only by the consumer.
+    head: AtomicUsize,
+    // The next slot to write, written only by the producer.
+    tail: AtomicUsize,
+}
+
+unsafe impl<T: Send> Send for SpscRing<T> {}
+unsafe impl<T: Send> Sync for SpscRing<T> {}
+
+impl<T> Ring<T> for SpscRing<T> {
+    fn with_capacity(capacity: usize) -> Self {
+        SpscRing {
+            buffer: (0..capacity)
+                .map(|_| UnsafeCell::new(MaybeUninit::uninit()))
+                .collect(),
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+        }
+    }
+
+    unsafe fn push(&self, value: T) -> Result<(), T> {
+        let tail = self.tail.load(Ordering::Relaxed);
+        let head = self.head.load(Ordering::Acquire);
+        if tail.wrapping_sub(head) == self.buffer.len() {
+            return Err(value);
+        }
+        // The consumer does not read this slot until `tail` moves past it.
+        (*self.buffer[tail % self.buffer.len()].get()).write(value);
+        self.tail.store(tail.wrapping_add(1), Ordering::Release);
+        Ok(())
+    }
+
+    unsafe fn pop(&self) -> Option<T> {
+        let head = self.head.load(Ordering::Relaxed);
+        if head == self.tail.load(Ordering::Acquire) {
+            return None;
+        }
+        // The producer does not reuse this slot until `head` moves past it.
+        let value = (*self.buffer[head % self.buffer.len()].get())
+            .as_ptr()
+            .read();
+        self.head.store(head.wrapping_add(1), Ordering::Release);
+        Some(value)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.head.load(Ordering::Acquire) == self.tail.load(Ordering::Acquire)
+    }
+
+    fn is_full(&self) -> bool {
+        let tail = self.tail.load(Ordering::Acquire);
+        tail.wrapping_sub(self.head.load(Ordering::Acquire)) >= self.buffer.len()
+    }
+}
+
+impl<T> Drop for SpscRing<T> {
+    fn drop(&mut self) {
+        while unsafe { self.pop() }.is_some() {}
+    }
+}
+
+/// Creates a bounded single-producer, single-consumer channel that holds up
+/// to `capacity` values in a ring buffer.
+/// Sending and receiving never lock unless a side has to block.
+/// ## Panics
+/// Panics if `capacity` is zero.
+/// ## Examples
+/// ```
+/// # use stdlib_rs::concurrent::spsc;
+/// # use std::thread;
+/// let (sender, receiver) = spsc::channel(16);
+/// let producer = thread::spawn(move || {
+///     for i in 0..1000 {
+///         sender.send(i).unwrap();
+///     }
+/// });
+/// let received: Vec<i32> = std::iter::from_fn(|| receiver.recv().ok()).collect();
+/// producer.join().unwrap();
+/// assert_eq!(received, (0..1000).collect::<Vec<_>>());
+/// ```
+pub fn channel<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    let chan = Arc::new(Chan::new(capacity));
+    (
+        Sender {
+            chan: chan.clone(),
+            _not_sync: PhantomData,
+        },
+        Receiver {
+            chan,
+            _not_sync: PhantomData,
+        },
+    )
+}
+
+/// The sending half of an `spsc` channel. It can be moved to another
+/// thread, but not shared or cloned.
+pub struct Sender<T> {
+    chan: Arc<Chan<T, SpscRing<T>>>,
+    _not_sync: PhantomData<Cell<()>>,
+}
+
+/// The receiving half of an `spsc` channel. It can be moved to another
+/// thread, but not shared or cloned.
+pub struct Receiver<T> {
+    chan: Arc<Chan<T, SpscRing<T>>>,
+    _not_sync: PhantomData<Cell<()>>,
+}
+
+impl<T> Sender<T> {
+    /// Sends `value` if there is room, without blocking.
+    pub fn try_send(&self, value: T) -> Result<(), TrySendError<T>> {
+        // `Sender` is neither `Clone` nor `Sync`, so it is the only producer.
+        unsafe { self.chan.try_send(value) }
+    }
+
+    /// Sends `value`, parking the thread while the channel is full.
+    /// Fails if the receiver has been dropped.
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        unsafe { self.chan.send(value) }
+    }
+}
+
+impl<T> Receiver<T> {
+    /// Receives a value if one is ready, without blocking.
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        // `Receiver` is neither `Clone` nor `Sync`, so it is the only consumer.
+        unsafe { self.chan.try_recv() }
+    }
+
+    /// Receives a value, parking the thread while the channel is empty.
+    /// Fails once the channel is empty and the sender has been dropped.
+    pub fn recv(&self) -> Result<T, RecvError> {
+        unsafe { self.chan.recv() }
+    }
+
+    /// Receives a value, parking the thread for at most `timeout` while the
+    /// channel is empty.
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<T, RecvTimeoutError> {
+        unsafe { self.chan.recv_timeout(timeout) }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        self.chan.drop_sender();
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.chan.drop_receiver();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn try_send_and_try_recv() {
+        let (sender, receiver) = channel(2);
+        assert_eq!(receiver.try_recv(), Err(TryRecvError::Empty));
+        assert_eq!(sender.try_send(1), Ok(()));
+        assert_eq!(sender.try_send(2), Ok(()));
+        assert_eq!(sender.try_send(3), Err(TrySendError::Full(3)));
+        assert_eq!(receiver.try_recv(), Ok(1));
+        assert_eq!(sender.try_send(3), Ok(()));
+        assert_eq!(receiver.try_recv(), Ok(2));
+        assert_eq!(receiver.try_recv(), Ok(3));
+    }
+
+    #[test]
+    fn disconnects() {
+        let (sender, receiver) = channel(4);
+        sender.send(1).unwrap();
+        drop(sender);
+        assert_eq!(receiver.recv(), Ok(1));
+        assert_eq!(receiver.recv(), Err(RecvError));
+        assert_eq!(receiver.try_recv(), Err(TryRecvError::Disconnected));
+
+        let (sender, receiver) = channel(4);
+        drop(receiver);
+        assert_eq!(sender.send(1), Err(SendError(1)));
+        assert_eq!(sender.try_send(2), Err(TrySendError::Disconnected(2)));
+    }
+
+    #[test]
+    fn recv_timeout_expires() {
+        let (sender, receiver) = channel::<i32>(1);
+        let timeout = Duration::from_millis(20);
+        assert_eq!(
+            receiver.recv_timeout(timeout),
+            Err(RecvTimeoutError::Timeout)
+        );
+        sender.send(5).unwrap();
+        assert_eq!(receiver.recv_timeout(timeout), Ok(5));
+        drop(sender);
+        assert_eq!(
+            receiver.recv_timeout(timeout),
+            Err(RecvTimeoutError::Disconnected)
+        );
+    }
+
+    #[test]
+    fn recv_timeout_without_deadline() {
+        let (sender, receiver) = channel(1);
+        let producer = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(10));
+            sender.send(7).unwrap();
+        });
+        assert_eq!(receiver.recv_timeout(Duration::MAX), Ok(7));
+        producer.join().unwrap();
+        assert_eq!(
+            receiver.recv_timeout(Duration::MAX),
+            Err(RecvTimeoutError::Disconnected)
+        );
+    }
+
+    #[test]
+    fn blocking_across_threads() {
+        let (sender, receiver) = channel(3);
+        let producer = thread::spawn(move || {
+            for i in 0..50_000 {
+                sender.send(i).unwrap();
+            }
+        });
+        for i in 0..50_000 {
+            assert_eq!(receiver.recv(), Ok(i));
+        }
+        assert_eq!(receiver.recv(), Err(RecvError));
+        producer.join().unwrap();
+    }
+
+    #[test]
+    fn dropping_receiver_wakes_sender() {
+        let (sender, receiver) = channel(1);
+        sender.send(0).unwrap();
+        let blocked = thread::spawn(move || sender.send(1));
+        thread::sleep(Duration::from_millis(20));
+        drop(receiver);
+        assert_eq!(blocked.join().unwrap(), Err(SendError(1)));
+    }
+}
@@ -0,0 +1,234 @@
+#![deny(missing_docs)]
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::mem;
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use super::epoch;
+use crate::collections::min_stack::MinStack;
+
+/// A `MinStack` that can be shared between threads.
+///
+/// Pushes and pops take turns through a lock, but `min` and `len` never
+/// take it: each new minimum is published as a boxed copy behind an atomic
+/// pointer, and old copies are freed through `epoch`, so reading the
+/// minimum neither blocks nor slows down the threads that are pushing.
+/// Pushes and pops that leave the minimum unchanged do not allocate.
+/// ## Examples
+/// ```
+/// # use stdlib_rs::concurrent::SyncMinStack;
+/// # use std::sync::Arc;
+/// # use std::thread;
+/// let latencies = Arc::new(SyncMinStack::new());
+/// let handles: Vec<_> = (1..=4)
+///     .map(|i| {
+///         let latencies = latencies.clone();
+///         thread::spawn(move || latencies.push(i * 10))
+///     })
+///     .collect();
+/// for handle in handles {
+///     handle.join().unwrap();
+/// }
+/// assert_eq!(latencies.min(), Some(10));
+/// assert_eq!(latencies.len(), 4);
+/// ```
+pub struct SyncMinStack<T: Ord> {
+    stack: Mutex<MinStack<T>>,
+    // Null while the stack is empty. Only replaced while `stack` is locked.
+    min: AtomicPtr<T>,
+    len: AtomicUsize,
+    // `min` copies out a `T` that other threads can read at the same time,
+    // so the stack is only `Sync` if `T` is.
+    _marker: PhantomData<T>,
+}
+
+impl<T: Ord + Copy + Send> SyncMinStack<T> {
+    /// Creates a new, empty SyncMinStack.
+    pub fn new() -> Self {
+        SyncMinStack {
+            stack: Mutex::new(MinStack::new()),
+            min: AtomicPtr::new(ptr::null_mut()),
+            len: AtomicUsize::new(0),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Adds an item to the top of the stack.
+    pub fn push(&self, item: T) {
+        let mut stack = self.lock();
+        stack.push(item);
+        self.publish(&stack);
+    }
+
+    /// Removes the top item and returns it, or `None` if it is empty.
+    pub fn pop(&self) -> Option<T> {
+        let mut stack = self.lock();
+        let item = stack.pop();
+        self.publish(&stack);
+        item
+    }
+
+    /// Returns the top item, or `None` if it is empty.
+    pub fn peek(&self) -> Option<T> {
+        self.lock().peek()
+    }
+
+    /// Returns the minimum item, or `None` if it is empty, without taking
+    /// the lock or waiting for pushes and pops.
+    pub fn min(&self) -> Option<T> {
+        let _guard = epoch::pin();
+        // Pinned, so the copy cannot be freed until the guard is dropped.
+        unsafe { self.min.load(Ordering::Acquire).as_ref() }.copied()
+    }
+
+    /// Returns the number of items, without taking the lock.
+    pub fn len(&self) -> usize {
+        self.len.load(Ordering::Acquire)
+    }
+
+    /// Returns `true` if the stack has no items, without taking the lock.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Consumes the SyncMinStack, returning the MinStack inside it.
+    pub fn into_inner(mut self) -> MinStack<T> {
+        let stack = self.stack.get_mut().unwrap_or_else(PoisonError::into_inner);
+        mem::take(stack)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, MinStack<T>> {
+        self.stack.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Publishes the minimum and length of `stack`, whose lock is held.
+    fn publish(&self, stack: &MinStack<T>) {
+        let guard = epoch::pin();
+        let old = self.min.load(Ordering::Relaxed);
+        // Only publishers, which hold the lock, retire the copy, so `old`
+        // is still alive.
+        let min = stack.min();
+        if unsafe { old.as_ref() }.copied() != min {
+            let new = min.map_or(ptr::null_mut(), |min| Box::into_raw(Box::new(min)));
+            self.min.store(new, Ordering::Release);
+            if !old.is_null() {
+                // Readers that loaded `old` are pinned, and new readers see
+                // `new`.
+                unsafe { guard.defer_destroy(old) };
+            }
+        }
+        self.len.store(stack.len(), Ordering::Release);
+    }
+}
+
+impl<T: Ord> Drop for SyncMinStack<T> {
+    fn drop(&mut self) {
+        let min = *self.min.get_mut();
+        if !min.is_null() {
+            // No other thread can reach the copy once the stack is dropped.
+            drop(unsafe { Box::from_raw(min) });
+        }
+    }
+}
+
+impl<T: Ord + Copy + Send> Default for SyncMinStack<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord + Copy + Send> From<MinStack<T>> for SyncMinStack<T> {
+    fn from(stack: MinStack<T>) -> Self {
+        let sync = SyncMinStack::new();
+        sync.publish(&stack);
+        *sync.lock() = stack;
+        sync
+    }
+}
+
+impl<T: Ord + Copy + Send + fmt::Debug> fmt::Debug for SyncMinStack<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SyncMinStack")
+            .field("min", &self.min())
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicBool;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn tracks_min() {
+        let stack = SyncMinStack::new();
+        assert_eq!(stack.min(), None);
+        stack.push(5);
+        stack.push(3);
+        stack.push(8);
+        assert_eq!(stack.min(), Some(3));
+        assert_eq!(stack.pop(), Some(8));
+        assert_eq!(stack.pop(), Some(3));
+        assert_eq!(stack.min(), Some(5));
+        assert_eq!(stack.len(), 1);
+        assert_eq!(stack.into_inner().peek(), Some(5));
+    }
+
+    #[test]
+    fn min_does_not_take_the_lock() {
+        let stack = SyncMinStack::from(MinStack::from(vec![5, 3, 8]));
+        let _held = stack.lock();
+        assert_eq!(stack.min(), Some(3));
+        assert_eq!(stack.len(), 3);
+    }
+
+    #[test]
+    fn readers_see_consistent_minimums() {
+        // Every pushed pair is (n, n), so a torn read would show up as a
+        // pair with different halves.
+        let stack = Arc::new(SyncMinStack::new());
+        let done = Arc::new(AtomicBool::new(false));
+        let readers: Vec<_> = (0..3)
+            .map(|_| {
+                let stack = stack.clone();
+                let done = done.clone();
+                thread::spawn(move || {
+                    let mut last = u64::MAX;
+                    while !done.load(Ordering::Relaxed) {
+                        if let Some((a, b)) = stack.min() {
+                            assert_eq!(a, b);
+                            // Only pushes happen, so the minimum never grows.
+                            assert!(a <= last);
+                            last = a;
+                        }
+                    }
+                })
+            })
+            .collect();
+        let writers: Vec<_> = (0..3u64)
+            .map(|w| {
+                let stack = stack.clone();
+                thread::spawn(move || {
+                    for i in (0..10_000u64).rev() {
+                        stack.push((i * 3 + w, i * 3 + w));
+                    }
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+        done.store(true, Ordering::Relaxed);
+        for reader in readers {
+            reader.join().unwrap();
+        }
+        assert_eq!(stack.min(), Some((0, 0)));
+        assert_eq!(stack.len(), 30_000);
+    }
+}
@@ -0,0 +1,203 @@
+#![deny(missing_docs)]
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::mem::ManuallyDrop;
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, Ordering};
+
+use super::epoch;
+
+struct Node<T> {
+    // Moved out by the `pop` that unlinks the node, before it is retired.
+    value: ManuallyDrop<T>,
+    next: *mut Node<T>,
+}
+
+// A node only reaches another thread through `epoch::Guard::defer_destroy`,
+// after `pop` has moved its value out, so freeing it there never touches a
+// `T`.
+unsafe impl<T> Send for Node<T> {}
+
+/// A lock-free stack (Treiber's algorithm) that any number of threads can
+/// push to and pop from at once.
+/// The thread-safe counterpart of `collections::stack_with_queue::Stack`.
+///
+/// Popped nodes are freed through `epoch`, so a thread still reading a
+/// node never sees it freed or reused.
+/// ## Examples
+/// ```
+/// # use stdlib_rs::concurrent::TreiberStack;
+/// # use std::sync::Arc;
+/// # use std::thread;
+/// let stack = Arc::new(TreiberStack::new());
+/// let handles: Vec<_> = (0..4)
+///     .map(|i| {
+///         let stack = stack.clone();
+///         thread::spawn(move || stack.push(i))
+///     })
+///     .collect();
+/// for handle in handles {
+///     handle.join().unwrap();
+/// }
+/// let mut items: Vec<i32> = std::iter::from_fn(|| stack.pop()).collect();
+/// items.sort();
+/// assert_eq!(items, vec![0, 1, 2, 3]);
+/// ```
+pub struct TreiberStack<T> {
+    head: AtomicPtr<Node<T>>,
+    _marker: PhantomData<T>,
+}
+
+unsafe impl<T: Send> Send for TreiberStack<T> {}
+unsafe impl<T: Send> Sync for TreiberStack<T> {}
+
+impl<T> TreiberStack<T> {
+    /// Creates a new, empty TreiberStack.
+    pub fn new() -> Self {
+        TreiberStack {
+            head: AtomicPtr::new(ptr::null_mut()),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Adds an item to the top of the stack.
+    pub fn push(&self, value: T) {
+        let node = Box::into_raw(Box::new(Node {
+            value: ManuallyDrop::new(value),
+            next: ptr::null_mut(),
+        }));
+        let mut head = self.head.load(Ordering::Relaxed);
+        loop {
+            // `node` is not shared until the exchange succeeds.
+            unsafe { (*node).next = head };
+            match self
+                .head
+                .compare_exchange_weak(head, node, Ordering::Release, Ordering::Relaxed)
+            {
+                Ok(_) => return,
+                Err(now) => head = now,
+            }
+        }
+    }
+
+    /// Removes the top item and returns it, or `None` if it is empty.
+    pub fn pop(&self) -> Option<T> {
+        let guard = epoch::pin();
+        let mut head = self.head.load(Ordering::Acquire);
+        loop {
+            // Pinned, so `head` cannot be freed until the guard is dropped.
+            let node = unsafe { head.as_ref() }?;
+            match self.head.compare_exchange_weak(
+                head,
+                node.next,
+                Ordering::Acquire,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => unsafe {
+                    let value = ptr::read(&*node.value);
+                    guard.defer_destroy(head);
+                    return Some(value);
+                },
+                Err(now) => head = now,
+            }
+        }
+    }
+
+    /// Returns `true` if the stack was empty when checked.
+    pub fn is_empty(&self) -> bool {
+        self.head.load(Ordering::Acquire).is_null()
+    }
+}
+
+impl<T> Default for TreiberStack<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for TreiberStack<T> {
+    fn drop(&mut self) {
+        let mut current = *self.head.get_mut();
+        while !current.is_null() {
+            // No other thread can reach the nodes once the stack is dropped.
+            let mut node = unsafe { Box::from_raw(current) };
+            unsafe { ManuallyDrop::drop(&mut node.value) };
+            current = node.next;
+        }
+    }
+}
+
+impl<T> fmt::Debug for TreiberStack<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TreiberStack")
+            .field("is_empty", &self.is_empty())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn lifo_order() {
+        let stack = TreiberStack::new();
+        assert_eq!(stack.pop(), None);
+        for i in 0..10 {
+            stack.push(i);
+        }
+        assert_eq!(stack.pop(), Some(9));
+        stack.push(10);
+        assert_eq!(stack.pop(), Some(10));
+        assert_eq!(stack.pop(), Some(8));
+        assert!(!stack.is_empty());
+    }
+
+    #[test]
+    fn stress() {
+        const THREADS: usize = 8;
+        const PER_THREAD: usize = 10_000;
+        let stack = Arc::new(TreiberStack::new());
+        let handles: Vec<_> = (0..THREADS)
+            .map(|t| {
+                let stack = stack.clone();
+                thread::spawn(move || {
+                    let mut seen = vec![];
+                    for i in 0..PER_THREAD {
+                        stack.push(t * PER_THREAD + i);
+                        if i % 2 == 0 {
+                            if let Some(value) = stack.pop() {
+                                seen.push(value);
+                            }
+                        }
+                    }
+                    seen
+                })
+            })
+            .collect();
+        let mut all: Vec<usize> = handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect();
+        all.extend(std::iter::from_fn(|| stack.pop()));
+        all.sort_unstable();
+        assert_eq!(all, (0..THREADS * PER_THREAD).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn drops_remaining_items() {
+        let counter = Arc::new(());
+        {
+            let stack = TreiberStack::new();
+            for _ in 0..10 {
+                stack.push(counter.clone());
+            }
+            drop(stack.pop());
+            assert_eq!(Arc::strong_count(&counter), 10);
+        }
+        assert_eq!(Arc::strong_count(&counter), 1);
+    }
+}
@@ -1,32 +1,308 @@
-use std::hash::Hasher;
+use core::hash::Hasher;
 
-/// A hasher that returns an adler32 sum.
-pub struct Adler32Hasher(u32, u32, u32);
+/// The largest prime below 2^16, which both sums are reduced by.
+const MOD: u32 = 65521;
+
+/// A hasher that returns an adler32 sum, matching zlib's `adler32`.
+/// ## Examples
+/// ```
+/// # use stdlib_rs::crypto::adler::Adler32Hasher;
+/// # use std::hash::Hasher;
+/// let mut hasher = Adler32Hasher::default();
+/// hasher.write(b"Wikipedia");
+/// assert_eq!(hasher.finish(), 0x11E6_0398);
+/// ```
+pub struct Adler32Hasher(u32, u32);
 
 impl Adler32Hasher {
-    /// The seed provided to the Hasher.
-    pub fn seed(seed: u32) -> Adler32Hasher {
-        Adler32Hasher(seed, 1, 0)
+    /// Creates a hasher that continues from a previous adler32 checksum, like
+    /// passing `adler` to zlib's `adler32(adler, buf, len)`.
+    /// `seed(1)` is the same as `default()`.
+    /// ## Migrating
+    /// `seed` used to set the modulus, so any seed other than 65521 produced
+    /// a checksum that was not adler32. Replace `seed(65521)` with
+    /// `default()`; other old seeds have no adler32 equivalent.
+    /// ## Examples
+    /// ```
+    /// # use stdlib_rs::crypto::adler::Adler32Hasher;
+    /// # use std::hash::Hasher;
+    /// let mut first = Adler32Hasher::default();
+    /// first.write(b"Wiki");
+    /// let mut rest = Adler32Hasher::seed(first.finish() as u32);
+    /// rest.write(b"pedia");
+    /// assert_eq!(rest.finish(), 0x11E6_0398);
+    /// ```
+    pub fn seed(adler: u32) -> Adler32Hasher {
+        Adler32Hasher((adler & 0xFFFF) % MOD, (adler >> 16) % MOD)
     }
 }
 
 impl Default for Adler32Hasher {
     fn default() -> Adler32Hasher {
-        Adler32Hasher(65521, 1, 0)
+        Adler32Hasher::seed(1)
     }
 }
 
 impl Hasher for Adler32Hasher {
     fn write(&mut self, bytes: &[u8]) {
-        for byte in bytes.iter() {
-            self.1 = (self.1 + *byte as u32) % self.0;
-            self.2 = (self.2 + self.1) % self.0;
-        }
+        let (a, b) = update(self.0, self.1, bytes);
+        self.0 = a;
+        self.1 = b;
     }
 
     fn finish(&self) -> u64 {
-        (self.2 << 16 | self.1).into()
+        (self.1 << 16 | self.0).into()
+    }
+}
+
+/// The most bytes that can be added to sums below `MOD` before `b` could
+/// overflow a `u32`, so the sums only need reducing once per `NMAX` bytes.
+const NMAX: usize = 5552;
+
+/// Adds `bytes` to the sums `a` and `b`, which must be below `MOD`, using
+/// the fastest implementation the CPU supports.
+fn update(a: u32, b: u32, bytes: &[u8]) -> (u32, u32) {
+    #[cfg(all(feature = "std", target_arch = "x86_64"))]
+    {
+        if std::is_x86_feature_detected!("avx2") {
+            return unsafe { x86::update_avx2(a, b, bytes) };
+        }
+        if std::is_x86_feature_detected!("ssse3") {
+            return unsafe { x86::update_ssse3(a, b, bytes) };
+        }
+    }
+    update_scalar(a, b, bytes)
+}
+
+/// Adds `bytes` to the sums `a` and `b`, 16 bytes at a time.
+fn update_scalar(mut a: u32, mut b: u32, bytes: &[u8]) -> (u32, u32) {
+    for chunk in bytes.chunks(NMAX) {
+        let mut blocks = chunk.chunks_exact(16);
+        for block in &mut blocks {
+            // Each byte is added to `b` once for itself and once for every
+            // byte after it in the block, and `a` is added once per byte.
+            let mut sum = 0;
+            let mut weighted = 0;
+            for (i, &byte) in block.iter().enumerate() {
+                sum += byte as u32;
+                weighted += (16 - i as u32) * byte as u32;
+            }
+            b += 16 * a + weighted;
+            a += sum;
+        }
+        for &byte in blocks.remainder() {
+            a += byte as u32;
+            b += a;
+        }
+        a %= MOD;
+        b %= MOD;
+    }
+    (a, b)
+}
+
+#[cfg(all(feature = "std", target_arch = "x86_64"))]
+mod x86 {
+    use super::{update_scalar, MOD, NMAX};
+    use core::arch::x86_64::*;
+
+    /// The bytes summed per vector step.
+    const BLOCK: usize = 32;
+
+    /// Adds `bytes` to the sums with AVX2, 32 bytes per step.
+    /// ## Safety
+    /// The CPU must support AVX2.
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn update_avx2(mut a: u32, mut b: u32, bytes: &[u8]) -> (u32, u32) {
+        let taps = _mm256_setr_epi8(
+            32, 31, 30, 29, 28, 27, 26, 25, 24, 23, 22, 21, 20, 19, 18, 17, 16, 15, 14, 13, 12, 11,
+            10, 9, 8, 7, 6, 5, 4, 3, 2, 1,
+        );
+        let zero = _mm256_setzero_si256();
+        let ones = _mm256_set1_epi16(1);
+        let mut chunks = bytes.chunks_exact(BLOCK);
+        // Whole blocks, at most `NMAX` bytes between reductions.
+        let blocks = chunks.by_ref().len();
+        let mut p = bytes.as_ptr();
+        let mut remaining = blocks;
+        while remaining > 0 {
+            let n = remaining.min(NMAX / BLOCK);
+            remaining -= n;
+            // `a` is added to `b` once per byte of the `n` blocks, and the
+            // `a` of each block once per byte of every later block.
+            let mut v_ps = _mm256_set_epi32(0, 0, 0, 0, 0, 0, 0, (a * n as u32) as i32);
+            let mut v_s2 = _mm256_set_epi32(0, 0, 0, 0, 0, 0, 0, b as i32);
+            let mut v_s1 = zero;
+            for _ in 0..n {
+                let block = _mm256_loadu_si256(p as *const __m256i);
+                v_ps = _mm256_add_epi32(v_ps, v_s1);
+                v_s1 = _mm256_add_epi32(v_s1, _mm256_sad_epu8(block, zero));
+                let weighted = _mm256_maddubs_epi16(block, taps);
+                v_s2 = _mm256_add_epi32(v_s2, _mm256_madd_epi16(weighted, ones));
+                p = p.add(BLOCK);
+            }
+            v_s2 = _mm256_add_epi32(v_s2, _mm256_slli_epi32(v_ps, 5));
+            a = (a + sum256(v_s1)) % MOD;
+            b = sum256(v_s2) % MOD;
+        }
+        update_scalar(a, b, chunks.remainder())
+    }
+
+    /// Adds `bytes` to the sums with SSSE3, 32 bytes per step.
+    /// ## Safety
+    /// The CPU must support SSSE3.
+    #[target_feature(enable = "ssse3")]
+    pub(super) unsafe fn update_ssse3(mut a: u32, mut b: u32, bytes: &[u8]) -> (u32, u32) {
+        let taps_1 = _mm_setr_epi8(
+            32, 31, 30, 29, 28, 27, 26, 25, 24, 23, 22, 21, 20, 19, 18, 17,
+        );
+        let taps_2 = _mm_setr_epi8(16, 15, 14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1);
+        let zero = _mm_setzero_si128();
+        let ones = _mm_set1_epi16(1);
+        let mut chunks = bytes.chunks_exact(BLOCK);
+        let blocks = chunks.by_ref().len();
+        let mut p = bytes.as_ptr();
+        let mut remaining = blocks;
+        while remaining > 0 {
+            let n = remaining.min(NMAX / BLOCK);
+            remaining -= n;
+            let mut v_ps = _mm_set_epi32(0, 0, 0, (a * n as u32) as i32);
+            let mut v_s2 = _mm_set_epi32(0, 0, 0, b as i32);
+            let mut v_s1 = zero;
+            for _ in 0..n {
+                let block_1 = _mm_loadu_si128(p as *const __m128i);
+                let block_2 = _mm_loadu_si128(p.add(16) as *const __m128i);
+                v_ps = _mm_add_epi32(v_ps, v_s1);
+                v_s1 = _mm_add_epi32(v_s1, _mm_sad_epu8(block_1, zero));
+                let weighted = _mm_maddubs_epi16(block_1, taps_1);
+                v_s2 = _mm_add_epi32(v_s2, _mm_madd_epi16(weighted, ones));
+                v_s1 = _mm_add_epi32(v_s1, _mm_sad_epu8(block_2, zero));
+                let weighted = _mm_maddubs_epi16(block_2, taps_2);
+                v_s2 = _mm_add_epi32(v_s2, _mm_madd_epi16(weighted, ones));
+                p = p.add(BLOCK);
+            }
+            v_s2 = _mm_add_epi32(v_s2, _mm_slli_epi32(v_ps, 5));
+            a = (a + sum128(v_s1)) % MOD;
+            b = sum128(v_s2) % MOD;
+        }
+        update_scalar(a, b, chunks.remainder())
+    }
+
+    #[target_feature(enable = "avx2")]
+    unsafe fn sum256(v: __m256i) -> u32 {
+        let mut lanes = [0u32; 8];
+        _mm256_storeu_si256(lanes.as_mut_ptr() as *mut __m256i, v);
+        lanes.iter().sum()
+    }
+
+    #[target_feature(enable = "ssse3")]
+    unsafe fn sum128(v: __m128i) -> u32 {
+        let mut lanes = [0u32; 4];
+        _mm_storeu_si128(lanes.as_mut_ptr() as *mut __m128i, v);
+        lanes.iter().sum()
+    }
+}
+
+/// The adler32 checksum of a window of bytes that slides over a buffer,
+/// updated in O(1) time per byte.
+/// This is the weak checksum rsync uses to find matching blocks.
+/// ## Examples
+/// ```
+/// # use stdlib_rs::crypto::adler::RollingAdler32;
+/// let data = b"rolling checksums";
+/// let mut rolling = RollingAdler32::new(&data[..4]);
+/// for i in 4..data.len() {
+///     rolling.rotate(data[i - 4], data[i]);
+/// }
+/// assert_eq!(rolling.checksum(), RollingAdler32::new(b"sums").checksum());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RollingAdler32 {
+    a: u32,
+    b: u32,
+    len: usize,
+}
+
+impl RollingAdler32 {
+    /// Creates the checksum of `window`.
+    pub fn new(window: &[u8]) -> RollingAdler32 {
+        let (a, b) = update(1, 0, window);
+        RollingAdler32 {
+            a,
+            b,
+            len: window.len(),
+        }
+    }
+
+    /// Adds `byte` to the end of the window.
+    pub fn roll_in(&mut self, byte: u8) {
+        self.a = (self.a + byte as u32) % MOD;
+        self.b = (self.b + self.a) % MOD;
+        self.len += 1;
+    }
+
+    /// Removes `byte`, which must be the first byte of the window, from the
+    /// window.
+    /// ## Panics
+    /// Panics if the window is empty.
+    pub fn roll_out(&mut self, byte: u8) {
+        assert!(self.len > 0, "cannot roll out of an empty window");
+        // The first byte was added to `b` once for every byte in the window,
+        // and `b` also counts the initial 1 of `a` once per byte.
+        let weight = (self.len % MOD as usize) as u32;
+        self.a = (self.a + MOD - byte as u32) % MOD;
+        self.b = (self.b + 2 * MOD - (weight * byte as u32 + 1) % MOD) % MOD;
+        self.len -= 1;
+    }
+
+    /// Slides the window one byte forward, removing `old`, which must be
+    /// the first byte of the window, and adding `new` to the end.
+    pub fn rotate(&mut self, old: u8, new: u8) {
+        self.roll_out(old);
+        self.roll_in(new);
     }
+
+    /// Returns the number of bytes in the window.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the window has no bytes.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the adler32 checksum of the window.
+    pub fn checksum(&self) -> u32 {
+        self.b << 16 | self.a
+    }
+}
+
+/// Returns the adler32 of two buffers joined together, given the adler32 of
+/// each and the length of the second, like zlib's `adler32_combine`.
+/// ## Examples
+/// ```
+/// # use stdlib_rs::crypto::adler::{adler32_combine, Adler32Hasher};
+/// # use std::hash::Hasher;
+/// let adler = |bytes: &[u8]| {
+///     let mut hasher = Adler32Hasher::default();
+///     hasher.write(bytes);
+///     hasher.finish() as u32
+/// };
+/// let joined = adler32_combine(adler(b"Wiki"), adler(b"pedia"), 5);
+/// assert_eq!(joined, adler(b"Wikipedia"));
+/// ```
+pub fn adler32_combine(adler_a: u32, adler_b: u32, len_b: u64) -> u32 {
+    let rem = (len_b % MOD as u64) as u32;
+    let a_a = adler_a & 0xFFFF;
+    let b_a = adler_a >> 16;
+    let a_b = adler_b & 0xFFFF;
+    let b_b = adler_b >> 16;
+    // Joining shifts each byte of the first buffer `len_b` places further
+    // from the end, adding `len_b` copies of its A sum to B. Both A sums
+    // count the initial 1, so one is taken away from each sum.
+    let a = (a_a + a_b + MOD - 1) % MOD;
+    let b = (rem * a_a % MOD + b_a + b_b + MOD - rem) % MOD;
+    b << 16 | a
 }
 
 #[cfg(test)]
@@ -48,4 +324,126 @@ mod test {
     fn test_123() {
         assert_eq!(adler_32(b"123"), 19726487);
     }
+
+    #[test]
+    fn rfc_1950_vectors() {
+        // Checksums computed with zlib's `adler32`.
+        let vectors: [(&[u8], u64); 7] = [
+            (b"a", 0x0062_0062),
+            (b"abc", 0x024D_0127),
+            (b"message digest", 0x2975_0586),
+            (b"abcdefghijklmnopqrstuvwxyz", 0x9086_0B20),
+            (
+                b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789",
+                0x8ADB_150C,
+            ),
+            (
+                b"12345678901234567890123456789012345678901234567890123456789012345678901234567890",
+                0x97B6_1069,
+            ),
+            (b"Wikipedia", 0x11E6_0398),
+        ];
+        for (bytes, sum) in vectors.iter() {
+            assert_eq!(adler_32(bytes), *sum);
+        }
+    }
+
+    #[test]
+    fn large_inputs_wrap_the_modulus() {
+        assert_eq!(adler_32(&[0xFF; 5552]), 0xF18F_9B8C);
+        assert_eq!(adler_32(&[0xFF; 100_000]), 0x149A_302C);
+    }
+
+    #[test]
+    fn seed_continues_a_checksum() {
+        let mut hasher = Adler32Hasher::seed(adler_32(b"abc") as u32);
+        hasher.write(b"Wikipedia");
+        assert_eq!(hasher.finish(), 0x1E89_04BE);
+        assert_eq!(Adler32Hasher::seed(1).finish(), 1);
+    }
+
+    #[test]
+    fn rolling_matches_hasher() {
+        let data: Vec<u8> = (0..2000u32).map(|i| (i * 7919 % 251) as u8).collect();
+        let window = 64;
+        let mut rolling = RollingAdler32::new(&data[..window]);
+        for start in 1..=data.len() - window {
+            rolling.rotate(data[start - 1], data[start + window - 1]);
+            assert_eq!(
+                rolling.checksum() as u64,
+                adler_32(&data[start..start + window])
+            );
+        }
+    }
+
+    #[test]
+    fn roll_in_and_out() {
+        let mut rolling = RollingAdler32::new(b"");
+        assert!(rolling.is_empty());
+        for &byte in b"\xFF\xFFabc" {
+            rolling.roll_in(byte);
+        }
+        rolling.roll_out(0xFF);
+        rolling.roll_out(0xFF);
+        assert_eq!(rolling.len(), 3);
+        assert_eq!(rolling, RollingAdler32::new(b"abc"));
+        for &byte in b"abc" {
+            rolling.roll_out(byte);
+        }
+        assert_eq!(rolling.checksum(), 1);
+    }
+
+    #[test]
+    fn combine_every_split() {
+        let data: Vec<u8> = (0..300u32).map(|i| (i * 131 % 256) as u8).collect();
+        let whole = adler_32(&data) as u32;
+        for split in 0..=data.len() {
+            let (a, b) = data.split_at(split);
+            let combined = adler32_combine(adler_32(a) as u32, adler_32(b) as u32, b.len() as u64);
+            assert_eq!(combined, whole);
+        }
+        assert_eq!(
+            adler32_combine(adler_32(&[0xFF; 70_000]) as u32, 1, 0),
+            adler_32(&[0xFF; 70_000]) as u32
+        );
+    }
+
+    /// Adds one byte at a time, reducing after each byte.
+    fn reference(bytes: &[u8]) -> (u32, u32) {
+        let (mut a, mut b) = (1, 0);
+        for &byte in bytes {
+            a = (a + byte as u32) % MOD;
+            b = (b + a) % MOD;
+        }
+        (a, b)
+    }
+
+    #[test]
+    fn differential() {
+        let mut state = 1u32;
+        let random: Vec<u8> = (0..40_000)
+            .map(|_| {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                (state >> 16) as u8
+            })
+            .collect();
+        let ones = vec![0xFF; 40_000];
+        for data in [&random, &ones] {
+            for len in (0..100).chain([5551, 5552, 5553, 11_104, 40_000]) {
+                let bytes = &data[..len];
+                let expected = reference(bytes);
+                assert_eq!(update_scalar(1, 0, bytes), expected);
+                assert_eq!(update(1, 0, bytes), expected);
+                #[cfg(all(feature = "std", target_arch = "x86_64"))]
+                unsafe {
+                    if std::is_x86_feature_detected!("ssse3") {
+                        assert_eq!(x86::update_ssse3(1, 0, bytes), expected);
+                    }
+                    if std::is_x86_feature_detected!("avx2") {
+                        assert_eq!(x86::update_avx2(1, 0, bytes), expected);
+                    }
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,419 @@
+#![deny(missing_docs)]
+
+use core::convert::TryInto;
+
+use super::digest::Digest;
+
+/// The order message words are read in each round, shared by BLAKE2b and
+/// BLAKE2s.
+const SIGMA: [[usize; 16]; 10] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+];
+
+/// A BLAKE2 hash of up to `N` bytes, as long as the hasher was asked for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Blake2Output<const N: usize> {
+    bytes: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> AsRef<[u8]> for Blake2Output<N> {
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes[..self.len]
+    }
+}
+
+macro_rules! blake2 {
+    (
+        $(#[$hasher_meta:meta])*
+        hasher: $hasher:ident,
+        $(#[$params_meta:meta])*
+        params: $params:ident,
+        word: $word:ty,
+        counter: $counter:ty,
+        block: $block:literal,
+        output: $output:literal,
+        salt: $salt:literal,
+        rounds: $rounds:literal,
+        rotations: [$r1:literal, $r2:literal, $r3:literal, $r4:literal],
+        iv: $iv:expr,
+    ) => {
+        $(#[$params_meta])*
+        #[derive(Debug, Clone, Copy)]
+        pub struct $params {
+            output_len: usize,
+            key: [u8; $output],
+            key_len: usize,
+            salt: [u8; $salt],
+            personal: [u8; $salt],
+        }
+
+        impl $params {
+            /// Parameters for an unkeyed hash of the full output length.
+            pub fn new() -> $params {
+                $params {
+                    output_len: $output,
+                    key: [0; $output],
+                    key_len: 0,
+                    salt: [0; $salt],
+                    personal: [0; $salt],
+                }
+            }
+
+            /// Sets how many bytes the hash is.
+            /// ## Panics
+            #[doc = concat!("Panics if `len` is zero or more than ", $output, ".")]
+            pub fn output_len(mut self, len: usize) -> $params {
+                assert!(len > 0 && len <= $output, "invalid output length");
+                self.output_len = len;
+                self
+            }
+
+            /// Sets the key, which turns the hash into a MAC.
+            /// ## Panics
+            #[doc = concat!("Panics if `key` is longer than ", $output, " bytes.")]
+            pub fn key(mut self, key: &[u8]) -> $params {
+                assert!(key.len() <= $output, "key is too long");
+                self.key = [0; $output];
+                self.key[..key.len()].copy_from_slice(key);
+                self.key_len = key.len();
+                self
+            }
+
+            /// Sets the salt, which is padded with zeros.
+            /// ## Panics
+            #[doc = concat!("Panics if `salt` is longer than ", $salt, " bytes.")]
+            pub fn salt(mut self, salt: &[u8]) -> $params {
+                assert!(salt.len() <= $salt, "salt is too long");
+                self.salt = [0; $salt];
+                self.salt[..salt.len()].copy_from_slice(salt);
+                self
+            }
+
+            /// Sets the personalization, which keeps hashes made for one
+            /// purpose apart from those made for another. It is padded with
+            /// zeros.
+            /// ## Panics
+            #[doc = concat!("Panics if `personal` is longer than ", $salt, " bytes.")]
+            pub fn personal(mut self, personal: &[u8]) -> $params {
+                assert!(personal.len() <= $salt, "personalization is too long");
+                self.personal = [0; $salt];
+                self.personal[..personal.len()].copy_from_slice(personal);
+                self
+            }
+
+            /// Returns a hasher with these parameters.
+            pub fn to_hasher(&self) -> $hasher {
+                const WORD: usize = core::mem::size_of::<$word>();
+                let mut block = [0u8; 8 * WORD];
+                block[0] = self.output_len as u8;
+                block[1] = self.key_len as u8;
+                // A fanout and depth of one: a sequential hash, not a tree.
+                block[2] = 1;
+                block[3] = 1;
+                block[4 * WORD..6 * WORD].copy_from_slice(&self.salt);
+                block[6 * WORD..].copy_from_slice(&self.personal);
+                let mut h = $iv;
+                for (word, bytes) in h.iter_mut().zip(block.chunks_exact(WORD)) {
+                    *word ^= <$word>::from_le_bytes(bytes.try_into().unwrap());
+                }
+                let mut hasher = $hasher {
+                    h,
+                    counter: 0,
+                    buffer: [0; $block],
+                    buffered: 0,
+                    output_len: self.output_len,
+                };
+                if self.key_len > 0 {
+                    // The key, padded to a block, is hashed before the input.
+                    hasher.buffer[..self.key_len].copy_from_slice(&self.key[..self.key_len]);
+                    hasher.buffered = $block;
+                }
+                hasher
+            }
+        }
+
+        impl Default for $params {
+            fn default() -> $params {
+                $params::new()
+            }
+        }
+
+        $(#[$hasher_meta])*
+        #[derive(Debug, Clone)]
+        pub struct $hasher {
+            h: [$word; 8],
+            counter: $counter,
+            buffer: [u8; $block],
+            buffered: usize,
+            output_len: usize,
+        }
+
+        impl $hasher {
+            /// Returns an unkeyed hasher whose hashes are `output_len` bytes.
+            /// ## Panics
+            #[doc = concat!("Panics if `output_len` is zero or more than ", $output, ".")]
+            pub fn new(output_len: usize) -> $hasher {
+                $params::new().output_len(output_len).to_hasher()
+            }
+
+            fn compress(&mut self, last: bool) {
+                const WORD: usize = core::mem::size_of::<$word>();
+                let mut m = [0; 16];
+                for (word, bytes) in m.iter_mut().zip(self.buffer.chunks_exact(WORD)) {
+                    *word = <$word>::from_le_bytes(bytes.try_into().unwrap());
+                }
+                let iv = $iv;
+                let mut v = [0; 16];
+                v[..8].copy_from_slice(&self.h);
+                v[8..].copy_from_slice(&iv);
+                v[12] ^= self.counter as $word;
+                v[13] ^= (self.counter >> (8 * WORD)) as $word;
+                if last {
+                    v[14] = !v[14];
+                }
+                for round in 0..$rounds {
+                    let s = &SIGMA[round % 10];
+                    let mut g = |a: usize, b: usize, c: usize, d: usize, x: $word, y: $word| {
+                        v[a] = v[a].wrapping_add(v[b]).wrapping_add(x);
+                        v[d] = (v[d] ^ v[a]).rotate_right($r1);
+                        v[c] = v[c].wrapping_add(v[d]);
+                        v[b] = (v[b] ^ v[c]).rotate_right($r2);
+                        v[a] = v[a].wrapping_add(v[b]).wrapping_add(y);
+                        v[d] = (v[d] ^ v[a]).rotate_right($r3);
+                        v[c] = v[c].wrapping_add(v[d]);
+                        v[b] = (v[b] ^ v[c]).rotate_right($r4);
+                    };
+                    g(0, 4, 8, 12, m[s[0]], m[s[1]]);
+                    g(1, 5, 9, 13, m[s[2]], m[s[3]]);
+                    g(2, 6, 10, 14, m[s[4]], m[s[5]]);
+                    g(3, 7, 11, 15, m[s[6]], m[s[7]]);
+                    g(0, 5, 10, 15, m[s[8]], m[s[9]]);
+                    g(1, 6, 11, 12, m[s[10]], m[s[11]]);
+                    g(2, 7, 8, 13, m[s[12]], m[s[13]]);
+                    g(3, 4, 9, 14, m[s[14]], m[s[15]]);
+                }
+                for i in 0..8 {
+                    self.h[i] ^= v[i] ^ v[i + 8];
+                }
+            }
+        }
+
+        impl Default for $hasher {
+            fn default() -> $hasher {
+                $params::new().to_hasher()
+            }
+        }
+
+        impl Digest for $hasher {
+            type Output = Blake2Output<$output>;
+            const BLOCK_LEN: usize = $block;
+
+            fn update(&mut self, mut bytes: &[u8]) {
+                while !bytes.is_empty() {
+                    // The last block is compressed differently, so a full
+                    // buffer waits until more input shows it is not the last.
+                    if self.buffered == $block {
+                        self.counter += $block;
+                        self.compress(false);
+                        self.buffered = 0;
+                    }
+                    let n = bytes.len().min($block - self.buffered);
+                    self.buffer[self.buffered..self.buffered + n].copy_from_slice(&bytes[..n]);
+                    self.buffered += n;
+                    bytes = &bytes[n..];
+                }
+            }
+
+            fn finalize(mut self) -> Blake2Output<$output> {
+                self.counter += self.buffered as $counter;
+                self.buffer[self.buffered..].fill(0);
+                self.compress(true);
+                let mut bytes = [0; $output];
+                let words = self.h.iter().flat_map(|word| word.to_le_bytes());
+                for (byte, value) in bytes.iter_mut().zip(words) {
+                    *byte = value;
+                }
+                Blake2Output {
+                    bytes,
+                    len: self.output_len,
+                }
+            }
+        }
+    };
+}
+
+blake2! {
+    /// BLAKE2b, which is tuned for 64-bit machines and returns up to 64
+    /// bytes.
+    /// ## Examples
+    /// ```
+    /// # use stdlib_rs::crypto::blake2::{Blake2b, Blake2bParams};
+    /// # use stdlib_rs::crypto::digest::Digest;
+    /// let hash = Blake2b::digest(b"abc");
+    /// assert_eq!(hash.as_ref().len(), 64);
+    ///
+    /// let mut mac = Blake2bParams::new().output_len(32).key(b"secret").to_hasher();
+    /// mac.update(b"abc");
+    /// assert_eq!(mac.finalize().as_ref().len(), 32);
+    /// ```
+    hasher: Blake2b,
+    /// The output length, key, salt and personalization of a BLAKE2b hash.
+    params: Blake2bParams,
+    word: u64,
+    counter: u128,
+    block: 128,
+    output: 64,
+    salt: 16,
+    rounds: 12,
+    rotations: [32, 24, 16, 63],
+    iv: [
+        0x6A09_E667_F3BC_C908,
+        0xBB67_AE85_84CA_A73B,
+        0x3C6E_F372_FE94_F82B,
+        0xA54F_F53A_5F1D_36F1,
+        0x510E_527F_ADE6_82D1,
+        0x9B05_688C_2B3E_6C1F,
+        0x1F83_D9AB_FB41_BD6B,
+        0x5BE0_CD19_137E_2179,
+    ],
+}
+
+blake2! {
+    /// BLAKE2s, which is tuned for 32-bit machines and returns up to 32
+    /// bytes.
+    /// ## Examples
+    /// ```
+    /// # use stdlib_rs::crypto::blake2::{Blake2s, Blake2sParams};
+    /// # use stdlib_rs::crypto::digest::Digest;
+    /// let params = Blake2sParams::new().salt(b"salt").personal(b"app v1");
+    /// let mut hasher = params.to_hasher();
+    /// hasher.update(b"abc");
+    /// assert_ne!(hasher.finalize(), Blake2s::digest(b"abc"));
+    /// ```
+    hasher: Blake2s,
+    /// The output length, key, salt and personalization of a BLAKE2s hash.
+    params: Blake2sParams,
+    word: u32,
+    counter: u64,
+    block: 64,
+    output: 32,
+    salt: 8,
+    rounds: 10,
+    rotations: [16, 12, 8, 7],
+    iv: [
+        0x6A09_E667,
+        0xBB67_AE85,
+        0x3C6E_F372,
+        0xA54F_F53A,
+        0x510E_527F,
+        0x9B05_688C,
+        0x1F83_D9AB,
+        0x5BE0_CD19,
+    ],
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::test_util::{hex, pattern};
+    use super::*;
+
+    #[test]
+    fn unkeyed() {
+        assert_eq!(
+            hex(Blake2b::digest(b"abc").as_ref()),
+            "ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d1\
+             7d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923"
+        );
+        assert_eq!(
+            hex(Blake2s::digest(b"abc").as_ref()),
+            "508c5e8c327c14e2e1a72ba34eeb452f37458b209ed63a294d999b4c86675982"
+        );
+    }
+
+    #[test]
+    fn keyed() {
+        let input = pattern(1000);
+        let key: Vec<u8> = (0..64).collect();
+        let mut blake2b = Blake2bParams::new().key(&key).to_hasher();
+        let mut blake2s = Blake2sParams::new().key(&key[..32]).to_hasher();
+        for piece in input.chunks(97) {
+            blake2b.update(piece);
+            blake2s.update(piece);
+        }
+        assert_eq!(
+            hex(blake2b.finalize().as_ref()),
+            "715377e0611515b904d259ce52fc8e5d2c50468b1680b2984786b6949cc571f4\
+             53d28cfb6969cb523ec84e06bf2a4465f3f37511db7792228d038942935750c1"
+        );
+        assert_eq!(
+            hex(blake2s.finalize().as_ref()),
+            "d5c42863172fb2424de520ff25866bf2ac9201ce81b6a8b703f67ea4c6735767"
+        );
+        // Input that ends on a block boundary, and no input after the key.
+        let mut blake2b = Blake2bParams::new().key(&key).to_hasher();
+        blake2b.update(&input[..256]);
+        assert_eq!(
+            hex(blake2b.finalize().as_ref()),
+            "38efcfc158f8057f5365285db9184c77ddf4d53090fd89ef261815370fd994a1\
+             b23b3e3336d7ff97823271e7e50042576ce14feadab1e8357346ffa335a3e97e"
+        );
+        assert_eq!(
+            hex(Blake2sParams::new()
+                .key(&key[..32])
+                .to_hasher()
+                .finalize()
+                .as_ref()),
+            "48a8997da407876b3d79c0d92325ad3b89cbb754d86ab71aee047ad345fd2c49"
+        );
+    }
+
+    #[test]
+    fn all_parameters() {
+        let mut blake2b = Blake2bParams::new()
+            .output_len(20)
+            .key(b"secret key")
+            .salt(b"salty")
+            .personal(b"me")
+            .to_hasher();
+        let mut blake2s = Blake2sParams::new()
+            .output_len(20)
+            .key(b"secret key")
+            .salt(b"salty")
+            .personal(b"me")
+            .to_hasher();
+        blake2b.update(b"abc");
+        blake2s.update(b"abc");
+        assert_eq!(
+            hex(blake2b.finalize().as_ref()),
+            "2a37ca0aaf582525ff0b1c4de0907e7ab478d906"
+        );
+        assert_eq!(
+            hex(blake2s.finalize().as_ref()),
+            "c27e5b23431f52450f084527f9d679cba8e0d1cb"
+        );
+        assert_ne!(
+            Blake2b::new(32).finalize(),
+            Blake2bParams::new()
+                .output_len(32)
+                .personal(b"other")
+                .to_hasher()
+                .finalize()
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn output_too_long() {
+        Blake2s::new(33);
+    }
+}
@@ -0,0 +1,539 @@
+#![deny(missing_docs)]
+
+use core::convert::TryInto;
+
+use super::digest::Digest;
+
+const OUT_LEN: usize = 32;
+const BLOCK_LEN: usize = 64;
+const CHUNK_LEN: usize = 1024;
+
+const CHUNK_START: u32 = 1 << 0;
+const CHUNK_END: u32 = 1 << 1;
+const PARENT: u32 = 1 << 2;
+const ROOT: u32 = 1 << 3;
+const KEYED_HASH: u32 = 1 << 4;
+const DERIVE_KEY_CONTEXT: u32 = 1 << 5;
+const DERIVE_KEY_MATERIAL: u32 = 1 << 6;
+
+/// The initial hash of SHA-256, which BLAKE3 uses as its key when it is not
+/// given one.
+const IV: [u32; 8] = [
+    0x6A09_E667,
+    0xBB67_AE85,
+    0x3C6E_F372,
+    0xA54F_F53A,
+    0x510E_527F,
+    0x9B05_688C,
+    0x1F83_D9AB,
+    0x5BE0_CD19,
+];
+
+const MSG_PERMUTATION: [usize; 16] = [2, 6, 3, 10, 7, 0, 4, 13, 1, 11, 12, 5, 9, 14, 15, 8];
+
+/// The most subtrees the stack can hold, enough for 2^64 bytes of input.
+const MAX_DEPTH: usize = 54;
+
+/// Inputs shorter than this are not worth a thread of their own.
+#[cfg(feature = "std")]
+const MIN_PARALLEL_LEN: usize = 16 * CHUNK_LEN;
+
+fn g(state: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize, x: u32, y: u32) {
+    state[a] = state[a].wrapping_add(state[b]).wrapping_add(x);
+    state[d] = (state[d] ^ state[a]).rotate_right(16);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_right(12);
+    state[a] = state[a].wrapping_add(state[b]).wrapping_add(y);
+    state[d] = (state[d] ^ state[a]).rotate_right(8);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_right(7);
+}
+
+fn compress(cv: &[u32; 8], block: &[u32; 16], counter: u64, len: u32, flags: u32) -> [u32; 16] {
+    let mut state = [
+        cv[0],
+        cv[1],
+        cv[2],
+        cv[3],
+        cv[4],
+        cv[5],
+        cv[6],
+        cv[7],
+        IV[0],
+        IV[1],
+        IV[2],
+        IV[3],
+        counter as u32,
+        (counter >> 32) as u32,
+        len,
+        flags,
+    ];
+    let mut m = *block;
+    for _ in 0..7 {
+        g(&mut state, 0, 4, 8, 12, m[0], m[1]);
+        g(&mut state, 1, 5, 9, 13, m[2], m[3]);
+        g(&mut state, 2, 6, 10, 14, m[4], m[5]);
+        g(&mut state, 3, 7, 11, 15, m[6], m[7]);
+        g(&mut state, 0, 5, 10, 15, m[8], m[9]);
+        g(&mut state, 1, 6, 11, 12, m[10], m[11]);
+        g(&mut state, 2, 7, 8, 13, m[12], m[13]);
+        g(&mut state, 3, 4, 9, 14, m[14], m[15]);
+        m = MSG_PERMUTATION.map(|i| m[i]);
+    }
+    for i in 0..8 {
+        state[i] ^= state[i + 8];
+        state[i + 8] ^= cv[i];
+    }
+    state
+}
+
+fn words<const N: usize>(bytes: &[u8]) -> [u32; N] {
+    let mut words = [0; N];
+    for (word, bytes) in words.iter_mut().zip(bytes.chunks_exact(4)) {
+        *word = u32::from_le_bytes(bytes.try_into().unwrap());
+    }
+    words
+}
+
+fn first_8(words: [u32; 16]) -> [u32; 8] {
+    words[..8].try_into().unwrap()
+}
+
+/// The last compression of a node, held back until it is known whether the
+/// node is the root.
+struct Output {
+    cv: [u32; 8],
+    block: [u32; 16],
+    counter: u64,
+    len: u32,
+    flags: u32,
+}
+
+impl Output {
+    fn chaining_value(&self) -> [u32; 8] {
+        first_8(compress(
+            &self.cv,
+            &self.block,
+            self.counter,
+            self.len,
+            self.flags,
+        ))
+    }
+
+    /// Fills `out` with the root hash, extended to any length by counting
+    /// up the output blocks.
+    fn root_bytes(&self, out: &mut [u8]) {
+        for (counter, out_block) in out.chunks_mut(2 * OUT_LEN).enumerate() {
+            let words = compress(
+                &self.cv,
+                &self.block,
+                counter as u64,
+                self.len,
+                self.flags | ROOT,
+            );
+            let bytes = words.iter().flat_map(|word| word.to_le_bytes());
+            for (byte, value) in out_block.iter_mut().zip(bytes) {
+                *byte = value;
+            }
+        }
+    }
+}
+
+fn parent_output(left: [u32; 8], right: [u32; 8], key: &[u32; 8], flags: u32) -> Output {
+    let mut block = [0; 16];
+    block[..8].copy_from_slice(&left);
+    block[8..].copy_from_slice(&right);
+    Output {
+        cv: *key,
+        block,
+        counter: 0,
+        len: BLOCK_LEN as u32,
+        flags: flags | PARENT,
+    }
+}
+
+/// A chunk of up to `CHUNK_LEN` bytes, a leaf of the tree.
+#[derive(Debug, Clone)]
+struct ChunkState {
+    cv: [u32; 8],
+    counter: u64,
+    block: [u8; BLOCK_LEN],
+    block_len: usize,
+    blocks_compressed: usize,
+    flags: u32,
+}
+
+impl ChunkState {
+    fn new(key: [u32; 8], counter: u64, flags: u32) -> ChunkState {
+        ChunkState {
+            cv: key,
+            counter,
+            block: [0; BLOCK_LEN],
+            block_len: 0,
+            blocks_compressed: 0,
+            flags,
+        }
+    }
+
+    fn len(&self) -> usize {
+        BLOCK_LEN * self.blocks_compressed + self.block_len
+    }
+
+    fn start_flag(&self) -> u32 {
+        if self.blocks_compressed == 0 {
+            CHUNK_START
+        } else {
+            0
+        }
+    }
+
+    fn update(&mut self, mut bytes: &[u8]) {
+        while !bytes.is_empty() {
+            // The last block ends the chunk, so a full block waits until
+            // more input shows it is not the last.
+            if self.block_len == BLOCK_LEN {
+                let block = words(&self.block);
+                let flags = self.flags | self.start_flag();
+                self.cv = first_8(compress(
+                    &self.cv,
+                    &block,
+                    self.counter,
+                    BLOCK_LEN as u32,
+                    flags,
+                ));
+                self.blocks_compressed += 1;
+                self.block = [0; BLOCK_LEN];
+                self.block_len = 0;
+            }
+            let n = bytes.len().min(BLOCK_LEN - self.block_len);
+            self.block[self.block_len..self.block_len + n].copy_from_slice(&bytes[..n]);
+            self.block_len += n;
+            bytes = &bytes[n..];
+        }
+    }
+
+    fn output(&self) -> Output {
+        Output {
+            cv: self.cv,
+            block: words(&self.block),
+            counter: self.counter,
+            len: self.block_len as u32,
+            flags: self.flags | self.start_flag() | CHUNK_END,
+        }
+    }
+}
+
+/// Returns the chaining value of a subtree that is not the root: `bytes`
+/// is a power of two chunks, the first of which is chunk `counter`.
+/// Halves are hashed on separate threads while `threads` allows.
+// Without `std` there are no threads, so `threads` is only passed down.
+#[cfg_attr(not(feature = "std"), allow(clippy::only_used_in_recursion))]
+fn subtree_cv(bytes: &[u8], key: &[u32; 8], counter: u64, flags: u32, threads: usize) -> [u32; 8] {
+    if bytes.len() <= CHUNK_LEN {
+        let mut chunk = ChunkState::new(*key, counter, flags);
+        chunk.update(bytes);
+        return chunk.output().chaining_value();
+    }
+    let (left, right) = bytes.split_at(bytes.len() / 2);
+    let right_counter = counter + (left.len() / CHUNK_LEN) as u64;
+    #[cfg(feature = "std")]
+    if threads > 1 && bytes.len() >= MIN_PARALLEL_LEN {
+        let (left_cv, right_cv) = std::thread::scope(|scope| {
+            let left_cv = scope.spawn(|| subtree_cv(left, key, counter, flags, threads / 2));
+            let right_cv = subtree_cv(right, key, right_counter, flags, threads - threads / 2);
+            (left_cv.join().unwrap(), right_cv)
+        });
+        return parent_output(left_cv, right_cv, key, flags).chaining_value();
+    }
+    let left_cv = subtree_cv(left, key, counter, flags, threads);
+    let right_cv = subtree_cv(right, key, right_counter, flags, threads);
+    parent_output(left_cv, right_cv, key, flags).chaining_value()
+}
+
+/// BLAKE3, which splits its input into 1 KiB chunks and hashes them as the
+/// leaves of a binary tree. Hashes are 32 bytes, or any length through
+/// `finalize_xof`. With the `std` feature, `update_parallel` hashes
+/// subtrees on several threads.
+/// ## Examples
+/// ```
+/// # use stdlib_rs::crypto::blake3::Blake3;
+/// # use stdlib_rs::crypto::digest::Digest;
+/// let mut hasher = Blake3::default();
+/// hasher.update(b"ab");
+/// hasher.update(b"c");
+/// assert_eq!(hasher.finalize(), Blake3::digest(b"abc"));
+///
+/// let mut mac = Blake3::new_keyed(&[7; 32]);
+/// mac.update(b"abc");
+/// assert_ne!(mac.finalize(), Blake3::digest(b"abc"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Blake3 {
+    chunk: ChunkState,
+    key: [u32; 8],
+    /// The chaining values of the complete subtrees left of `chunk`, largest
+    /// first.
+    cv_stack: [[u32; 8]; MAX_DEPTH],
+    cv_stack_len: usize,
+    flags: u32,
+}
+
+impl Blake3 {
+    fn with_key_words(key: [u32; 8], flags: u32) -> Blake3 {
+        Blake3 {
+            chunk: ChunkState::new(key, 0, flags),
+            key,
+            cv_stack: [[0; 8]; MAX_DEPTH],
+            cv_stack_len: 0,
+            flags,
+        }
+    }
+
+    /// Returns a hasher that computes a MAC with `key`.
+    pub fn new_keyed(key: &[u8; 32]) -> Blake3 {
+        Blake3::with_key_words(words(key), KEYED_HASH)
+    }
+
+    /// Returns a hasher that derives keys from the key material it is fed.
+    /// `context` should be a hardcoded string unique to the application and
+    /// purpose, so keys derived for one purpose never match another's.
+    /// ## Examples
+    /// ```
+    /// # use stdlib_rs::crypto::blake3::Blake3;
+    /// # use stdlib_rs::crypto::digest::Digest;
+    /// let mut kdf = Blake3::new_derive_key("example.com 2026-10-18 session keys");
+    /// kdf.update(b"shared secret");
+    /// let session_key = kdf.finalize();
+    /// ```
+    pub fn new_derive_key(context: &str) -> Blake3 {
+        let mut context_hasher = Blake3::with_key_words(IV, DERIVE_KEY_CONTEXT);
+        context_hasher.update(context.as_bytes());
+        let mut context_key = [0; 32];
+        context_hasher.finalize_xof(&mut context_key);
+        Blake3::with_key_words(words(&context_key), DERIVE_KEY_MATERIAL)
+    }
+
+    /// Adds `bytes` to the input like `update`, hashing independent
+    /// subtrees on up to `threads` threads.
+    /// ## Panics
+    /// Panics if `threads` is zero.
+    /// ## Examples
+    /// ```
+    /// # use stdlib_rs::crypto::blake3::Blake3;
+    /// # use stdlib_rs::crypto::digest::Digest;
+    /// let data = vec![7u8; 1 << 20];
+    /// let mut hasher = Blake3::default();
+    /// hasher.update_parallel(&data, 4);
+    /// assert_eq!(hasher.finalize(), Blake3::digest(&data));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn update_parallel(&mut self, bytes: &[u8], threads: usize) {
+        assert!(threads > 0, "hashing needs at least one thread");
+        self.update_with_threads(bytes, threads);
+    }
+
+    fn update_with_threads(&mut self, mut bytes: &[u8], threads: usize) {
+        while !bytes.is_empty() {
+            // More input follows, so a full chunk is not the root.
+            if self.chunk.len() == CHUNK_LEN {
+                let cv = self.chunk.output().chaining_value();
+                let counter = self.chunk.counter + 1;
+                self.push_cv(cv, counter);
+                self.chunk = ChunkState::new(self.key, counter, self.flags);
+            }
+            if self.chunk.len() == 0 && bytes.len() > CHUNK_LEN {
+                // Hash the largest whole subtree that leaves some input
+                // after it and lines up with the chunks before it.
+                let counter = self.chunk.counter;
+                let max_chunks = ((bytes.len() - 1) / CHUNK_LEN) as u64;
+                let mut chunks = 1 << (63 - max_chunks.leading_zeros());
+                while counter & (chunks - 1) != 0 {
+                    chunks /= 2;
+                }
+                let len = chunks as usize * CHUNK_LEN;
+                let cv = subtree_cv(&bytes[..len], &self.key, counter, self.flags, threads);
+                self.push_cv(cv, (counter + chunks) >> chunks.trailing_zeros());
+                self.chunk = ChunkState::new(self.key, counter + chunks, self.flags);
+                bytes = &bytes[len..];
+            } else {
+                let n = bytes.len().min(CHUNK_LEN - self.chunk.len());
+                self.chunk.update(&bytes[..n]);
+                bytes = &bytes[n..];
+            }
+        }
+    }
+
+    /// Pushes the chaining value of a complete subtree, first merging it
+    /// with the subtrees to its left that it completes. `count` is how many
+    /// subtrees of its size the input has had so far.
+    fn push_cv(&mut self, mut cv: [u32; 8], mut count: u64) {
+        while count & 1 == 0 {
+            self.cv_stack_len -= 1;
+            let left = self.cv_stack[self.cv_stack_len];
+            cv = parent_output(left, cv, &self.key, self.flags).chaining_value();
+            count >>= 1;
+        }
+        self.cv_stack[self.cv_stack_len] = cv;
+        self.cv_stack_len += 1;
+    }
+
+    fn root_output(&self) -> Output {
+        let mut output = self.chunk.output();
+        for left in self.cv_stack[..self.cv_stack_len].iter().rev() {
+            output = parent_output(*left, output.chaining_value(), &self.key, self.flags);
+        }
+        output
+    }
+
+    /// Fills `out` with the hash extended to its length. The first 32 bytes
+    /// are the same as `finalize`.
+    /// ## Examples
+    /// ```
+    /// # use stdlib_rs::crypto::blake3::Blake3;
+    /// # use stdlib_rs::crypto::digest::Digest;
+    /// let mut hasher = Blake3::default();
+    /// hasher.update(b"abc");
+    /// let mut long = [0; 100];
+    /// hasher.finalize_xof(&mut long);
+    /// assert_eq!(long[..32], hasher.finalize());
+    /// ```
+    pub fn finalize_xof(&self, out: &mut [u8]) {
+        self.root_output().root_bytes(out);
+    }
+}
+
+impl Default for Blake3 {
+    fn default() -> Blake3 {
+        Blake3::with_key_words(IV, 0)
+    }
+}
+
+impl Digest for Blake3 {
+    type Output = [u8; OUT_LEN];
+    const BLOCK_LEN: usize = BLOCK_LEN;
+
+    fn update(&mut self, bytes: &[u8]) {
+        self.update_with_threads(bytes, 1);
+    }
+
+    fn finalize(self) -> [u8; OUT_LEN] {
+        let mut hash = [0; OUT_LEN];
+        self.finalize_xof(&mut hash);
+        hash
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::test_util::{hex, pattern};
+    use super::*;
+
+    #[test]
+    fn hash_vectors() {
+        let cases = [
+            (
+                0,
+                "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262",
+            ),
+            (
+                1,
+                "2d3adedff11b61f14c886e35afa036736dcd87a74d27b5c1510225d0f592e213",
+            ),
+            (
+                1023,
+                "10108970eeda3eb932baac1428c7a2163b0e924c9a9e25b35bba72b28f70bd11",
+            ),
+            (
+                1024,
+                "42214739f095a406f3fc83deb889744ac00df831c10daa55189b5d121c855af7",
+            ),
+            (
+                1025,
+                "d00278ae47eb27b34faecf67b4fe263f82d5412916c1ffd97c8cb7fb814b8444",
+            ),
+            (
+                2048,
+                "e776b6028c7cd22a4d0ba182a8bf62205d2ef576467e838ed6f2529b85fba24a",
+            ),
+            (
+                2049,
+                "5f4d72f40d7a5f82b15ca2b2e44b1de3c2ef86c426c95c1af0b6879522563030",
+            ),
+            (
+                3072,
+                "b98cb0ff3623be03326b373de6b9095218513e64f1ee2edd2525c7ad1e5cffd2",
+            ),
+            (
+                8193,
+                "bab6c09cb8ce8cf459261398d2e7aef35700bf488116ceb94a36d0f5f1b7bc3b",
+            ),
+            (
+                31745,
+                "5c80ce0c3bbe9a6f432a1c6c2ccbde45923d23249386988a30f512d23919eb98",
+            ),
+        ];
+        for (len, expected) in cases {
+            let input = pattern(len);
+            assert_eq!(hex(&Blake3::digest(&input)), expected, "length {}", len);
+            // Odd-sized pieces cross chunk boundaries and fall back from
+            // whole subtrees to buffering.
+            let mut hasher = Blake3::default();
+            for piece in input.chunks(1000) {
+                hasher.update(piece);
+            }
+            assert_eq!(hex(&hasher.finalize()), expected, "length {}", len);
+        }
+        assert_eq!(
+            hex(&Blake3::digest(b"abc")),
+            "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85"
+        );
+    }
+
+    #[test]
+    fn keyed_and_derived() {
+        let input = pattern(2049);
+        let mut keyed = Blake3::new_keyed(b"whats the Elephant in the room? ");
+        keyed.update(&input);
+        assert_eq!(
+            hex(&keyed.finalize()),
+            "3ea4b347250075b8cd1008a1d62d7594ee6772ccef45f445d2e4c61189aaccdd"
+        );
+        let mut derived = Blake3::new_derive_key("stdlib-rs 2026-10-18 blake3 test context");
+        derived.update(&input);
+        assert_eq!(
+            hex(&derived.finalize()),
+            "459e794465de9cef5a4ac9d22e254aa53d731f84511fb683e56026d05d7c244b"
+        );
+    }
+
+    #[test]
+    fn extended_output() {
+        let mut hasher = Blake3::default();
+        hasher.update(&pattern(1025));
+        let mut out = [0; 80];
+        hasher.finalize_xof(&mut out);
+        assert_eq!(
+            hex(&out),
+            "d00278ae47eb27b34faecf67b4fe263f82d5412916c1ffd97c8cb7fb814b8444\
+             f4c4a22b4b399155358a994e52bf255de60035742ec71bd08ac275a1b51cc6bf\
+             e332b0ef84b409108cda080e6269ed4b"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn parallel_matches_serial() {
+        let input = pattern(300_000);
+        let expected = Blake3::digest(&input);
+        for threads in [1, 2, 3, 8] {
+            let mut hasher = Blake3::default();
+            hasher.update_parallel(&input, threads);
+            assert_eq!(hasher.finalize(), expected);
+            // After a partial chunk the subtrees no longer start at zero.
+            let mut hasher = Blake3::default();
+            hasher.update(&input[..5000]);
+            hasher.update_parallel(&input[5000..], threads);
+            assert_eq!(hasher.finalize(), expected);
+        }
+    }
+}
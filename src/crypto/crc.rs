@@ -0,0 +1,526 @@
+use core::convert::TryInto;
+use core::hash::Hasher;
+
+/// The IEEE CRC-32 polynomial used by zlib, gzip and PNG, bit-reversed.
+const POLY: u32 = 0xEDB8_8320;
+
+/// The parameters of a CRC, in the form of the Rocksoft model used by most
+/// CRC catalogues.
+/// ## Examples
+/// ```
+/// # use stdlib_rs::crypto::crc::{Crc, CrcSpec};
+/// // CRC-16/CCITT-FALSE
+/// static CRC_16: Crc = Crc::new(CrcSpec {
+///     width: 16,
+///     poly: 0x1021,
+///     init: 0xFFFF,
+///     refin: false,
+///     refout: false,
+///     xorout: 0,
+/// });
+/// assert_eq!(CRC_16.checksum(b"123456789"), 0x29B1);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CrcSpec {
+    /// The number of bits in the CRC, from 1 to 64.
+    pub width: u32,
+    /// The polynomial, without its top bit, with bits in normal order.
+    pub poly: u64,
+    /// The value of the register before the first byte.
+    pub init: u64,
+    /// Whether each input byte is read least significant bit first.
+    pub refin: bool,
+    /// Whether the register is reflected before `xorout` is applied.
+    pub refout: bool,
+    /// The value xored into the register to give the CRC.
+    pub xorout: u64,
+}
+
+impl CrcSpec {
+    /// CRC-32/ISO-HDLC, used by zlib, gzip, PNG and Ethernet.
+    pub const CRC_32: CrcSpec = CrcSpec {
+        width: 32,
+        poly: 0x04C1_1DB7,
+        init: 0xFFFF_FFFF,
+        refin: true,
+        refout: true,
+        xorout: 0xFFFF_FFFF,
+    };
+
+    /// CRC-32C (Castagnoli), used by iSCSI, ext4 and SSE4.2's `crc32`.
+    pub const CRC_32C: CrcSpec = CrcSpec {
+        width: 32,
+        poly: 0x1EDC_6F41,
+        init: 0xFFFF_FFFF,
+        refin: true,
+        refout: true,
+        xorout: 0xFFFF_FFFF,
+    };
+
+    /// CRC-64/XZ, the ECMA-182 polynomial as used by xz.
+    pub const CRC_64_XZ: CrcSpec = CrcSpec {
+        width: 64,
+        poly: 0x42F0_E1EB_A9EA_3693,
+        init: u64::MAX,
+        refin: true,
+        refout: true,
+        xorout: u64::MAX,
+    };
+}
+
+/// Returns a mask of the low `width` bits.
+const fn mask(width: u32) -> u64 {
+    u64::MAX >> (64 - width)
+}
+
+/// Reverses the low `width` bits of `value`.
+const fn reflect(value: u64, width: u32) -> u64 {
+    value.reverse_bits() >> (64 - width)
+}
+
+/// A CRC with its lookup tables, ready to checksum bytes eight at a time.
+///
+/// Reflected CRCs keep the register in the low `width` bits and shift
+/// right; the others keep it in the high `width` bits and shift left, so
+/// both work for any width. Build one in a `static` to compute the tables at
+/// compile time.
+/// ## Examples
+/// ```
+/// # use stdlib_rs::crypto::crc::{Crc, CrcSpec};
+/// # use std::hash::Hasher;
+/// static CRC_32C: Crc = Crc::new(CrcSpec::CRC_32C);
+/// let mut hasher = CRC_32C.hasher();
+/// hasher.write(b"1234");
+/// hasher.write(b"56789");
+/// assert_eq!(hasher.finish(), CRC_32C.checksum(b"123456789"));
+/// ```
+pub struct Crc {
+    spec: CrcSpec,
+    // `tables[k][i]` is the register after byte `i` and then `k` zero bytes.
+    tables: [[u64; 256]; 8],
+}
+
+impl Crc {
+    /// Builds the tables for `spec`.
+    /// ## Panics
+    /// Panics if the width is not between 1 and 64.
+    pub const fn new(spec: CrcSpec) -> Crc {
+        assert!(
+            spec.width >= 1 && spec.width <= 64,
+            "CRC width must be between 1 and 64"
+        );
+        let mut tables = [[0; 256]; 8];
+        let mut i = 0;
+        while i < 256 {
+            tables[0][i] = if spec.refin {
+                let poly = reflect(spec.poly, spec.width);
+                let mut crc = i as u64;
+                let mut bit = 0;
+                while bit < 8 {
+                    crc = if crc & 1 == 1 {
+                        crc >> 1 ^ poly
+                    } else {
+                        crc >> 1
+                    };
+                    bit += 1;
+                }
+                crc
+            } else {
+                let poly = spec.poly << (64 - spec.width);
+                let mut crc = (i as u64) << 56;
+                let mut bit = 0;
+                while bit < 8 {
+                    crc = if crc >> 63 == 1 {
+                        crc << 1 ^ poly
+                    } else {
+                        crc << 1
+                    };
+                    bit += 1;
+                }
+                crc
+            };
+            i += 1;
+        }
+        let mut k = 1;
+        while k < 8 {
+            let mut i = 0;
+            while i < 256 {
+                let prev = tables[k - 1][i];
+                tables[k][i] = if spec.refin {
+                    prev >> 8 ^ tables[0][(prev & 0xFF) as usize]
+                } else {
+                    prev << 8 ^ tables[0][(prev >> 56) as usize]
+                };
+                i += 1;
+            }
+            k += 1;
+        }
+        Crc { spec, tables }
+    }
+
+    /// Returns the parameters of the CRC.
+    pub fn spec(&self) -> &CrcSpec {
+        &self.spec
+    }
+
+    /// Returns the CRC of `bytes`.
+    pub fn checksum(&self, bytes: &[u8]) -> u64 {
+        self.finish(self.update(self.init(), bytes))
+    }
+
+    /// Returns a hasher that computes the CRC of everything written to it.
+    pub fn hasher(&self) -> CrcHasher<'_> {
+        CrcHasher {
+            crc: self,
+            register: self.init(),
+        }
+    }
+
+    /// Returns the register before the first byte.
+    fn init(&self) -> u64 {
+        let init = self.spec.init & mask(self.spec.width);
+        if self.spec.refin {
+            reflect(init, self.spec.width)
+        } else {
+            init << (64 - self.spec.width)
+        }
+    }
+
+    /// Returns the CRC for the register after the last byte.
+    fn finish(&self, register: u64) -> u64 {
+        let width = self.spec.width;
+        let crc = if self.spec.refin {
+            register
+        } else {
+            register >> (64 - width)
+        };
+        let crc = if self.spec.refin == self.spec.refout {
+            crc
+        } else {
+            reflect(crc, width)
+        };
+        (crc ^ self.spec.xorout) & mask(width)
+    }
+
+    /// Feeds `bytes` through the register, eight at a time while it can.
+    fn update(&self, mut register: u64, bytes: &[u8]) -> u64 {
+        let t = &self.tables;
+        let mut words = bytes.chunks_exact(8);
+        if self.spec.refin {
+            for word in &mut words {
+                let x = register ^ u64::from_le_bytes(word.try_into().unwrap());
+                register = t[7][(x & 0xFF) as usize]
+                    ^ t[6][(x >> 8 & 0xFF) as usize]
+                    ^ t[5][(x >> 16 & 0xFF) as usize]
+                    ^ t[4][(x >> 24 & 0xFF) as usize]
+                    ^ t[3][(x >> 32 & 0xFF) as usize]
+                    ^ t[2][(x >> 40 & 0xFF) as usize]
+                    ^ t[1][(x >> 48 & 0xFF) as usize]
+                    ^ t[0][(x >> 56) as usize];
+            }
+            for &byte in words.remainder() {
+                register = register >> 8 ^ t[0][((register ^ byte as u64) & 0xFF) as usize];
+            }
+        } else {
+            for word in &mut words {
+                let x = register ^ u64::from_be_bytes(word.try_into().unwrap());
+                register = t[7][(x >> 56) as usize]
+                    ^ t[6][(x >> 48 & 0xFF) as usize]
+                    ^ t[5][(x >> 40 & 0xFF) as usize]
+                    ^ t[4][(x >> 32 & 0xFF) as usize]
+                    ^ t[3][(x >> 24 & 0xFF) as usize]
+                    ^ t[2][(x >> 16 & 0xFF) as usize]
+                    ^ t[1][(x >> 8 & 0xFF) as usize]
+                    ^ t[0][(x & 0xFF) as usize];
+            }
+            for &byte in words.remainder() {
+                register = register << 8 ^ t[0][(register >> 56 ^ byte as u64) as usize];
+            }
+        }
+        register
+    }
+}
+
+/// A hasher that returns the CRC of a `Crc`, for CRCs without a hasher of
+/// their own.
+pub struct CrcHasher<'a> {
+    crc: &'a Crc,
+    register: u64,
+}
+
+impl Hasher for CrcHasher<'_> {
+    fn write(&mut self, bytes: &[u8]) {
+        self.register = self.crc.update(self.register, bytes);
+    }
+
+    fn finish(&self) -> u64 {
+        self.crc.finish(self.register)
+    }
+}
+
+static CRC_32: Crc = Crc::new(CrcSpec::CRC_32);
+static CRC_32C: Crc = Crc::new(CrcSpec::CRC_32C);
+static CRC_64_XZ: Crc = Crc::new(CrcSpec::CRC_64_XZ);
+
+/// A hasher that returns a CRC-32 checksum, matching zlib's `crc32`.
+/// ## Examples
+/// ```
+/// # use stdlib_rs::crypto::crc::Crc32Hasher;
+/// # use std::hash::Hasher;
+/// let mut hasher = Crc32Hasher::default();
+/// hasher.write(b"123456789");
+/// assert_eq!(hasher.finish(), 0xCBF4_3926);
+/// ```
+pub struct Crc32Hasher(u32);
+
+impl Crc32Hasher {
+    /// Creates a hasher that continues from a previous CRC-32, like passing
+    /// `crc` to zlib's `crc32(crc, buf, len)`.
+    /// `seed(0)` is the same as `default()`.
+    pub fn seed(crc: u32) -> Crc32Hasher {
+        Crc32Hasher(!crc)
+    }
+}
+
+impl Default for Crc32Hasher {
+    fn default() -> Crc32Hasher {
+        Crc32Hasher::seed(0)
+    }
+}
+
+impl Hasher for Crc32Hasher {
+    fn write(&mut self, bytes: &[u8]) {
+        self.0 = CRC_32.update(self.0.into(), bytes) as u32;
+    }
+
+    fn finish(&self) -> u64 {
+        (!self.0).into()
+    }
+}
+
+/// A hasher that returns a CRC-32C checksum, as used by iSCSI and ext4.
+/// ## Examples
+/// ```
+/// # use stdlib_rs::crypto::crc::Crc32cHasher;
+/// # use std::hash::Hasher;
+/// let mut hasher = Crc32cHasher::default();
+/// hasher.write(b"123456789");
+/// assert_eq!(hasher.finish(), 0xE306_9283);
+/// ```
+pub struct Crc32cHasher(u32);
+
+impl Crc32cHasher {
+    /// Creates a hasher that continues from a previous CRC-32C.
+    /// `seed(0)` is the same as `default()`.
+    pub fn seed(crc: u32) -> Crc32cHasher {
+        Crc32cHasher(!crc)
+    }
+}
+
+impl Default for Crc32cHasher {
+    fn default() -> Crc32cHasher {
+        Crc32cHasher::seed(0)
+    }
+}
+
+impl Hasher for Crc32cHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        self.0 = CRC_32C.update(self.0.into(), bytes) as u32;
+    }
+
+    fn finish(&self) -> u64 {
+        (!self.0).into()
+    }
+}
+
+/// A hasher that returns a CRC-64 checksum, matching the one in xz files.
+/// ## Examples
+/// ```
+/// # use stdlib_rs::crypto::crc::Crc64Hasher;
+/// # use std::hash::Hasher;
+/// let mut hasher = Crc64Hasher::default();
+/// hasher.write(b"123456789");
+/// assert_eq!(hasher.finish(), 0x995D_C9BB_DF19_39FA);
+/// ```
+pub struct Crc64Hasher(u64);
+
+impl Crc64Hasher {
+    /// Creates a hasher that continues from a previous CRC-64.
+    /// `seed(0)` is the same as `default()`.
+    pub fn seed(crc: u64) -> Crc64Hasher {
+        Crc64Hasher(!crc)
+    }
+}
+
+impl Default for Crc64Hasher {
+    fn default() -> Crc64Hasher {
+        Crc64Hasher::seed(0)
+    }
+}
+
+impl Hasher for Crc64Hasher {
+    fn write(&mut self, bytes: &[u8]) {
+        self.0 = CRC_64_XZ.update(self.0, bytes);
+    }
+
+    fn finish(&self) -> u64 {
+        !self.0
+    }
+}
+
+/// Multiplies two polynomials modulo the CRC polynomial, with bits in the
+/// reflected order the CRC uses.
+fn multiply(a: u32, mut b: u32) -> u32 {
+    let mut product = 0;
+    let mut bit = 1 << 31;
+    while bit != 0 {
+        if a & bit != 0 {
+            product ^= b;
+        }
+        b = if b & 1 == 1 { b >> 1 ^ POLY } else { b >> 1 };
+        bit >>= 1;
+    }
+    product
+}
+
+/// Returns x^(8 * len) modulo the CRC polynomial, by squaring.
+fn shift(len: u64) -> u32 {
+    // x^8, the shift for one byte: x^1 squared three times.
+    let mut power = 1 << 30;
+    for _ in 0..3 {
+        power = multiply(power, power);
+    }
+    // x^0, multiplied by x^(8 * 2^i) for each bit i set in `len`.
+    let mut result = 1 << 31;
+    let mut len = len;
+    while len != 0 {
+        if len & 1 == 1 {
+            result = multiply(result, power);
+        }
+        power = multiply(power, power);
+        len >>= 1;
+    }
+    result
+}
+
+/// Returns the CRC-32 of two buffers joined together, given the CRC-32 of
+/// each and the length of the second, in O(log len_b) time.
+/// ## Examples
+/// ```
+/// # use stdlib_rs::crypto::crc::{crc32_combine, Crc32Hasher};
+/// # use std::hash::Hasher;
+/// let crc = |bytes: &[u8]| {
+///     let mut hasher = Crc32Hasher::default();
+///     hasher.write(bytes);
+///     hasher.finish() as u32
+/// };
+/// let joined = crc32_combine(crc(b"12345"), crc(b"6789"), 4);
+/// assert_eq!(joined, crc(b"123456789"));
+/// ```
+pub fn crc32_combine(crc_a: u32, crc_b: u32, len_b: u64) -> u32 {
+    multiply(shift(len_b), crc_a) ^ crc_b
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn crc_32(bytes: &[u8]) -> u32 {
+        let mut hasher = Crc32Hasher::default();
+        hasher.write(bytes);
+        hasher.finish() as u32
+    }
+
+    #[test]
+    fn check_values() {
+        assert_eq!(crc_32(b""), 0);
+        assert_eq!(crc_32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(
+            crc_32(b"The quick brown fox jumps over the lazy dog"),
+            0x414F_A339
+        );
+    }
+
+    #[test]
+    fn seed_continues_a_checksum() {
+        let mut hasher = Crc32Hasher::seed(crc_32(b"1234"));
+        hasher.write(b"56789");
+        assert_eq!(hasher.finish(), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn castagnoli_and_xz_check_values() {
+        let mut hasher = Crc32cHasher::default();
+        hasher.write(b"");
+        assert_eq!(hasher.finish(), 0);
+        // The 32 byte iSCSI test pattern of zeros, from RFC 3720.
+        hasher.write(&[0; 32]);
+        assert_eq!(hasher.finish(), 0x8A91_36AA);
+        let mut hasher = Crc64Hasher::seed(CRC_64_XZ.checksum(b"1234"));
+        hasher.write(b"56789");
+        assert_eq!(hasher.finish(), 0x995D_C9BB_DF19_39FA);
+    }
+
+    #[test]
+    fn custom_specs() {
+        let spec = |width, poly, init, refin, refout, xorout| CrcSpec {
+            width,
+            poly,
+            init,
+            refin,
+            refout,
+            xorout,
+        };
+        // Check values from the catalogue of parametrised CRC algorithms.
+        let cases = [
+            (spec(3, 0x3, 0x7, true, true, 0), 0x6),
+            (spec(5, 0x05, 0x1F, true, true, 0x1F), 0x19),
+            (spec(8, 0x07, 0, false, false, 0), 0xF4),
+            (spec(12, 0x80F, 0, false, true, 0), 0xDAF),
+            (spec(16, 0x8005, 0, true, true, 0), 0xBB3D),
+            (spec(16, 0x1021, 0xFFFF, false, false, 0), 0x29B1),
+            (
+                spec(32, 0x04C1_1DB7, 0xFFFF_FFFF, false, false, 0xFFFF_FFFF),
+                0xFC89_1918,
+            ),
+            (
+                spec(64, 0x42F0_E1EB_A9EA_3693, 0, false, false, 0),
+                0x6C40_DF5F_0B49_7347,
+            ),
+        ];
+        for (spec, check) in cases {
+            assert_eq!(Crc::new(spec).checksum(b"123456789"), check, "{:?}", spec);
+        }
+    }
+
+    #[test]
+    fn slice_by_8_matches_bytewise() {
+        let data: Vec<u8> = (0..100u32).map(|i| (i * 97 % 256) as u8).collect();
+        for spec in [
+            CrcSpec::CRC_32C,
+            CrcSpec {
+                refin: false,
+                ..CrcSpec::CRC_64_XZ
+            },
+        ] {
+            let crc = Crc::new(spec);
+            for len in 0..data.len() {
+                let mut hasher = crc.hasher();
+                for byte in &data[..len] {
+                    hasher.write(&[*byte]);
+                }
+                assert_eq!(hasher.finish(), crc.checksum(&data[..len]));
+            }
+        }
+    }
+
+    #[test]
+    fn combine_every_split() {
+        let data: Vec<u8> = (0..300u32).map(|i| (i * 31 % 256) as u8).collect();
+        let whole = crc_32(&data);
+        for split in 0..=data.len() {
+            let (a, b) = data.split_at(split);
+            assert_eq!(crc32_combine(crc_32(a), crc_32(b), b.len() as u64), whole);
+        }
+    }
+}
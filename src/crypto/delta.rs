@@ -0,0 +1,255 @@
+#![deny(missing_docs)]
+
+use alloc::collections::BTreeMap;
+use alloc::{vec, vec::Vec};
+
+use super::adler::RollingAdler32;
+use super::blake3::Blake3;
+use super::digest::Digest;
+
+/// The checksums of one block of the base buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Block {
+    offset: usize,
+    len: usize,
+    strong: [u8; 32],
+}
+
+/// The block checksums of a base buffer, which is all a delta needs to know
+/// about it.
+/// Each block has a weak adler32 checksum that can be rolled over the target
+/// one byte at a time, and a strong BLAKE3 hash that confirms a match.
+/// Nothing checks the rebuilt buffer afterwards, so the strong hash has to
+/// be collision resistant for a delta to be trusted.
+/// ## Examples
+/// ```
+/// # use stdlib_rs::crypto::delta::Signature;
+/// let base = b"the quick brown fox jumps over the lazy dog";
+/// let target = b"the quick brown cat jumps over the lazy dog";
+/// let delta = Signature::new(base, 8).delta(target);
+/// assert_eq!(delta.apply(base), target);
+/// assert!(delta.literal_len() < target.len());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Signature {
+    block_size: usize,
+    base_len: usize,
+    // Blocks by weak checksum. Blocks with the same weak checksum are kept
+    // in base order.
+    blocks: BTreeMap<u32, Vec<Block>>,
+}
+
+impl Signature {
+    /// Computes the signature of `base`, split into blocks of `block_size`
+    /// bytes. The last block may be shorter.
+    /// ## Panics
+    /// Panics if `block_size` is zero.
+    pub fn new(base: &[u8], block_size: usize) -> Signature {
+        assert!(block_size > 0, "block size must be positive");
+        let mut blocks: BTreeMap<u32, Vec<Block>> = BTreeMap::new();
+        for (i, chunk) in base.chunks(block_size).enumerate() {
+            let block = Block {
+                offset: i * block_size,
+                len: chunk.len(),
+                strong: strong(chunk),
+            };
+            let weak = RollingAdler32::new(chunk).checksum();
+            blocks.entry(weak).or_default().push(block);
+        }
+        Signature {
+            block_size,
+            base_len: base.len(),
+            blocks,
+        }
+    }
+
+    /// Returns the size of the blocks.
+    pub fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    /// Returns the length of the base buffer.
+    pub fn base_len(&self) -> usize {
+        self.base_len
+    }
+
+    /// Computes the instructions that rebuild `target` from the base buffer,
+    /// copying every block of the base that appears in `target` and sending
+    /// the rest as literal bytes.
+    pub fn delta(&self, target: &[u8]) -> Delta {
+        let mut delta = Delta::default();
+        let mut start = 0;
+        // The window always covers `target[start..start + rolling.len()]`.
+        let mut rolling = RollingAdler32::new(self.window(target, start));
+        while !rolling.is_empty() {
+            let window = &target[start..start + rolling.len()];
+            if let Some(block) = self.find(&rolling, window) {
+                delta.copy(block.offset, block.len);
+                start += block.len;
+                rolling = RollingAdler32::new(self.window(target, start));
+                continue;
+            }
+            delta.literal(target[start]);
+            if start + rolling.len() < target.len() {
+                rolling.rotate(target[start], target[start + rolling.len()]);
+            } else {
+                // Only a short block at the end of the base can match now.
+                rolling.roll_out(target[start]);
+            }
+            start += 1;
+        }
+        delta
+    }
+
+    /// Returns the window of up to `block_size` bytes at `start`.
+    fn window<'a>(&self, target: &'a [u8], start: usize) -> &'a [u8] {
+        &target[start..target.len().min(start + self.block_size)]
+    }
+
+    /// Finds a block of the base with the same bytes as `window`.
+    fn find(&self, rolling: &RollingAdler32, window: &[u8]) -> Option<&Block> {
+        let candidates = self.blocks.get(&rolling.checksum())?;
+        let hash = strong(window);
+        candidates
+            .iter()
+            .find(|block| block.len == window.len() && block.strong == hash)
+    }
+}
+
+fn strong(bytes: &[u8]) -> [u8; 32] {
+    Blake3::digest(bytes)
+}
+
+/// One step of rebuilding a target buffer from a base buffer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Instruction {
+    /// Copy `len` bytes of the base, starting at `offset`.
+    Copy {
+        /// Where the bytes start in the base.
+        offset: usize,
+        /// How many bytes to copy.
+        len: usize,
+    },
+    /// Append bytes that are not in the base.
+    Literal(Vec<u8>),
+}
+
+/// The instructions that rebuild a target buffer from a base buffer.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Delta {
+    instructions: Vec<Instruction>,
+}
+
+impl Delta {
+    /// Returns the instructions, which copy neighbouring blocks as one range
+    /// and group neighbouring literal bytes.
+    pub fn instructions(&self) -> &[Instruction] {
+        &self.instructions
+    }
+
+    /// Returns the number of literal bytes, which is what has to be sent
+    /// besides the instructions themselves.
+    pub fn literal_len(&self) -> usize {
+        self.instructions
+            .iter()
+            .map(|instruction| match instruction {
+                Instruction::Literal(bytes) => bytes.len(),
+                Instruction::Copy { .. } => 0,
+            })
+            .sum()
+    }
+
+    /// Rebuilds the target buffer from `base`.
+    /// ## Panics
+    /// Panics if a copy reaches past the end of `base`, which means the
+    /// delta was computed against a different base.
+    pub fn apply(&self, base: &[u8]) -> Vec<u8> {
+        let mut target = Vec::new();
+        for instruction in &self.instructions {
+            match instruction {
+                Instruction::Copy { offset, len } => {
+                    target.extend_from_slice(&base[*offset..*offset + *len])
+                }
+                Instruction::Literal(bytes) => target.extend_from_slice(bytes),
+            }
+        }
+        target
+    }
+
+    fn copy(&mut self, offset: usize, len: usize) {
+        if let Some(Instruction::Copy {
+            offset: last_offset,
+            len: last_len,
+        }) = self.instructions.last_mut()
+        {
+            if *last_offset + *last_len == offset {
+                *last_len += len;
+                return;
+            }
+        }
+        self.instructions.push(Instruction::Copy { offset, len });
+    }
+
+    fn literal(&mut self, byte: u8) {
+        match self.instructions.last_mut() {
+            Some(Instruction::Literal(bytes)) => bytes.push(byte),
+            _ => self.instructions.push(Instruction::Literal(vec![byte])),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data(len: usize, seed: u32) -> Vec<u8> {
+        let mut state = seed;
+        (0..len)
+            .map(|_| {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                (state >> 16) as u8
+            })
+            .collect()
+    }
+
+    #[test]
+    fn identical_buffers_are_one_copy() {
+        let base = data(1000, 1);
+        let delta = Signature::new(&base, 64).delta(&base);
+        assert_eq!(
+            delta.instructions(),
+            &[Instruction::Copy {
+                offset: 0,
+                len: 1000
+            }]
+        );
+    }
+
+    #[test]
+    fn finds_moved_and_edited_blocks() {
+        let base = data(4096, 2);
+        let mut target = Vec::new();
+        target.extend_from_slice(&base[2048..]);
+        target.extend_from_slice(b"inserted bytes");
+        target.extend_from_slice(&base[..1000]);
+        target[100] ^= 0xFF;
+        let delta = Signature::new(&base, 128).delta(&target);
+        assert_eq!(delta.apply(&base), target);
+        // One damaged block, the insertion and the partial block before it.
+        assert!(delta.literal_len() < 3 * 128 + 14);
+    }
+
+    #[test]
+    fn unrelated_and_empty_buffers() {
+        let base = data(500, 3);
+        let target = data(300, 4);
+        let delta = Signature::new(&base, 32).delta(&target);
+        assert_eq!(delta.literal_len(), 300);
+        assert_eq!(delta.apply(&base), target);
+        assert!(Signature::new(&base, 32)
+            .delta(b"")
+            .instructions()
+            .is_empty());
+        assert_eq!(Signature::new(b"", 32).delta(&target).apply(b""), target);
+    }
+}
@@ -0,0 +1,52 @@
+#![deny(missing_docs)]
+
+use core::fmt;
+
+/// A cryptographic hash function that takes its input a piece at a time.
+/// ## Examples
+/// ```
+/// # use stdlib_rs::crypto::digest::Digest;
+/// # use stdlib_rs::crypto::sha2::Sha256;
+/// let mut hasher = Sha256::default();
+/// hasher.update(b"ab");
+/// hasher.update(b"c");
+/// assert_eq!(hasher.finalize(), Sha256::digest(b"abc"));
+/// ```
+pub trait Digest: Clone + Default {
+    /// The hash, usually a byte array.
+    type Output: AsRef<[u8]> + Copy + fmt::Debug + PartialEq + Eq;
+
+    /// The number of bytes the function compresses at a time, which HMAC
+    /// pads its key to.
+    const BLOCK_LEN: usize;
+
+    /// Adds `bytes` to the input.
+    fn update(&mut self, bytes: &[u8]);
+
+    /// Returns the hash of the input.
+    fn finalize(self) -> Self::Output;
+
+    /// Returns the hash of `bytes`.
+    fn digest(bytes: &[u8]) -> Self::Output {
+        let mut hasher = Self::default();
+        hasher.update(bytes);
+        hasher.finalize()
+    }
+}
+
+/// Compares two byte strings in time that depends only on their lengths,
+/// so comparing a secret tag does not leak how much of it matched.
+/// ## Examples
+/// ```
+/// # use stdlib_rs::crypto::digest::constant_time_eq;
+/// assert!(constant_time_eq(b"tag", b"tag"));
+/// assert!(!constant_time_eq(b"tag", b"tab"));
+/// ```
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let difference = a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y));
+    // Keep the compiler from turning the fold into an early exit.
+    core::hint::black_box(difference) == 0
+}
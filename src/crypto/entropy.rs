@@ -0,0 +1,93 @@
+#![deny(missing_docs)]
+
+use std::io;
+
+/// Fills `bytes` with random bytes from the operating system's
+/// cryptographically secure generator: `/dev/urandom` on Unix and
+/// `BCryptGenRandom` on Windows.
+/// ## Errors
+/// Returns an error if the generator cannot be read, or with
+/// `io::ErrorKind::Unsupported` on platforms that have neither.
+/// ## Examples
+/// ```
+/// # use stdlib_rs::crypto::entropy::fill;
+/// let mut key = [0u8; 16];
+/// fill(&mut key).unwrap();
+/// ```
+pub fn fill(bytes: &mut [u8]) -> io::Result<()> {
+    imp::fill(bytes)
+}
+
+#[cfg(unix)]
+mod imp {
+    use std::fs::File;
+    use std::io::{self, Read};
+
+    pub(super) fn fill(bytes: &mut [u8]) -> io::Result<()> {
+        File::open("/dev/urandom")?.read_exact(bytes)
+    }
+}
+
+#[cfg(windows)]
+mod imp {
+    use std::ffi::c_void;
+    use std::io;
+    use std::ptr;
+
+    /// Use the system's preferred generator instead of an opened algorithm
+    /// handle.
+    const BCRYPT_USE_SYSTEM_PREFERRED_RNG: u32 = 0x0000_0002;
+
+    #[link(name = "bcrypt")]
+    extern "system" {
+        fn BCryptGenRandom(algorithm: *mut c_void, buffer: *mut u8, len: u32, flags: u32) -> i32;
+    }
+
+    pub(super) fn fill(bytes: &mut [u8]) -> io::Result<()> {
+        for chunk in bytes.chunks_mut(u32::MAX as usize) {
+            let status = unsafe {
+                BCryptGenRandom(
+                    ptr::null_mut(),
+                    chunk.as_mut_ptr(),
+                    chunk.len() as u32,
+                    BCRYPT_USE_SYSTEM_PREFERRED_RNG,
+                )
+            };
+            // Any negative NTSTATUS is a failure.
+            if status < 0 {
+                return Err(io::Error::other(format!(
+                    "BCryptGenRandom failed with status {:#x}",
+                    status
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+mod imp {
+    use std::io;
+
+    pub(super) fn fill(_bytes: &mut [u8]) -> io::Result<()> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "no secure random source on this platform",
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn fills_with_different_bytes() {
+        let mut a = [0u8; 32];
+        let mut b = [0u8; 32];
+        fill(&mut a).unwrap();
+        fill(&mut b).unwrap();
+        assert_ne!(a, b);
+        assert_ne!(a, [0; 32]);
+    }
+}
@@ -1,60 +1,250 @@
-use std::hash::Hasher;
+use core::hash::Hasher;
 
-/// A struct that represents an FNV Hasher.
-pub struct Fnv32Hasher(u32);
-/// A struct that represents an FNV Hasher.
-pub struct Fnv64Hasher(u64);
+/// The state of an FNV hash: `u32`, `u64` or `u128`, each with the offset
+/// basis and prime FNV defines for that width.
+pub trait FnvWord: Copy {
+    /// The hash of no bytes.
+    const OFFSET_BASIS: Self;
+    /// The number the hash is multiplied by for each byte.
+    const PRIME: Self;
 
-impl Fnv32Hasher {
-    /// The seed provided to the hasher.
-    pub fn seed(seed: u32) -> Fnv32Hasher {
-        Fnv32Hasher(seed)
+    /// Multiplies by `PRIME`, wrapping around.
+    fn multiply(self) -> Self;
+    /// Xors `byte` into the low bits.
+    fn xor(self, byte: u8) -> Self;
+    /// Returns the low 64 bits.
+    fn low_64(self) -> u64;
+}
+
+impl FnvWord for u32 {
+    const OFFSET_BASIS: u32 = 0x811C_9DC5;
+    const PRIME: u32 = 0x0100_0193;
+
+    fn multiply(self) -> u32 {
+        self.wrapping_mul(Self::PRIME)
+    }
+    fn xor(self, byte: u8) -> u32 {
+        self ^ byte as u32
+    }
+    fn low_64(self) -> u64 {
+        self.into()
+    }
+}
+
+impl FnvWord for u64 {
+    const OFFSET_BASIS: u64 = 0xCBF2_9CE4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01B3;
+
+    fn multiply(self) -> u64 {
+        self.wrapping_mul(Self::PRIME)
+    }
+    fn xor(self, byte: u8) -> u64 {
+        self ^ byte as u64
+    }
+    fn low_64(self) -> u64 {
+        self
     }
 }
 
-impl Default for Fnv32Hasher {
-    fn default() -> Fnv32Hasher {
-        Fnv32Hasher(2166136261)
+impl FnvWord for u128 {
+    const OFFSET_BASIS: u128 = 0x6C62_272E_07BB_0142_62B8_2175_6295_C58D;
+    const PRIME: u128 = 0x0000_0000_0100_0000_0000_0000_0000_013B;
+
+    fn multiply(self) -> u128 {
+        self.wrapping_mul(Self::PRIME)
+    }
+    fn xor(self, byte: u8) -> u128 {
+        self ^ byte as u128
+    }
+    fn low_64(self) -> u64 {
+        self as u64
     }
 }
 
-impl Hasher for Fnv32Hasher {
+/// A hasher that returns FNV-1 hashes, which multiply by the prime and then
+/// xor in each byte. `T` is the width of the hash.
+/// ## Examples
+/// ```
+/// # use stdlib_rs::crypto::fnv::{fnv1_128, Fnv1};
+/// # use std::hash::Hasher;
+/// let mut hasher = Fnv1::<u128>::default();
+/// hasher.write(b"foobar");
+/// assert_eq!(hasher.hash(), fnv1_128(b"foobar"));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fnv1<T: FnvWord = u64>(T);
+
+/// A hasher that returns FNV-1a hashes, which xor in each byte and then
+/// multiply by the prime. `T` is the width of the hash.
+/// ## Examples
+/// ```
+/// # use stdlib_rs::crypto::fnv::{fnv1a_32, Fnv1a};
+/// # use std::hash::Hasher;
+/// let mut hasher = Fnv1a::<u32>::default();
+/// hasher.write(b"foobar");
+/// assert_eq!(hasher.hash(), fnv1a_32(b"foobar"));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fnv1a<T: FnvWord = u64>(T);
+
+/// A 32-bit FNV-1a hasher.
+pub type Fnv32Hasher = Fnv1a<u32>;
+/// A 64-bit FNV-1a hasher.
+pub type Fnv64Hasher = Fnv1a<u64>;
+
+impl<T: FnvWord> Fnv1<T> {
+    /// The seed provided to the hasher, which continues from a previous
+    /// hash. `seed(T::OFFSET_BASIS)` is the same as `default()`.
+    pub fn seed(seed: T) -> Fnv1<T> {
+        Fnv1(seed)
+    }
+
+    /// Returns the hash at its full width.
+    pub fn hash(&self) -> T {
+        self.0
+    }
+}
+
+impl<T: FnvWord> Default for Fnv1<T> {
+    fn default() -> Fnv1<T> {
+        Fnv1(T::OFFSET_BASIS)
+    }
+}
+
+impl<T: FnvWord> Hasher for Fnv1<T> {
     fn write(&mut self, bytes: &[u8]) {
         for byte in bytes.iter() {
-            self.0 = self.0 ^ (*byte as u32);
-            self.0 = self.0.wrapping_mul(16777619);
+            self.0 = self.0.multiply().xor(*byte);
         }
     }
     fn finish(&self) -> u64 {
-        self.0.into()
+        self.0.low_64()
     }
 }
 
-impl Fnv64Hasher {
-    /// The seed provided to the hasher.
-    pub fn seed(seed: u64) -> Fnv64Hasher {
-        Fnv64Hasher(seed)
+impl<T: FnvWord> Fnv1a<T> {
+    /// The seed provided to the hasher, which continues from a previous
+    /// hash. `seed(T::OFFSET_BASIS)` is the same as `default()`.
+    pub fn seed(seed: T) -> Fnv1a<T> {
+        Fnv1a(seed)
+    }
+
+    /// Returns the hash at its full width.
+    pub fn hash(&self) -> T {
+        self.0
     }
 }
 
-impl Default for Fnv64Hasher {
-    fn default() -> Fnv64Hasher {
-        Fnv64Hasher(14695981039346656037)
+impl<T: FnvWord> Default for Fnv1a<T> {
+    fn default() -> Fnv1a<T> {
+        Fnv1a(T::OFFSET_BASIS)
     }
 }
 
-impl Hasher for Fnv64Hasher {
+impl<T: FnvWord> Hasher for Fnv1a<T> {
     fn write(&mut self, bytes: &[u8]) {
         for byte in bytes.iter() {
-            self.0 = self.0 ^ (*byte as u64);
-            self.0 = self.0.wrapping_mul(1099511628211);
+            self.0 = self.0.xor(*byte).multiply();
         }
     }
     fn finish(&self) -> u64 {
-        self.0
+        self.0.low_64()
     }
 }
 
+/// Returns the 32-bit FNV-1 hash of `bytes`, in `const` contexts too.
+pub const fn fnv1_32(bytes: &[u8]) -> u32 {
+    let mut hash = u32::OFFSET_BASIS;
+    let mut i = 0;
+    while i < bytes.len() {
+        hash = hash.wrapping_mul(u32::PRIME) ^ bytes[i] as u32;
+        i += 1;
+    }
+    hash
+}
+
+/// Returns the 32-bit FNV-1a hash of `bytes`, in `const` contexts too.
+/// ## Examples
+/// ```
+/// # use stdlib_rs::crypto::fnv::fnv1a_32;
+/// const PING_ID: u32 = fnv1a_32(b"Ping");
+/// assert_eq!(PING_ID, fnv1a_32("Ping".as_bytes()));
+/// ```
+pub const fn fnv1a_32(bytes: &[u8]) -> u32 {
+    let mut hash = u32::OFFSET_BASIS;
+    let mut i = 0;
+    while i < bytes.len() {
+        hash = (hash ^ bytes[i] as u32).wrapping_mul(u32::PRIME);
+        i += 1;
+    }
+    hash
+}
+
+/// Returns the 64-bit FNV-1 hash of `bytes`, in `const` contexts too.
+pub const fn fnv1_64(bytes: &[u8]) -> u64 {
+    let mut hash = u64::OFFSET_BASIS;
+    let mut i = 0;
+    while i < bytes.len() {
+        hash = hash.wrapping_mul(u64::PRIME) ^ bytes[i] as u64;
+        i += 1;
+    }
+    hash
+}
+
+/// Returns the 64-bit FNV-1a hash of `bytes`, in `const` contexts too.
+pub const fn fnv1a_64(bytes: &[u8]) -> u64 {
+    let mut hash = u64::OFFSET_BASIS;
+    let mut i = 0;
+    while i < bytes.len() {
+        hash = (hash ^ bytes[i] as u64).wrapping_mul(u64::PRIME);
+        i += 1;
+    }
+    hash
+}
+
+/// Returns the 128-bit FNV-1 hash of `bytes`, in `const` contexts too.
+pub const fn fnv1_128(bytes: &[u8]) -> u128 {
+    let mut hash = u128::OFFSET_BASIS;
+    let mut i = 0;
+    while i < bytes.len() {
+        hash = hash.wrapping_mul(u128::PRIME) ^ bytes[i] as u128;
+        i += 1;
+    }
+    hash
+}
+
+/// Returns the 128-bit FNV-1a hash of `bytes`, in `const` contexts too.
+pub const fn fnv1a_128(bytes: &[u8]) -> u128 {
+    let mut hash = u128::OFFSET_BASIS;
+    let mut i = 0;
+    while i < bytes.len() {
+        hash = (hash ^ bytes[i] as u128).wrapping_mul(u128::PRIME);
+        i += 1;
+    }
+    hash
+}
+
+/// Xor-folds `hash` down to its low `bits` bits, the way FNV builds hashes
+/// of other widths: hash with the next wider FNV, xor the hash shifted right
+/// by `bits` onto itself once, and keep the low `bits` bits.
+/// ## Panics
+/// Panics if `bits` is zero or more than 128.
+/// ## Examples
+/// ```
+/// # use stdlib_rs::crypto::fnv::{fnv1a_32, xor_fold};
+/// // A 24-bit FNV-1a hash.
+/// let hash = fnv1a_32(b"foobar");
+/// assert_eq!(xor_fold(hash.into(), 24), (hash >> 24 ^ hash & 0xFF_FFFF).into());
+/// ```
+pub const fn xor_fold(hash: u128, bits: u32) -> u128 {
+    assert!(bits > 0 && bits <= 128, "bits must be between 1 and 128");
+    if bits == 128 {
+        return hash;
+    }
+    let mask = (1 << bits) - 1;
+    (hash >> bits ^ hash) & mask
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -82,4 +272,50 @@ mod test {
         assert_eq!(fnv_64(b"123"), 5003431119771845851);
         assert_eq!(fnv_32(b"123"), 1916298011);
     }
+
+    #[test]
+    fn fnv1_and_fnv1a_vectors() {
+        assert_eq!(fnv1_32(b"a"), 0x050C_5D7E);
+        assert_eq!(fnv1a_32(b"a"), 0xE40C_292C);
+        assert_eq!(fnv1_32(b"foobar"), 0x31F0_B262);
+        assert_eq!(fnv1a_32(b"foobar"), 0xBF9C_F968);
+        assert_eq!(fnv1_64(b"a"), 0xAF63_BD4C_8601_B7BE);
+        assert_eq!(fnv1a_64(b"a"), 0xAF63_DC4C_8601_EC8C);
+        assert_eq!(fnv1_64(b"foobar"), 0x340D_8765_A4DD_A9C2);
+        assert_eq!(fnv1a_64(b"foobar"), 0x8594_4171_F739_67E8);
+        assert_eq!(fnv1_128(b""), u128::OFFSET_BASIS);
+        assert_eq!(fnv1_128(b"a"), 0xD228_CB69_101A_8CAF_7891_2B70_4E4A_141E);
+        assert_eq!(fnv1a_128(b"a"), 0xD228_CB69_6F1A_8CAF_7891_2B70_4E4A_8964);
+    }
+
+    #[test]
+    fn hashers_match_const_fns() {
+        let bytes = b"The quick brown fox jumps over the lazy dog";
+        let mut fnv1 = Fnv1::<u128>::default();
+        let mut fnv1a = Fnv1a::<u64>::seed(fnv1a_64(&bytes[..10]));
+        fnv1.write(bytes);
+        fnv1a.write(&bytes[10..]);
+        assert_eq!(fnv1.hash(), fnv1_128(bytes));
+        assert_eq!(fnv1.finish(), fnv1_128(bytes) as u64);
+        assert_eq!(fnv1a.hash(), fnv1a_64(bytes));
+        const ID: u64 = fnv1_64(b"const");
+        assert_eq!(ID, fnv1_64(b"const"));
+    }
+
+    #[test]
+    fn folding() {
+        let hash = fnv1a_64(b"foobar");
+        assert_eq!(xor_fold(hash.into(), 64), hash.into());
+        assert_eq!(
+            xor_fold(hash.into(), 48),
+            (hash >> 48 ^ hash & 0xFFFF_FFFF_FFFF).into()
+        );
+        // Below 16 bits the single fold drops the shifted hash's high bits
+        // instead of folding them in again.
+        assert_eq!(xor_fold(fnv1a_32(b"foobar").into(), 10), 0x256);
+        for bits in 1..=128 {
+            let folded = xor_fold(fnv1a_128(b"foobar"), bits);
+            assert!(bits == 128 || folded < 1 << bits);
+        }
+    }
 }
@@ -0,0 +1,236 @@
+#![deny(missing_docs)]
+
+use alloc::{vec, vec::Vec};
+use core::fmt;
+
+use super::digest::{constant_time_eq, Digest};
+
+/// HMAC (RFC 2104) over any `Digest`: a tag that proves a message came from
+/// someone holding the key.
+/// ## Examples
+/// ```
+/// # use stdlib_rs::crypto::hmac::Hmac;
+/// # use stdlib_rs::crypto::sha2::Sha256;
+/// let tag = Hmac::<Sha256>::mac(b"secret", b"payload");
+/// let mut hmac = Hmac::<Sha256>::new(b"secret");
+/// hmac.update(b"pay");
+/// hmac.update(b"load");
+/// assert!(hmac.verify(&tag));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Hmac<D: Digest> {
+    inner: D,
+    outer: D,
+}
+
+impl<D: Digest> Hmac<D> {
+    /// Creates an HMAC with `key`. Keys longer than the digest's block are
+    /// hashed first.
+    pub fn new(key: &[u8]) -> Self {
+        let mut block = vec![0; D::BLOCK_LEN];
+        if key.len() > D::BLOCK_LEN {
+            let hashed = D::digest(key);
+            block[..hashed.as_ref().len()].copy_from_slice(hashed.as_ref());
+        } else {
+            block[..key.len()].copy_from_slice(key);
+        }
+        let mut inner = D::default();
+        let mut outer = D::default();
+        inner.update(&block.iter().map(|byte| byte ^ 0x36).collect::<Vec<_>>());
+        outer.update(&block.iter().map(|byte| byte ^ 0x5C).collect::<Vec<_>>());
+        Hmac { inner, outer }
+    }
+
+    /// Adds `bytes` to the message.
+    pub fn update(&mut self, bytes: &[u8]) {
+        self.inner.update(bytes);
+    }
+
+    /// Returns the tag of the message.
+    pub fn finalize(self) -> D::Output {
+        let mut outer = self.outer;
+        outer.update(self.inner.finalize().as_ref());
+        outer.finalize()
+    }
+
+    /// Returns whether `tag` is the tag of the message, comparing in
+    /// constant time.
+    pub fn verify(self, tag: &[u8]) -> bool {
+        constant_time_eq(self.finalize().as_ref(), tag)
+    }
+
+    /// Returns the tag of `message` with `key`.
+    pub fn mac(key: &[u8], message: &[u8]) -> D::Output {
+        let mut hmac = Self::new(key);
+        hmac.update(message);
+        hmac.finalize()
+    }
+}
+
+/// The error returned when HKDF is asked for more than 255 hashes of
+/// output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidLength;
+
+impl fmt::Display for InvalidLength {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "HKDF output is limited to 255 hashes")
+    }
+}
+
+impl core::error::Error for InvalidLength {}
+
+/// HKDF (RFC 5869), which turns input keying material into any number of
+/// independent keys.
+/// ## Examples
+/// ```
+/// # use stdlib_rs::crypto::hmac::Hkdf;
+/// # use stdlib_rs::crypto::sha2::Sha256;
+/// let hkdf = Hkdf::<Sha256>::extract(Some(b"salt"), b"shared secret");
+/// let mut encryption_key = [0; 32];
+/// let mut signing_key = [0; 32];
+/// hkdf.expand(b"encryption", &mut encryption_key).unwrap();
+/// hkdf.expand(b"signing", &mut signing_key).unwrap();
+/// assert_ne!(encryption_key, signing_key);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Hkdf<D: Digest> {
+    prk: D::Output,
+}
+
+impl<D: Digest> Hkdf<D> {
+    /// Extracts a pseudorandom key from `ikm`. A missing salt is a hash
+    /// length of zeros.
+    pub fn extract(salt: Option<&[u8]>, ikm: &[u8]) -> Self {
+        let prk = match salt {
+            Some(salt) => Hmac::<D>::mac(salt, ikm),
+            None => {
+                let zeros = vec![0; D::default().finalize().as_ref().len()];
+                Hmac::<D>::mac(&zeros, ikm)
+            }
+        };
+        Hkdf { prk }
+    }
+
+    /// Uses `prk` as the pseudorandom key, skipping the extract step.
+    pub fn from_prk(prk: D::Output) -> Self {
+        Hkdf { prk }
+    }
+
+    /// Returns the pseudorandom key.
+    pub fn prk(&self) -> &D::Output {
+        &self.prk
+    }
+
+    /// Fills `okm` with keying material bound to `info`.
+    /// ## Errors
+    /// Returns `InvalidLength` if `okm` is longer than 255 hashes.
+    pub fn expand(&self, info: &[u8], okm: &mut [u8]) -> Result<(), InvalidLength> {
+        let hash_len = self.prk.as_ref().len();
+        if okm.len() > 255 * hash_len {
+            return Err(InvalidLength);
+        }
+        let mut previous: Option<D::Output> = None;
+        for (i, chunk) in okm.chunks_mut(hash_len).enumerate() {
+            let mut hmac = Hmac::<D>::new(self.prk.as_ref());
+            if let Some(previous) = &previous {
+                hmac.update(previous.as_ref());
+            }
+            hmac.update(info);
+            hmac.update(&[i as u8 + 1]);
+            let block = hmac.finalize();
+            chunk.copy_from_slice(&block.as_ref()[..chunk.len()]);
+            previous = Some(block);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::sha2::{Sha224, Sha256, Sha384, Sha512};
+    use super::super::test_util::hex;
+    use super::*;
+
+    #[test]
+    fn rfc_4231_vectors() {
+        let key = [0x0B; 20];
+        assert_eq!(
+            hex(&Hmac::<Sha256>::mac(&key, b"Hi There")),
+            "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7"
+        );
+        assert_eq!(
+            hex(&Hmac::<Sha512>::mac(&key, b"Hi There")),
+            "87aa7cdea5ef619d4ff0b4241a1d6cb02379f4e2ce4ec2787ad0b30545e17cde\
+             daa833b7d6b8a702038b274eaea3f4e4be9d914eeb61f1702e696c203a126854"
+        );
+        let message = b"what do ya want for nothing?";
+        assert_eq!(
+            hex(&Hmac::<Sha224>::mac(b"Jefe", message)),
+            "a30e01098bc6dbbf45690f3a7e9e6d0f8bbea2a39e6148008fd05e44"
+        );
+        assert_eq!(
+            hex(&Hmac::<Sha256>::mac(b"Jefe", message)),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        assert_eq!(
+            hex(&Hmac::<Sha384>::mac(b"Jefe", message)),
+            "af45d2e376484031617f78d2b58a6b1b9c7ef464f5a01b47\
+             e42ec3736322445e8e2240ca5e69e2c78b3239ecfab21649"
+        );
+        // A key longer than the block is hashed first.
+        assert_eq!(
+            hex(&Hmac::<Sha256>::mac(
+                &[0xAA; 131],
+                b"Test Using Larger Than Block-Size Key - Hash Key First"
+            )),
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+        );
+    }
+
+    #[test]
+    fn verify_rejects_other_tags() {
+        let tag = Hmac::<Sha256>::mac(b"key", b"message");
+        let mut wrong = tag;
+        wrong[31] ^= 1;
+        let hmac = Hmac::<Sha256>::new(b"key");
+        let mut signed = hmac.clone();
+        signed.update(b"message");
+        assert!(signed.clone().verify(&tag));
+        assert!(!signed.clone().verify(&wrong));
+        assert!(!signed.verify(&tag[..16]));
+        assert!(!hmac.verify(&tag));
+    }
+
+    #[test]
+    fn rfc_5869_vectors() {
+        let salt: Vec<u8> = (0..=0x0C).collect();
+        let info: Vec<u8> = (0xF0..=0xF9).collect();
+        let hkdf = Hkdf::<Sha256>::extract(Some(&salt), &[0x0B; 22]);
+        assert_eq!(
+            hex(hkdf.prk()),
+            "077709362c2e32df0ddc3f0dc47bba6390b6c73bb50f9c3122ec844ad7c2b3e5"
+        );
+        let mut okm = [0; 42];
+        hkdf.expand(&info, &mut okm).unwrap();
+        assert_eq!(
+            hex(&okm),
+            "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf34007208d5b887185865"
+        );
+
+        let hkdf = Hkdf::<Sha256>::extract(None, &[0x0B; 22]);
+        assert_eq!(
+            hex(hkdf.prk()),
+            "19ef24a32c717b167f33a91d6f648bdf96596776afdb6377ac434c1c293ccb04"
+        );
+        hkdf.expand(b"", &mut okm).unwrap();
+        assert_eq!(
+            hex(&okm),
+            "8da4e775a563c18f715f802a063c5a31b8a11f5c5ee1879ec3454e5f3c738d2d9d201395faa4b61a96c8"
+        );
+
+        let mut too_long = vec![0; 255 * 32 + 1];
+        assert_eq!(hkdf.expand(b"", &mut too_long), Err(InvalidLength));
+        assert!(hkdf.expand(b"", &mut too_long[1..]).is_ok());
+    }
+}
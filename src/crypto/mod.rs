@@ -1,4 +1,88 @@
 /// Adler function
 pub mod adler;
+/// BLAKE2b and BLAKE2s hash functions
+pub mod blake2;
+/// BLAKE3 hash function, including multi-threaded hashing
+pub mod blake3;
+/// Crc function
+pub mod crc;
+/// Rsync-style deltas between byte buffers
+pub mod delta;
+/// A common interface to cryptographic hash functions
+pub mod digest;
+/// Random bytes from the operating system
+#[cfg(feature = "std")]
+pub mod entropy;
 /// Fnv function
 pub mod fnv;
+/// HMAC message authentication and HKDF key derivation
+pub mod hmac;
+/// MurmurHash3 hash functions
+pub mod murmur3;
+/// Checksums computed on several threads at once
+#[cfg(feature = "std")]
+pub mod parallel;
+/// SHA-224, SHA-256, SHA-384 and SHA-512
+pub mod sha2;
+/// Keyed SipHash and HalfSipHash hash functions
+pub mod siphash;
+/// wyhash hash function
+pub mod wyhash;
+/// XXH64 and XXH3 hash functions
+pub mod xxhash;
+
+/// Words that hash functions serialize big-endian.
+pub(crate) trait Word: Copy {
+    /// The bytes of one word, most significant first.
+    type Bytes: IntoIterator<Item = u8>;
+
+    /// Returns the word's big-endian bytes.
+    fn to_be(self) -> Self::Bytes;
+}
+
+impl Word for u32 {
+    type Bytes = [u8; 4];
+
+    fn to_be(self) -> [u8; 4] {
+        self.to_be_bytes()
+    }
+}
+
+impl Word for u64 {
+    type Bytes = [u8; 8];
+
+    fn to_be(self) -> [u8; 8] {
+        self.to_be_bytes()
+    }
+}
+
+/// Writes `words` big-endian into the start of `out`, cutting the last word
+/// short if `out` ends inside it.
+pub(crate) fn write_be<W: Word>(words: &[W], out: &mut [u8]) {
+    let bytes = words.iter().flat_map(|word| word.to_be());
+    for (byte, value) in out.iter_mut().zip(bytes) {
+        *byte = value;
+    }
+}
+
+/// Helpers shared by the hash function tests.
+#[cfg(test)]
+pub(crate) mod test_util {
+    use alloc::string::String;
+    use alloc::vec::Vec;
+    use core::fmt::Write;
+
+    /// Formats `bytes` as lowercase hex.
+    pub(crate) fn hex(bytes: &[u8]) -> String {
+        let mut hex = String::new();
+        for byte in bytes {
+            write!(hex, "{:02x}", byte).unwrap();
+        }
+        hex
+    }
+
+    /// Returns `len` bytes counting up from zero and wrapping at 251.
+    pub(crate) fn pattern(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i % 251) as u8).collect()
+    }
+}
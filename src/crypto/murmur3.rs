@@ -0,0 +1,334 @@
+#![deny(missing_docs)]
+
+use core::convert::TryInto;
+use core::hash::Hasher;
+
+const C1_32: u32 = 0xCC9E_2D51;
+const C2_32: u32 = 0x1B87_3593;
+const C1_64: u64 = 0x87C3_7B91_1142_53D5;
+const C2_64: u64 = 0x4CF5_AD43_2745_937F;
+
+/// Returns the 32-bit MurmurHash3 (`MurmurHash3_x86_32`) of `bytes`.
+/// ## Examples
+/// ```
+/// # use stdlib_rs::crypto::murmur3::murmur3_32;
+/// assert_eq!(murmur3_32(b"Hello, world!", 0x9747_B28C), 0x2488_4CBA);
+/// ```
+pub fn murmur3_32(bytes: &[u8], seed: u32) -> u32 {
+    let mut hasher = Murmur32Hasher::seed(seed);
+    hasher.write(bytes);
+    hasher.finish() as u32
+}
+
+/// Returns the 128-bit MurmurHash3 (`MurmurHash3_x64_128`) of `bytes`. The
+/// reference's 16 output bytes are the little-endian bytes of the result.
+/// ## Examples
+/// ```
+/// # use stdlib_rs::crypto::murmur3::murmur3_128;
+/// assert_eq!(murmur3_128(b"", 0), 0);
+/// ```
+pub fn murmur3_128(bytes: &[u8], seed: u32) -> u128 {
+    let mut hasher = Murmur128Hasher::seed(seed);
+    hasher.write(bytes);
+    hasher.finish_128()
+}
+
+/// A hasher that returns 32-bit MurmurHash3 hashes.
+/// ## Examples
+/// ```
+/// # use stdlib_rs::crypto::murmur3::{murmur3_32, Murmur32Hasher};
+/// # use std::hash::Hasher;
+/// let mut hasher = Murmur32Hasher::seed(42);
+/// hasher.write(b"Hello, ");
+/// hasher.write(b"world!");
+/// assert_eq!(hasher.finish(), murmur3_32(b"Hello, world!", 42) as u64);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Murmur32Hasher {
+    h: u32,
+    tail: [u8; 4],
+    buffered: usize,
+    total: u64,
+}
+
+impl Murmur32Hasher {
+    /// Creates a hasher with the given seed. `seed(0)` is the same as
+    /// `default()`.
+    pub fn seed(seed: u32) -> Murmur32Hasher {
+        Murmur32Hasher {
+            h: seed,
+            tail: [0; 4],
+            buffered: 0,
+            total: 0,
+        }
+    }
+
+    fn block(&mut self, block: &[u8]) {
+        let k = u32::from_le_bytes(block.try_into().unwrap());
+        self.h ^= mix_k32(k);
+        self.h = self
+            .h
+            .rotate_left(13)
+            .wrapping_mul(5)
+            .wrapping_add(0xE654_6B64);
+    }
+}
+
+fn mix_k32(k: u32) -> u32 {
+    k.wrapping_mul(C1_32).rotate_left(15).wrapping_mul(C2_32)
+}
+
+fn fmix32(mut h: u32) -> u32 {
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x85EB_CA6B);
+    h ^= h >> 13;
+    h = h.wrapping_mul(0xC2B2_AE35);
+    h ^ h >> 16
+}
+
+impl Default for Murmur32Hasher {
+    fn default() -> Murmur32Hasher {
+        Murmur32Hasher::seed(0)
+    }
+}
+
+impl Hasher for Murmur32Hasher {
+    fn write(&mut self, mut bytes: &[u8]) {
+        self.total += bytes.len() as u64;
+        if self.buffered > 0 {
+            let n = bytes.len().min(4 - self.buffered);
+            self.tail[self.buffered..self.buffered + n].copy_from_slice(&bytes[..n]);
+            self.buffered += n;
+            bytes = &bytes[n..];
+            if self.buffered < 4 {
+                return;
+            }
+            let tail = self.tail;
+            self.block(&tail);
+            self.buffered = 0;
+        }
+        let mut blocks = bytes.chunks_exact(4);
+        for block in &mut blocks {
+            self.block(block);
+        }
+        let rest = blocks.remainder();
+        self.tail[..rest.len()].copy_from_slice(rest);
+        self.buffered = rest.len();
+    }
+
+    fn finish(&self) -> u64 {
+        let mut h = self.h;
+        if self.buffered > 0 {
+            let mut k = [0; 4];
+            k[..self.buffered].copy_from_slice(&self.tail[..self.buffered]);
+            h ^= mix_k32(u32::from_le_bytes(k));
+        }
+        // The reference takes the length as a 32-bit int.
+        fmix32(h ^ self.total as u32).into()
+    }
+}
+
+/// A hasher that returns 128-bit MurmurHash3 hashes through `finish_128`,
+/// and their low 64 bits through `finish`.
+/// ## Examples
+/// ```
+/// # use stdlib_rs::crypto::murmur3::{murmur3_128, Murmur128Hasher};
+/// # use std::hash::Hasher;
+/// let mut hasher = Murmur128Hasher::seed(42);
+/// hasher.write(b"The quick brown fox ");
+/// hasher.write(b"jumps over the lazy dog");
+/// let hash = murmur3_128(b"The quick brown fox jumps over the lazy dog", 42);
+/// assert_eq!(hasher.finish_128(), hash);
+/// assert_eq!(hasher.finish(), hash as u64);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Murmur128Hasher {
+    h1: u64,
+    h2: u64,
+    tail: [u8; 16],
+    buffered: usize,
+    total: u64,
+}
+
+impl Murmur128Hasher {
+    /// Creates a hasher with the given seed. `seed(0)` is the same as
+    /// `default()`.
+    pub fn seed(seed: u32) -> Murmur128Hasher {
+        Murmur128Hasher {
+            h1: seed.into(),
+            h2: seed.into(),
+            tail: [0; 16],
+            buffered: 0,
+            total: 0,
+        }
+    }
+
+    /// Returns the 128-bit hash of the bytes written so far.
+    pub fn finish_128(&self) -> u128 {
+        let (mut h1, mut h2) = (self.h1, self.h2);
+        if self.buffered > 0 {
+            let mut k = [0; 16];
+            k[..self.buffered].copy_from_slice(&self.tail[..self.buffered]);
+            let (k1, k2) = read_block(&k);
+            h1 ^= mix_k1(k1);
+            h2 ^= mix_k2(k2);
+        }
+        h1 ^= self.total;
+        h2 ^= self.total;
+        h1 = h1.wrapping_add(h2);
+        h2 = h2.wrapping_add(h1);
+        h1 = fmix64(h1);
+        h2 = fmix64(h2);
+        h1 = h1.wrapping_add(h2);
+        h2 = h2.wrapping_add(h1);
+        (h2 as u128) << 64 | h1 as u128
+    }
+
+    fn block(&mut self, block: &[u8]) {
+        let (k1, k2) = read_block(block);
+        self.h1 ^= mix_k1(k1);
+        self.h1 = self
+            .h1
+            .rotate_left(27)
+            .wrapping_add(self.h2)
+            .wrapping_mul(5)
+            .wrapping_add(0x52DC_E729);
+        self.h2 ^= mix_k2(k2);
+        self.h2 = self
+            .h2
+            .rotate_left(31)
+            .wrapping_add(self.h1)
+            .wrapping_mul(5)
+            .wrapping_add(0x3849_5AB5);
+    }
+}
+
+fn read_block(block: &[u8]) -> (u64, u64) {
+    (
+        u64::from_le_bytes(block[..8].try_into().unwrap()),
+        u64::from_le_bytes(block[8..16].try_into().unwrap()),
+    )
+}
+
+fn mix_k1(k: u64) -> u64 {
+    k.wrapping_mul(C1_64).rotate_left(31).wrapping_mul(C2_64)
+}
+
+fn mix_k2(k: u64) -> u64 {
+    k.wrapping_mul(C2_64).rotate_left(33).wrapping_mul(C1_64)
+}
+
+fn fmix64(mut k: u64) -> u64 {
+    k ^= k >> 33;
+    k = k.wrapping_mul(0xFF51_AFD7_ED55_8CCD);
+    k ^= k >> 33;
+    k = k.wrapping_mul(0xC4CE_B9FE_1A85_EC53);
+    k ^ k >> 33
+}
+
+impl Default for Murmur128Hasher {
+    fn default() -> Murmur128Hasher {
+        Murmur128Hasher::seed(0)
+    }
+}
+
+impl Hasher for Murmur128Hasher {
+    fn write(&mut self, mut bytes: &[u8]) {
+        self.total += bytes.len() as u64;
+        if self.buffered > 0 {
+            let n = bytes.len().min(16 - self.buffered);
+            self.tail[self.buffered..self.buffered + n].copy_from_slice(&bytes[..n]);
+            self.buffered += n;
+            bytes = &bytes[n..];
+            if self.buffered < 16 {
+                return;
+            }
+            let tail = self.tail;
+            self.block(&tail);
+            self.buffered = 0;
+        }
+        let mut blocks = bytes.chunks_exact(16);
+        for block in &mut blocks {
+            self.block(block);
+        }
+        let rest = blocks.remainder();
+        self.tail[..rest.len()].copy_from_slice(rest);
+        self.buffered = rest.len();
+    }
+
+    fn finish(&self) -> u64 {
+        self.finish_128() as u64
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::vec::Vec;
+
+    /// SMHasher's verification: hashes keys `[0]`, `[0, 1]`, ... up to 255
+    /// bytes, each with seed `256 - len`, then hashes the hashes.
+    fn verification<const N: usize>(hash: impl Fn(&[u8], u32) -> [u8; N]) -> u32 {
+        let key: Vec<u8> = (0..=255).collect();
+        let mut hashes = Vec::new();
+        for len in 0..256 {
+            hashes.extend_from_slice(&hash(&key[..len], 256 - len as u32));
+        }
+        let last = hash(&hashes, 0);
+        u32::from_le_bytes(last[..4].try_into().unwrap())
+    }
+
+    #[test]
+    fn verification_values() {
+        assert_eq!(
+            verification(|key, seed| murmur3_32(key, seed).to_le_bytes()),
+            0xB0F5_7EE3
+        );
+        assert_eq!(
+            verification(|key, seed| murmur3_128(key, seed).to_le_bytes()),
+            0x6384_BA69
+        );
+    }
+
+    #[test]
+    fn murmur3_32_vectors() {
+        let cases: [(&[u8], u32, u32); 8] = [
+            (b"", 0, 0),
+            (b"", 1, 0x514E_28B7),
+            (b"", 0xFFFF_FFFF, 0x81F1_6F39),
+            (&[0; 4], 0, 0x2362_F9DE),
+            (b"aaaa", 0x9747_B28C, 0x5A97_808A),
+            (b"abc", 0, 0xB3DD_93FA),
+            (b"abcd", 0x9747_B28C, 0xF047_8627),
+            (
+                b"The quick brown fox jumps over the lazy dog",
+                0x9747_B28C,
+                0x2FA8_26CD,
+            ),
+        ];
+        for (bytes, seed, expected) in cases {
+            assert_eq!(murmur3_32(bytes, seed), expected);
+        }
+    }
+
+    #[test]
+    fn streaming_matches_one_shot() {
+        let data: Vec<u8> = (0..100u32).map(|i| (i * 37 % 256) as u8).collect();
+        for len in 0..data.len() {
+            let mut hasher_32 = Murmur32Hasher::seed(len as u32);
+            let mut hasher_128 = Murmur128Hasher::seed(len as u32);
+            for chunk in data[..len].chunks(3) {
+                hasher_32.write(chunk);
+                hasher_128.write(chunk);
+            }
+            assert_eq!(
+                hasher_32.finish(),
+                murmur3_32(&data[..len], len as u32) as u64
+            );
+            assert_eq!(
+                hasher_128.finish_128(),
+                murmur3_128(&data[..len], len as u32)
+            );
+        }
+    }
+}
@@ -0,0 +1,116 @@
+#![deny(missing_docs)]
+
+use std::hash::Hasher;
+use std::thread;
+
+use super::adler::{adler32_combine, Adler32Hasher};
+use super::crc::{crc32_combine, Crc32Hasher};
+
+/// A 32-bit checksum whose values for two buffers can be combined into the
+/// value for both buffers joined together.
+pub trait Combine: Hasher + Default {
+    /// Returns the checksum of two buffers joined together, given the
+    /// checksum of each and the length of the second.
+    fn combine(checksum_a: u32, checksum_b: u32, len_b: u64) -> u32;
+}
+
+impl Combine for Adler32Hasher {
+    fn combine(checksum_a: u32, checksum_b: u32, len_b: u64) -> u32 {
+        adler32_combine(checksum_a, checksum_b, len_b)
+    }
+}
+
+impl Combine for Crc32Hasher {
+    fn combine(checksum_a: u32, checksum_b: u32, len_b: u64) -> u32 {
+        crc32_combine(checksum_a, checksum_b, len_b)
+    }
+}
+
+/// Computes the checksum of `bytes` by splitting them into `chunks` pieces
+/// of about the same size, hashing each piece on its own thread, and
+/// combining the results. `chunks` is capped at the number of CPUs, so
+/// asking for more pieces never starts more threads than can run at once.
+/// ## Panics
+/// Panics if `chunks` is zero, since there is no way to split the bytes
+/// into no pieces.
+/// ## Examples
+/// ```
+/// # use stdlib_rs::crypto::crc::Crc32Hasher;
+/// # use stdlib_rs::crypto::parallel::parallel_checksum;
+/// let data = vec![7u8; 1 << 20];
+/// let crc = parallel_checksum::<Crc32Hasher>(&data, 4);
+/// assert_eq!(crc, parallel_checksum::<Crc32Hasher>(&data, 1));
+/// ```
+pub fn parallel_checksum<H: Combine>(bytes: &[u8], chunks: usize) -> u32 {
+    assert!(chunks > 0, "a checksum needs at least one chunk");
+    let cpus = thread::available_parallelism().map_or(1, |n| n.get());
+    let chunk_size = bytes.len().div_ceil(chunks.min(cpus)).max(1);
+    thread::scope(|scope| {
+        let handles: Vec<_> = bytes
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    let mut hasher = H::default();
+                    hasher.write(chunk);
+                    (hasher.finish() as u32, chunk.len() as u64)
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .fold(H::default().finish() as u32, |checksum, (next, len)| {
+                H::combine(checksum, next, len)
+            })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sequential<H: Combine>(bytes: &[u8]) -> u32 {
+        let mut hasher = H::default();
+        hasher.write(bytes);
+        hasher.finish() as u32
+    }
+
+    #[test]
+    fn matches_sequential() {
+        let data: Vec<u8> = (0..100_003u32)
+            .map(|i| (i.wrapping_mul(2_654_435_761) >> 24) as u8)
+            .collect();
+        for chunks in [1, 2, 3, 8, 64] {
+            assert_eq!(
+                parallel_checksum::<Adler32Hasher>(&data, chunks),
+                sequential::<Adler32Hasher>(&data)
+            );
+            assert_eq!(
+                parallel_checksum::<Crc32Hasher>(&data, chunks),
+                sequential::<Crc32Hasher>(&data)
+            );
+        }
+    }
+
+    #[test]
+    fn more_chunks_than_bytes() {
+        assert_eq!(parallel_checksum::<Adler32Hasher>(b"", 4), 1);
+        assert_eq!(parallel_checksum::<Crc32Hasher>(b"", 4), 0);
+        assert_eq!(
+            parallel_checksum::<Crc32Hasher>(b"123456789", 100),
+            0xCBF4_3926
+        );
+        // One piece per byte would be a thread per byte without the cap.
+        let data = vec![0x5Au8; 1 << 20];
+        assert_eq!(
+            parallel_checksum::<Crc32Hasher>(&data, data.len()),
+            sequential::<Crc32Hasher>(&data)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one chunk")]
+    fn zero_chunks() {
+        parallel_checksum::<Crc32Hasher>(b"123456789", 0);
+    }
+}
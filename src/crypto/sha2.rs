@@ -0,0 +1,582 @@
+#![deny(missing_docs)]
+
+use core::convert::TryInto;
+
+use super::digest::Digest;
+use super::write_be;
+
+/// The first 32 bits of the fractional parts of the cube roots of the first
+/// 64 primes.
+const K256: [u32; 64] = [
+    0x428A_2F98,
+    0x7137_4491,
+    0xB5C0_FBCF,
+    0xE9B5_DBA5,
+    0x3956_C25B,
+    0x59F1_11F1,
+    0x923F_82A4,
+    0xAB1C_5ED5,
+    0xD807_AA98,
+    0x1283_5B01,
+    0x2431_85BE,
+    0x550C_7DC3,
+    0x72BE_5D74,
+    0x80DE_B1FE,
+    0x9BDC_06A7,
+    0xC19B_F174,
+    0xE49B_69C1,
+    0xEFBE_4786,
+    0x0FC1_9DC6,
+    0x240C_A1CC,
+    0x2DE9_2C6F,
+    0x4A74_84AA,
+    0x5CB0_A9DC,
+    0x76F9_88DA,
+    0x983E_5152,
+    0xA831_C66D,
+    0xB003_27C8,
+    0xBF59_7FC7,
+    0xC6E0_0BF3,
+    0xD5A7_9147,
+    0x06CA_6351,
+    0x1429_2967,
+    0x27B7_0A85,
+    0x2E1B_2138,
+    0x4D2C_6DFC,
+    0x5338_0D13,
+    0x650A_7354,
+    0x766A_0ABB,
+    0x81C2_C92E,
+    0x9272_2C85,
+    0xA2BF_E8A1,
+    0xA81A_664B,
+    0xC24B_8B70,
+    0xC76C_51A3,
+    0xD192_E819,
+    0xD699_0624,
+    0xF40E_3585,
+    0x106A_A070,
+    0x19A4_C116,
+    0x1E37_6C08,
+    0x2748_774C,
+    0x34B0_BCB5,
+    0x391C_0CB3,
+    0x4ED8_AA4A,
+    0x5B9C_CA4F,
+    0x682E_6FF3,
+    0x748F_82EE,
+    0x78A5_636F,
+    0x84C8_7814,
+    0x8CC7_0208,
+    0x90BE_FFFA,
+    0xA450_6CEB,
+    0xBEF9_A3F7,
+    0xC671_78F2,
+];
+
+/// The first 64 bits of the fractional parts of the cube roots of the first
+/// 80 primes.
+const K512: [u64; 80] = [
+    0x428A_2F98_D728_AE22,
+    0x7137_4491_23EF_65CD,
+    0xB5C0_FBCF_EC4D_3B2F,
+    0xE9B5_DBA5_8189_DBBC,
+    0x3956_C25B_F348_B538,
+    0x59F1_11F1_B605_D019,
+    0x923F_82A4_AF19_4F9B,
+    0xAB1C_5ED5_DA6D_8118,
+    0xD807_AA98_A303_0242,
+    0x1283_5B01_4570_6FBE,
+    0x2431_85BE_4EE4_B28C,
+    0x550C_7DC3_D5FF_B4E2,
+    0x72BE_5D74_F27B_896F,
+    0x80DE_B1FE_3B16_96B1,
+    0x9BDC_06A7_25C7_1235,
+    0xC19B_F174_CF69_2694,
+    0xE49B_69C1_9EF1_4AD2,
+    0xEFBE_4786_384F_25E3,
+    0x0FC1_9DC6_8B8C_D5B5,
+    0x240C_A1CC_77AC_9C65,
+    0x2DE9_2C6F_592B_0275,
+    0x4A74_84AA_6EA6_E483,
+    0x5CB0_A9DC_BD41_FBD4,
+    0x76F9_88DA_8311_53B5,
+    0x983E_5152_EE66_DFAB,
+    0xA831_C66D_2DB4_3210,
+    0xB003_27C8_98FB_213F,
+    0xBF59_7FC7_BEEF_0EE4,
+    0xC6E0_0BF3_3DA8_8FC2,
+    0xD5A7_9147_930A_A725,
+    0x06CA_6351_E003_826F,
+    0x1429_2967_0A0E_6E70,
+    0x27B7_0A85_46D2_2FFC,
+    0x2E1B_2138_5C26_C926,
+    0x4D2C_6DFC_5AC4_2AED,
+    0x5338_0D13_9D95_B3DF,
+    0x650A_7354_8BAF_63DE,
+    0x766A_0ABB_3C77_B2A8,
+    0x81C2_C92E_47ED_AEE6,
+    0x9272_2C85_1482_353B,
+    0xA2BF_E8A1_4CF1_0364,
+    0xA81A_664B_BC42_3001,
+    0xC24B_8B70_D0F8_9791,
+    0xC76C_51A3_0654_BE30,
+    0xD192_E819_D6EF_5218,
+    0xD699_0624_5565_A910,
+    0xF40E_3585_5771_202A,
+    0x106A_A070_32BB_D1B8,
+    0x19A4_C116_B8D2_D0C8,
+    0x1E37_6C08_5141_AB53,
+    0x2748_774C_DF8E_EB99,
+    0x34B0_BCB5_E19B_48A8,
+    0x391C_0CB3_C5C9_5A63,
+    0x4ED8_AA4A_E341_8ACB,
+    0x5B9C_CA4F_7763_E373,
+    0x682E_6FF3_D6B2_B8A3,
+    0x748F_82EE_5DEF_B2FC,
+    0x78A5_636F_4317_2F60,
+    0x84C8_7814_A1F0_AB72,
+    0x8CC7_0208_1A64_39EC,
+    0x90BE_FFFA_2363_1E28,
+    0xA450_6CEB_DE82_BDE9,
+    0xBEF9_A3F7_B2C6_7915,
+    0xC671_78F2_E372_532B,
+    0xCA27_3ECE_EA26_619C,
+    0xD186_B8C7_21C0_C207,
+    0xEADA_7DD6_CDE0_EB1E,
+    0xF57D_4F7F_EE6E_D178,
+    0x06F0_67AA_7217_6FBA,
+    0x0A63_7DC5_A2C8_98A6,
+    0x113F_9804_BEF9_0DAE,
+    0x1B71_0B35_131C_471B,
+    0x28DB_77F5_2304_7D84,
+    0x32CA_AB7B_40C7_2493,
+    0x3C9E_BE0A_15C9_BEBC,
+    0x431D_67C4_9C10_0D4C,
+    0x4CC5_D4BE_CB3E_42B6,
+    0x597F_299C_FC65_7E2A,
+    0x5FCB_6FAB_3AD6_FAEC,
+    0x6C44_198C_4A47_5817,
+];
+
+/// The state of SHA-224 and SHA-256, which differ only in their initial
+/// hash and output length.
+#[derive(Debug, Clone)]
+struct State256 {
+    h: [u32; 8],
+    buffer: [u8; 64],
+    buffered: usize,
+    total: u64,
+}
+
+impl State256 {
+    fn new(h: [u32; 8]) -> State256 {
+        State256 {
+            h,
+            buffer: [0; 64],
+            buffered: 0,
+            total: 0,
+        }
+    }
+
+    fn update(&mut self, mut bytes: &[u8]) {
+        self.total += bytes.len() as u64;
+        if self.buffered > 0 {
+            let n = bytes.len().min(64 - self.buffered);
+            self.buffer[self.buffered..self.buffered + n].copy_from_slice(&bytes[..n]);
+            self.buffered += n;
+            bytes = &bytes[n..];
+            if self.buffered < 64 {
+                return;
+            }
+            compress256(&mut self.h, &self.buffer);
+            self.buffered = 0;
+        }
+        let mut blocks = bytes.chunks_exact(64);
+        for block in &mut blocks {
+            compress256(&mut self.h, block);
+        }
+        let rest = blocks.remainder();
+        self.buffer[..rest.len()].copy_from_slice(rest);
+        self.buffered = rest.len();
+    }
+
+    /// Pads the input and returns the final hash words.
+    fn finalize(mut self) -> [u32; 8] {
+        let bits = self.total * 8;
+        self.update(&[0x80]);
+        while self.buffered != 56 {
+            self.update(&[0]);
+        }
+        self.update(&bits.to_be_bytes());
+        self.h
+    }
+}
+
+fn compress256(h: &mut [u32; 8], block: &[u8]) {
+    let mut w = [0u32; 64];
+    for (i, word) in block.chunks_exact(4).enumerate() {
+        w[i] = u32::from_be_bytes(word.try_into().unwrap());
+    }
+    for i in 16..64 {
+        let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ w[i - 15] >> 3;
+        let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ w[i - 2] >> 10;
+        w[i] = w[i - 16]
+            .wrapping_add(s0)
+            .wrapping_add(w[i - 7])
+            .wrapping_add(s1);
+    }
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut hh] = *h;
+    for i in 0..64 {
+        let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+        let ch = (e & f) ^ (!e & g);
+        let t1 = hh
+            .wrapping_add(s1)
+            .wrapping_add(ch)
+            .wrapping_add(K256[i])
+            .wrapping_add(w[i]);
+        let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let t2 = s0.wrapping_add(maj);
+        hh = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(t1);
+        d = c;
+        c = b;
+        b = a;
+        a = t1.wrapping_add(t2);
+    }
+    for (word, value) in h.iter_mut().zip([a, b, c, d, e, f, g, hh]) {
+        *word = word.wrapping_add(value);
+    }
+}
+
+/// The state of SHA-384 and SHA-512, which differ only in their initial
+/// hash and output length.
+#[derive(Debug, Clone)]
+struct State512 {
+    h: [u64; 8],
+    buffer: [u8; 128],
+    buffered: usize,
+    total: u128,
+}
+
+impl State512 {
+    fn new(h: [u64; 8]) -> State512 {
+        State512 {
+            h,
+            buffer: [0; 128],
+            buffered: 0,
+            total: 0,
+        }
+    }
+
+    fn update(&mut self, mut bytes: &[u8]) {
+        self.total += bytes.len() as u128;
+        if self.buffered > 0 {
+            let n = bytes.len().min(128 - self.buffered);
+            self.buffer[self.buffered..self.buffered + n].copy_from_slice(&bytes[..n]);
+            self.buffered += n;
+            bytes = &bytes[n..];
+            if self.buffered < 128 {
+                return;
+            }
+            compress512(&mut self.h, &self.buffer);
+            self.buffered = 0;
+        }
+        let mut blocks = bytes.chunks_exact(128);
+        for block in &mut blocks {
+            compress512(&mut self.h, block);
+        }
+        let rest = blocks.remainder();
+        self.buffer[..rest.len()].copy_from_slice(rest);
+        self.buffered = rest.len();
+    }
+
+    /// Pads the input and returns the final hash words.
+    fn finalize(mut self) -> [u64; 8] {
+        let bits = self.total * 8;
+        self.update(&[0x80]);
+        while self.buffered != 112 {
+            self.update(&[0]);
+        }
+        self.update(&bits.to_be_bytes());
+        self.h
+    }
+}
+
+fn compress512(h: &mut [u64; 8], block: &[u8]) {
+    let mut w = [0u64; 80];
+    for (i, word) in block.chunks_exact(8).enumerate() {
+        w[i] = u64::from_be_bytes(word.try_into().unwrap());
+    }
+    for i in 16..80 {
+        let s0 = w[i - 15].rotate_right(1) ^ w[i - 15].rotate_right(8) ^ w[i - 15] >> 7;
+        let s1 = w[i - 2].rotate_right(19) ^ w[i - 2].rotate_right(61) ^ w[i - 2] >> 6;
+        w[i] = w[i - 16]
+            .wrapping_add(s0)
+            .wrapping_add(w[i - 7])
+            .wrapping_add(s1);
+    }
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut hh] = *h;
+    for i in 0..80 {
+        let s1 = e.rotate_right(14) ^ e.rotate_right(18) ^ e.rotate_right(41);
+        let ch = (e & f) ^ (!e & g);
+        let t1 = hh
+            .wrapping_add(s1)
+            .wrapping_add(ch)
+            .wrapping_add(K512[i])
+            .wrapping_add(w[i]);
+        let s0 = a.rotate_right(28) ^ a.rotate_right(34) ^ a.rotate_right(39);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let t2 = s0.wrapping_add(maj);
+        hh = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(t1);
+        d = c;
+        c = b;
+        b = a;
+        a = t1.wrapping_add(t2);
+    }
+    for (word, value) in h.iter_mut().zip([a, b, c, d, e, f, g, hh]) {
+        *word = word.wrapping_add(value);
+    }
+}
+
+/// SHA-224, SHA-256 cut to 28 bytes with a different initial hash.
+/// ## Examples
+/// ```
+/// # use stdlib_rs::crypto::digest::Digest;
+/// # use stdlib_rs::crypto::sha2::Sha224;
+/// assert_eq!(Sha224::digest(b"abc")[..4], [0x23, 0x09, 0x7D, 0x22]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Sha224(State256);
+
+impl Default for Sha224 {
+    fn default() -> Sha224 {
+        Sha224(State256::new([
+            0xC105_9ED8,
+            0x367C_D507,
+            0x3070_DD17,
+            0xF70E_5939,
+            0xFFC0_0B31,
+            0x6858_1511,
+            0x64F9_8FA7,
+            0xBEFA_4FA4,
+        ]))
+    }
+}
+
+impl Digest for Sha224 {
+    type Output = [u8; 28];
+    const BLOCK_LEN: usize = 64;
+
+    fn update(&mut self, bytes: &[u8]) {
+        self.0.update(bytes)
+    }
+
+    fn finalize(self) -> [u8; 28] {
+        let mut out = [0; 28];
+        write_be(&self.0.finalize(), &mut out);
+        out
+    }
+}
+
+/// SHA-256.
+/// ## Examples
+/// ```
+/// # use stdlib_rs::crypto::digest::Digest;
+/// # use stdlib_rs::crypto::sha2::Sha256;
+/// let hash = Sha256::digest(b"abc");
+/// assert_eq!(hash[..4], [0xBA, 0x78, 0x16, 0xBF]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Sha256(State256);
+
+impl Default for Sha256 {
+    fn default() -> Sha256 {
+        Sha256(State256::new([
+            0x6A09_E667,
+            0xBB67_AE85,
+            0x3C6E_F372,
+            0xA54F_F53A,
+            0x510E_527F,
+            0x9B05_688C,
+            0x1F83_D9AB,
+            0x5BE0_CD19,
+        ]))
+    }
+}
+
+impl Digest for Sha256 {
+    type Output = [u8; 32];
+    const BLOCK_LEN: usize = 64;
+
+    fn update(&mut self, bytes: &[u8]) {
+        self.0.update(bytes)
+    }
+
+    fn finalize(self) -> [u8; 32] {
+        let mut out = [0; 32];
+        write_be(&self.0.finalize(), &mut out);
+        out
+    }
+}
+
+/// SHA-384, SHA-512 cut to 48 bytes with a different initial hash.
+#[derive(Debug, Clone)]
+pub struct Sha384(State512);
+
+impl Default for Sha384 {
+    fn default() -> Sha384 {
+        Sha384(State512::new([
+            0xCBBB_9D5D_C105_9ED8,
+            0x629A_292A_367C_D507,
+            0x9159_015A_3070_DD17,
+            0x152F_ECD8_F70E_5939,
+            0x6733_2667_FFC0_0B31,
+            0x8EB4_4A87_6858_1511,
+            0xDB0C_2E0D_64F9_8FA7,
+            0x47B5_481D_BEFA_4FA4,
+        ]))
+    }
+}
+
+impl Digest for Sha384 {
+    type Output = [u8; 48];
+    const BLOCK_LEN: usize = 128;
+
+    fn update(&mut self, bytes: &[u8]) {
+        self.0.update(bytes)
+    }
+
+    fn finalize(self) -> [u8; 48] {
+        let mut out = [0; 48];
+        write_be(&self.0.finalize(), &mut out);
+        out
+    }
+}
+
+/// SHA-512.
+#[derive(Debug, Clone)]
+pub struct Sha512(State512);
+
+impl Default for Sha512 {
+    fn default() -> Sha512 {
+        Sha512(State512::new([
+            0x6A09_E667_F3BC_C908,
+            0xBB67_AE85_84CA_A73B,
+            0x3C6E_F372_FE94_F82B,
+            0xA54F_F53A_5F1D_36F1,
+            0x510E_527F_ADE6_82D1,
+            0x9B05_688C_2B3E_6C1F,
+            0x1F83_D9AB_FB41_BD6B,
+            0x5BE0_CD19_137E_2179,
+        ]))
+    }
+}
+
+impl Digest for Sha512 {
+    type Output = [u8; 64];
+    const BLOCK_LEN: usize = 128;
+
+    fn update(&mut self, bytes: &[u8]) {
+        self.0.update(bytes)
+    }
+
+    fn finalize(self) -> [u8; 64] {
+        let mut out = [0; 64];
+        write_be(&self.0.finalize(), &mut out);
+        out
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::test_util::hex;
+    use super::*;
+    use alloc::vec;
+
+    const ABC_2: &[u8] = b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq";
+    const ABC_4: &[u8] = b"abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmn\
+        hijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu";
+
+    #[test]
+    fn sha256_vectors() {
+        let cases: [(&[u8], &str); 3] = [
+            (
+                b"",
+                "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+            ),
+            (
+                b"abc",
+                "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+            ),
+            (
+                ABC_2,
+                "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1",
+            ),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(hex(&Sha256::digest(input)), expected);
+        }
+        assert_eq!(
+            hex(&Sha224::digest(b"abc")),
+            "23097d223405d8228642a477bda255b32aadbce4bda0b3f7e36c9da7"
+        );
+        assert_eq!(
+            hex(&Sha224::digest(b"")),
+            "d14a028c2a3a2bc9476102bb288234c415a2b01f828ea62ac5b3e42f"
+        );
+    }
+
+    #[test]
+    fn sha512_vectors() {
+        assert_eq!(
+            hex(&Sha512::digest(b"")),
+            "cf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce\
+             47d0d13c5d85f2b0ff8318d2877eec2f63b931bd47417a81a538327af927da3e"
+        );
+        assert_eq!(
+            hex(&Sha512::digest(b"abc")),
+            "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a\
+             2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f"
+        );
+        assert_eq!(
+            hex(&Sha512::digest(ABC_4)),
+            "8e959b75dae313da8cf4f72814fc143f8f7779c6eb9f7fa17299aeadb6889018\
+             501d289e4900f7e4331b99dec4b5433ac7d329eeb6dd26545e96e55b874be909"
+        );
+        assert_eq!(
+            hex(&Sha384::digest(b"abc")),
+            "cb00753f45a35e8bb5a03d699ac65007272c32ab0eded163\
+             1a8b605a43ff5bed8086072ba1e7cc2358baeca134c825a7"
+        );
+        assert_eq!(
+            hex(&Sha384::digest(ABC_4)),
+            "09330c33f71147e83d192fc782cd1b4753111b173b3b05d2\
+             2fa08086e3b0f712fcc7c71a557e2db966c3e9fa91746039"
+        );
+    }
+
+    #[test]
+    fn million_a() {
+        let input = vec![b'a'; 1_000_000];
+        let mut hasher = Sha256::default();
+        for chunk in input.chunks(999) {
+            hasher.update(chunk);
+        }
+        assert_eq!(
+            hex(&hasher.finalize()),
+            "cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0"
+        );
+        assert_eq!(
+            hex(&Sha512::digest(&input)),
+            "e718483d0ce769644e2e42c7bc15b4638e1f98b13b2044285632a803afa973eb\
+             de0ff244877ea60a4cb0432ce577c31beb009c5c2c49aa2e4eadb217ad8cc09b"
+        );
+    }
+}
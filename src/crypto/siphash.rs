@@ -0,0 +1,390 @@
+#![deny(missing_docs)]
+
+use core::convert::TryInto;
+#[cfg(feature = "std")]
+use core::hash::BuildHasher;
+use core::hash::Hasher;
+
+/// A keyed hasher that can be built from a random key by `RandomState`.
+pub trait KeyedHasher: Hasher {
+    /// The key, `[u8; 16]` for SipHash and `[u8; 8]` for HalfSipHash.
+    type Key: Copy + Default + AsMut<[u8]>;
+
+    /// Creates a hasher with the given key.
+    fn with_key(key: Self::Key) -> Self;
+}
+
+/// A hasher that returns SipHash-c-d hashes: `C` rounds per 8-byte word and
+/// `D` rounds to finish. Without knowing the key, an attacker cannot choose
+/// keys that collide.
+/// ## Examples
+/// ```
+/// # use stdlib_rs::crypto::siphash::SipHasher24;
+/// # use std::hash::Hasher;
+/// let key = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15];
+/// let mut hasher = SipHasher24::new_with_key(key);
+/// hasher.write(&[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14]);
+/// assert_eq!(hasher.finish(), 0xA129_CA61_49BE_45E5);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SipHasher<const C: usize, const D: usize> {
+    v: [u64; 4],
+    tail: [u8; 8],
+    buffered: usize,
+    total: u64,
+}
+
+/// SipHash-2-4, the variant from the SipHash paper.
+pub type SipHasher24 = SipHasher<2, 4>;
+/// SipHash-1-3, faster and still good enough for hash tables, as used by
+/// `std`'s `HashMap`.
+pub type SipHasher13 = SipHasher<1, 3>;
+
+impl<const C: usize, const D: usize> SipHasher<C, D> {
+    /// Creates a hasher with the 128-bit key `k0`, `k1`.
+    pub fn new_with_keys(k0: u64, k1: u64) -> Self {
+        SipHasher {
+            v: [
+                k0 ^ 0x736F_6D65_7073_6575,
+                k1 ^ 0x646F_7261_6E64_6F6D,
+                k0 ^ 0x6C79_6765_6E65_7261,
+                k1 ^ 0x7465_6462_7974_6573,
+            ],
+            tail: [0; 8],
+            buffered: 0,
+            total: 0,
+        }
+    }
+
+    /// Creates a hasher with a 16-byte key, read as two little-endian words
+    /// like the reference implementation does.
+    pub fn new_with_key(key: [u8; 16]) -> Self {
+        let k0 = u64::from_le_bytes(key[..8].try_into().unwrap());
+        let k1 = u64::from_le_bytes(key[8..].try_into().unwrap());
+        Self::new_with_keys(k0, k1)
+    }
+
+    fn rounds(v: &mut [u64; 4], rounds: usize) {
+        for _ in 0..rounds {
+            v[0] = v[0].wrapping_add(v[1]);
+            v[1] = v[1].rotate_left(13) ^ v[0];
+            v[0] = v[0].rotate_left(32);
+            v[2] = v[2].wrapping_add(v[3]);
+            v[3] = v[3].rotate_left(16) ^ v[2];
+            v[0] = v[0].wrapping_add(v[3]);
+            v[3] = v[3].rotate_left(21) ^ v[0];
+            v[2] = v[2].wrapping_add(v[1]);
+            v[1] = v[1].rotate_left(17) ^ v[2];
+            v[2] = v[2].rotate_left(32);
+        }
+    }
+
+    fn word(&mut self, m: u64) {
+        self.v[3] ^= m;
+        Self::rounds(&mut self.v, C);
+        self.v[0] ^= m;
+    }
+}
+
+impl<const C: usize, const D: usize> Hasher for SipHasher<C, D> {
+    fn write(&mut self, mut bytes: &[u8]) {
+        self.total += bytes.len() as u64;
+        if self.buffered > 0 {
+            let n = bytes.len().min(8 - self.buffered);
+            self.tail[self.buffered..self.buffered + n].copy_from_slice(&bytes[..n]);
+            self.buffered += n;
+            bytes = &bytes[n..];
+            if self.buffered < 8 {
+                return;
+            }
+            self.word(u64::from_le_bytes(self.tail));
+            self.buffered = 0;
+        }
+        let mut words = bytes.chunks_exact(8);
+        for word in &mut words {
+            self.word(u64::from_le_bytes(word.try_into().unwrap()));
+        }
+        let rest = words.remainder();
+        self.tail[..rest.len()].copy_from_slice(rest);
+        self.buffered = rest.len();
+    }
+
+    fn finish(&self) -> u64 {
+        let mut last = [0; 8];
+        last[..self.buffered].copy_from_slice(&self.tail[..self.buffered]);
+        // The length is taken modulo 256.
+        let b = u64::from_le_bytes(last) | self.total << 56;
+        let mut v = self.v;
+        v[3] ^= b;
+        Self::rounds(&mut v, C);
+        v[0] ^= b;
+        v[2] ^= 0xFF;
+        Self::rounds(&mut v, D);
+        v[0] ^ v[1] ^ v[2] ^ v[3]
+    }
+}
+
+impl<const C: usize, const D: usize> KeyedHasher for SipHasher<C, D> {
+    type Key = [u8; 16];
+
+    fn with_key(key: [u8; 16]) -> Self {
+        Self::new_with_key(key)
+    }
+}
+
+/// A hasher that returns 32-bit HalfSipHash-c-d hashes. It works on 32-bit
+/// words with a 64-bit key, so it is faster on small CPUs but only good for
+/// hash tables, not as a MAC.
+/// ## Examples
+/// ```
+/// # use stdlib_rs::crypto::siphash::HalfSipHasher24;
+/// # use std::hash::Hasher;
+/// let hasher = HalfSipHasher24::new_with_key([0, 1, 2, 3, 4, 5, 6, 7]);
+/// assert_eq!(hasher.finish(), 0x5B9F_35A9);
+/// ```
+#[derive(Debug, Clone)]
+pub struct HalfSipHasher<const C: usize, const D: usize> {
+    v: [u32; 4],
+    tail: [u8; 4],
+    buffered: usize,
+    total: u64,
+}
+
+/// HalfSipHash-2-4, the variant with reference test vectors.
+pub type HalfSipHasher24 = HalfSipHasher<2, 4>;
+/// HalfSipHash-1-3, the variant Linux uses for hash tables.
+pub type HalfSipHasher13 = HalfSipHasher<1, 3>;
+
+impl<const C: usize, const D: usize> HalfSipHasher<C, D> {
+    /// Creates a hasher with the 64-bit key `k0`, `k1`.
+    pub fn new_with_keys(k0: u32, k1: u32) -> Self {
+        HalfSipHasher {
+            v: [k0, k1, k0 ^ 0x6C79_6765, k1 ^ 0x7465_6462],
+            tail: [0; 4],
+            buffered: 0,
+            total: 0,
+        }
+    }
+
+    /// Creates a hasher with an 8-byte key, read as two little-endian words
+    /// like the reference implementation does.
+    pub fn new_with_key(key: [u8; 8]) -> Self {
+        let k0 = u32::from_le_bytes(key[..4].try_into().unwrap());
+        let k1 = u32::from_le_bytes(key[4..].try_into().unwrap());
+        Self::new_with_keys(k0, k1)
+    }
+
+    fn rounds(v: &mut [u32; 4], rounds: usize) {
+        for _ in 0..rounds {
+            v[0] = v[0].wrapping_add(v[1]);
+            v[1] = v[1].rotate_left(5) ^ v[0];
+            v[0] = v[0].rotate_left(16);
+            v[2] = v[2].wrapping_add(v[3]);
+            v[3] = v[3].rotate_left(8) ^ v[2];
+            v[0] = v[0].wrapping_add(v[3]);
+            v[3] = v[3].rotate_left(7) ^ v[0];
+            v[2] = v[2].wrapping_add(v[1]);
+            v[1] = v[1].rotate_left(13) ^ v[2];
+            v[2] = v[2].rotate_left(16);
+        }
+    }
+
+    fn word(&mut self, m: u32) {
+        self.v[3] ^= m;
+        Self::rounds(&mut self.v, C);
+        self.v[0] ^= m;
+    }
+}
+
+impl<const C: usize, const D: usize> Hasher for HalfSipHasher<C, D> {
+    fn write(&mut self, mut bytes: &[u8]) {
+        self.total += bytes.len() as u64;
+        if self.buffered > 0 {
+            let n = bytes.len().min(4 - self.buffered);
+            self.tail[self.buffered..self.buffered + n].copy_from_slice(&bytes[..n]);
+            self.buffered += n;
+            bytes = &bytes[n..];
+            if self.buffered < 4 {
+                return;
+            }
+            self.word(u32::from_le_bytes(self.tail));
+            self.buffered = 0;
+        }
+        let mut words = bytes.chunks_exact(4);
+        for word in &mut words {
+            self.word(u32::from_le_bytes(word.try_into().unwrap()));
+        }
+        let rest = words.remainder();
+        self.tail[..rest.len()].copy_from_slice(rest);
+        self.buffered = rest.len();
+    }
+
+    fn finish(&self) -> u64 {
+        let mut last = [0; 4];
+        last[..self.buffered].copy_from_slice(&self.tail[..self.buffered]);
+        let b = u32::from_le_bytes(last) | (self.total as u32) << 24;
+        let mut v = self.v;
+        v[3] ^= b;
+        Self::rounds(&mut v, C);
+        v[0] ^= b;
+        v[2] ^= 0xFF;
+        Self::rounds(&mut v, D);
+        (v[1] ^ v[3]).into()
+    }
+}
+
+impl<const C: usize, const D: usize> KeyedHasher for HalfSipHasher<C, D> {
+    type Key = [u8; 8];
+
+    fn with_key(key: [u8; 8]) -> Self {
+        Self::new_with_key(key)
+    }
+}
+
+/// Builds keyed hashers that all share one random key, like `std`'s
+/// `RandomState`. Each `RandomState` draws a new key from the operating
+/// system's secure generator through `crypto::entropy::fill`, so
+/// hash-flooding keys cannot be computed ahead of time.
+/// ## Panics
+/// `new` and `default` panic if the generator cannot be read, including on
+/// platforms other than Unix and Windows, which have none to read.
+/// ## Examples
+/// ```
+/// # use stdlib_rs::crypto::siphash::{RandomState, SipHasher24};
+/// # use std::collections::HashMap;
+/// let mut map = HashMap::with_hasher(RandomState::<SipHasher24>::new());
+/// map.insert("key", 1);
+/// assert_eq!(map["key"], 1);
+/// ```
+#[cfg(feature = "std")]
+pub struct RandomState<H: KeyedHasher = SipHasher13> {
+    key: H::Key,
+}
+
+#[cfg(feature = "std")]
+impl<H: KeyedHasher> RandomState<H> {
+    /// Creates a `RandomState` with a random key.
+    pub fn new() -> Self {
+        let mut key = H::Key::default();
+        super::entropy::fill(key.as_mut()).expect("failed to read OS entropy");
+        RandomState { key }
+    }
+
+    /// Creates a `RandomState` with the given key, for reproducible hashes.
+    pub fn with_key(key: H::Key) -> Self {
+        RandomState { key }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<H: KeyedHasher> Default for RandomState<H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<H: KeyedHasher> Clone for RandomState<H> {
+    fn clone(&self) -> Self {
+        RandomState { key: self.key }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<H: KeyedHasher> BuildHasher for RandomState<H> {
+    type Hasher = H;
+
+    fn build_hasher(&self) -> H {
+        H::with_key(self.key)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::vec::Vec;
+
+    fn key_16() -> [u8; 16] {
+        let mut key = [0; 16];
+        for (i, byte) in key.iter_mut().enumerate() {
+            *byte = i as u8;
+        }
+        key
+    }
+
+    fn hash<H: Hasher>(mut hasher: H, bytes: &[u8]) -> u64 {
+        hasher.write(bytes);
+        hasher.finish()
+    }
+
+    #[test]
+    fn siphash_24_vectors() {
+        // From the reference implementation: key 00..0f, messages 00..len.
+        let message: Vec<u8> = (0..64).collect();
+        let cases = [
+            (0, 0x726F_DB47_DD0E_0E31),
+            (1, 0x74F8_39C5_93DC_67FD),
+            (15, 0xA129_CA61_49BE_45E5),
+            (63, 0x958A_324C_EB06_4572),
+        ];
+        for (len, expected) in cases {
+            let hasher = SipHasher24::new_with_key(key_16());
+            assert_eq!(hash(hasher, &message[..len]), expected);
+        }
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn matches_std() {
+        let message: Vec<u8> = (0..300u32).map(|i| (i * 7) as u8).collect();
+        for len in 0..message.len() {
+            let bytes = &message[..len];
+            let std_24 = core::hash::SipHasher::new_with_keys(1, 2);
+            let ours_24 = SipHasher24::new_with_keys(1, 2);
+            assert_eq!(hash(ours_24, bytes), hash(std_24, bytes));
+            let std_13 = std::collections::hash_map::DefaultHasher::new();
+            let ours_13 = SipHasher13::new_with_keys(0, 0);
+            assert_eq!(hash(ours_13, bytes), hash(std_13, bytes));
+        }
+    }
+
+    #[test]
+    fn streaming_matches_one_shot() {
+        let message: Vec<u8> = (0..100).collect();
+        for len in 0..message.len() {
+            let bytes = &message[..len];
+            let mut sip = SipHasher24::new_with_key(key_16());
+            let mut half = HalfSipHasher24::new_with_key([0, 1, 2, 3, 4, 5, 6, 7]);
+            for chunk in bytes.chunks(3) {
+                sip.write(chunk);
+                half.write(chunk);
+            }
+            assert_eq!(
+                sip.finish(),
+                hash(SipHasher24::new_with_key(key_16()), bytes)
+            );
+            let one_shot = HalfSipHasher24::new_with_key([0, 1, 2, 3, 4, 5, 6, 7]);
+            assert_eq!(half.finish(), hash(one_shot, bytes));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn random_states_differ() {
+        let a = RandomState::<SipHasher13>::new();
+        let b = RandomState::<SipHasher13>::new();
+        assert_eq!(
+            hash(a.build_hasher(), b"key"),
+            hash(a.build_hasher(), b"key")
+        );
+        assert_ne!(
+            hash(a.build_hasher(), b"key"),
+            hash(b.build_hasher(), b"key")
+        );
+        let fixed = RandomState::<HalfSipHasher13>::with_key([7; 8]);
+        assert_eq!(
+            hash(fixed.build_hasher(), b"key"),
+            hash(HalfSipHasher13::new_with_key([7; 8]), b"key")
+        );
+    }
+}
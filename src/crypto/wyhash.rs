@@ -0,0 +1,264 @@
+#![deny(missing_docs)]
+
+use core::convert::TryInto;
+use core::hash::Hasher;
+
+/// The default secret of wyhash final4.
+const SECRET: [u64; 4] = [
+    0xA076_1D64_78BD_642F,
+    0xE703_7ED1_A0B4_28DB,
+    0x8EBC_6AF0_9C88_C6E3,
+    0x5899_65CC_7537_4CC3,
+];
+
+fn read32(bytes: &[u8], offset: usize) -> u64 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap()).into()
+}
+
+fn read64(bytes: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap())
+}
+
+/// Multiplies to 128 bits and returns the halves.
+fn mum(a: u64, b: u64) -> (u64, u64) {
+    let product = a as u128 * b as u128;
+    (product as u64, (product >> 64) as u64)
+}
+
+/// Multiplies to 128 bits and xors the halves together.
+fn mix(a: u64, b: u64) -> u64 {
+    let (low, high) = mum(a, b);
+    low ^ high
+}
+
+/// Returns the wyhash (final4) of `bytes`.
+/// ## Examples
+/// ```
+/// # use stdlib_rs::crypto::wyhash::wyhash;
+/// assert_eq!(wyhash(b"abc", 2), 0x32DD_92E4_B291_5153);
+/// ```
+pub fn wyhash(bytes: &[u8], seed: u64) -> u64 {
+    let mut hasher = WyHasher::seed(seed);
+    hasher.write(bytes);
+    hasher.finish()
+}
+
+/// A hasher that returns wyhash hashes.
+///
+/// wyhash reads its input from both ends, so the hasher keeps the last 64
+/// bytes written and finishes the hash from them.
+/// ## Examples
+/// ```
+/// # use stdlib_rs::crypto::wyhash::{wyhash, WyHasher};
+/// # use std::hash::Hasher;
+/// let mut hasher = WyHasher::seed(5);
+/// hasher.write(b"message ");
+/// hasher.write(b"digest");
+/// assert_eq!(hasher.finish(), wyhash(b"message digest", 5));
+/// ```
+#[derive(Debug, Clone)]
+pub struct WyHasher {
+    seed: u64,
+    see1: u64,
+    see2: u64,
+    // The bytes after the last 48 byte block are at `buffer[16..]`, and the
+    // last 16 bytes of that block are at `buffer[..16]`.
+    buffer: [u8; 64],
+    buffered: usize,
+    total: u64,
+}
+
+impl WyHasher {
+    /// Creates a hasher with the given seed. `seed(0)` is the same as
+    /// `default()`.
+    pub fn seed(seed: u64) -> WyHasher {
+        let seed = seed ^ mix(seed ^ SECRET[0], SECRET[1]);
+        WyHasher {
+            seed,
+            see1: seed,
+            see2: seed,
+            buffer: [0; 64],
+            buffered: 0,
+            total: 0,
+        }
+    }
+
+    fn block(&mut self) {
+        let p = &self.buffer[16..];
+        self.seed = mix(read64(p, 0) ^ SECRET[1], read64(p, 8) ^ self.seed);
+        self.see1 = mix(read64(p, 16) ^ SECRET[2], read64(p, 24) ^ self.see1);
+        self.see2 = mix(read64(p, 32) ^ SECRET[3], read64(p, 40) ^ self.see2);
+        self.buffer.copy_within(48.., 0);
+        self.buffered = 0;
+    }
+}
+
+impl Default for WyHasher {
+    fn default() -> WyHasher {
+        WyHasher::seed(0)
+    }
+}
+
+impl Hasher for WyHasher {
+    fn write(&mut self, mut bytes: &[u8]) {
+        self.total += bytes.len() as u64;
+        while !bytes.is_empty() {
+            // Blocks are hashed only while more than 48 bytes remain, so a
+            // full block waits until more input shows it is not the last.
+            if self.buffered == 48 {
+                self.block();
+            }
+            let n = bytes.len().min(48 - self.buffered);
+            self.buffer[16 + self.buffered..16 + self.buffered + n].copy_from_slice(&bytes[..n]);
+            self.buffered += n;
+            bytes = &bytes[n..];
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        let len = self.total;
+        let mut seed = self.seed;
+        let (a, b) = if len <= 16 {
+            let p = &self.buffer[16..16 + self.buffered];
+            if len >= 4 {
+                let quarter = (len as usize >> 3) << 2;
+                (
+                    read32(p, 0) << 32 | read32(p, quarter),
+                    read32(p, p.len() - 4) << 32 | read32(p, p.len() - 4 - quarter),
+                )
+            } else if len > 0 {
+                let k = p.len();
+                (
+                    (p[0] as u64) << 16 | (p[k >> 1] as u64) << 8 | p[k - 1] as u64,
+                    0,
+                )
+            } else {
+                (0, 0)
+            }
+        } else {
+            if len > 48 {
+                seed ^= self.see1 ^ self.see2;
+            }
+            let p = &self.buffer[..16 + self.buffered];
+            let mut start = 16;
+            while p.len() - start > 16 {
+                seed = mix(read64(p, start) ^ SECRET[1], read64(p, start + 8) ^ seed);
+                start += 16;
+            }
+            (read64(p, p.len() - 16), read64(p, p.len() - 8))
+        };
+        let (a, b) = mum(a ^ SECRET[1], b ^ seed);
+        mix(a ^ SECRET[0] ^ len, b ^ SECRET[1])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::vec::Vec;
+
+    /// The one-shot final4 algorithm, read straight from the input as the
+    /// reference implementation does.
+    fn reference(p: &[u8], seed: u64) -> u64 {
+        let len = p.len();
+        let mut seed = seed ^ mix(seed ^ SECRET[0], SECRET[1]);
+        let (a, b) = if len <= 16 {
+            if len >= 4 {
+                let quarter = (len >> 3) << 2;
+                (
+                    read32(p, 0) << 32 | read32(p, quarter),
+                    read32(p, len - 4) << 32 | read32(p, len - 4 - quarter),
+                )
+            } else if len > 0 {
+                (
+                    (p[0] as u64) << 16 | (p[len >> 1] as u64) << 8 | p[len - 1] as u64,
+                    0,
+                )
+            } else {
+                (0, 0)
+            }
+        } else {
+            let mut i = len;
+            let mut start = 0;
+            if i > 48 {
+                let mut see1 = seed;
+                let mut see2 = seed;
+                while i > 48 {
+                    seed = mix(read64(p, start) ^ SECRET[1], read64(p, start + 8) ^ seed);
+                    see1 = mix(
+                        read64(p, start + 16) ^ SECRET[2],
+                        read64(p, start + 24) ^ see1,
+                    );
+                    see2 = mix(
+                        read64(p, start + 32) ^ SECRET[3],
+                        read64(p, start + 40) ^ see2,
+                    );
+                    start += 48;
+                    i -= 48;
+                }
+                seed ^= see1 ^ see2;
+            }
+            while i > 16 {
+                seed = mix(read64(p, start) ^ SECRET[1], read64(p, start + 8) ^ seed);
+                start += 16;
+                i -= 16;
+            }
+            (read64(p, start + i - 16), read64(p, start + i - 8))
+        };
+        let (a, b) = mum(a ^ SECRET[1], b ^ seed);
+        mix(a ^ SECRET[0] ^ len as u64, b ^ SECRET[1])
+    }
+
+    #[test]
+    fn reference_vectors() {
+        let cases: [(&[u8], u64, u64); 7] = [
+            (b"", 0, 0x0409_638E_E2BD_E459),
+            (b"a", 1, 0xA841_2D09_1B5F_E0A9),
+            (b"abc", 2, 0x32DD_92E4_B291_5153),
+            (b"message digest", 3, 0x8619_1240_89A3_A16B),
+            (b"abcdefghijklmnopqrstuvwxyz", 4, 0x7A43_AFB6_1D7F_5F40),
+            (
+                b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789",
+                5,
+                0xFF42_329B_90E5_0D58,
+            ),
+            (
+                b"12345678901234567890123456789012345678901234567890123456789012345678901234567890",
+                6,
+                0xC39C_AB13_B115_AAD3,
+            ),
+        ];
+        for (bytes, seed, expected) in cases {
+            assert_eq!(wyhash(bytes, seed), expected);
+            assert_eq!(reference(bytes, seed), expected);
+        }
+    }
+
+    #[test]
+    fn whole_blocks() {
+        // Inputs that end on a 48 byte block boundary finish through the
+        // tail, not the block round.
+        let cases = [
+            (48, 0xC152_FD91_08C0_9C0B),
+            (96, 0xA01F_0ED9_A983_65D2),
+            (144, 0x6494_55E1_4CDC_8068),
+        ];
+        for (len, expected) in cases {
+            let bytes: Vec<u8> = (0..len).map(|i| (i % 251) as u8).collect();
+            assert_eq!(wyhash(&bytes, len as u64), expected, "length {}", len);
+        }
+    }
+
+    #[test]
+    fn streaming_matches_reference() {
+        let data: Vec<u8> = (0..200u32).map(|i| (i * 53 % 256) as u8).collect();
+        for len in 0..data.len() {
+            let bytes = &data[..len];
+            let mut hasher = WyHasher::seed(len as u64);
+            for byte in bytes {
+                hasher.write(&[*byte]);
+            }
+            assert_eq!(hasher.finish(), reference(bytes, len as u64));
+        }
+    }
+}
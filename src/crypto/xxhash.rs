@@ -0,0 +1,742 @@
+#![deny(missing_docs)]
+
+use core::convert::TryInto;
+use core::hash::Hasher;
+
+const PRIME32_1: u64 = 0x9E37_79B1;
+const PRIME32_2: u64 = 0x85EB_CA77;
+const PRIME32_3: u64 = 0xC2B2_AE3D;
+const PRIME64_1: u64 = 0x9E37_79B1_85EB_CA87;
+const PRIME64_2: u64 = 0xC2B2_AE3D_27D4_EB4F;
+const PRIME64_3: u64 = 0x1656_67B1_9E37_79F9;
+const PRIME64_4: u64 = 0x85EB_CA77_C2B2_AE63;
+const PRIME64_5: u64 = 0x27D4_EB2F_1656_67C5;
+
+fn read32(bytes: &[u8], offset: usize) -> u64 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap()).into()
+}
+
+fn read64(bytes: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap())
+}
+
+/// Returns the XXH64 hash of `bytes`.
+/// ## Examples
+/// ```
+/// # use stdlib_rs::crypto::xxhash::xxh64;
+/// assert_eq!(xxh64(b"", 0), 0xEF46_DB37_51D8_E999);
+/// ```
+pub fn xxh64(bytes: &[u8], seed: u64) -> u64 {
+    let mut hasher = Xxh64Hasher::seed(seed);
+    hasher.write(bytes);
+    hasher.finish()
+}
+
+/// A hasher that returns XXH64 hashes.
+/// ## Examples
+/// ```
+/// # use stdlib_rs::crypto::xxhash::{xxh64, Xxh64Hasher};
+/// # use std::hash::Hasher;
+/// let mut hasher = Xxh64Hasher::seed(7);
+/// hasher.write(b"Nobody inspects");
+/// hasher.write(b" the spammish repetition");
+/// assert_eq!(hasher.finish(), xxh64(b"Nobody inspects the spammish repetition", 7));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Xxh64Hasher {
+    seed: u64,
+    lanes: [u64; 4],
+    buffer: [u8; 32],
+    buffered: usize,
+    total: u64,
+}
+
+impl Xxh64Hasher {
+    /// Creates a hasher with the given seed. `seed(0)` is the same as
+    /// `default()`.
+    pub fn seed(seed: u64) -> Xxh64Hasher {
+        Xxh64Hasher {
+            seed,
+            lanes: [
+                seed.wrapping_add(PRIME64_1).wrapping_add(PRIME64_2),
+                seed.wrapping_add(PRIME64_2),
+                seed,
+                seed.wrapping_sub(PRIME64_1),
+            ],
+            buffer: [0; 32],
+            buffered: 0,
+            total: 0,
+        }
+    }
+
+    fn stripe(lanes: &mut [u64; 4], stripe: &[u8]) {
+        for (i, lane) in lanes.iter_mut().enumerate() {
+            *lane = round(*lane, read64(stripe, 8 * i));
+        }
+    }
+}
+
+fn round(acc: u64, input: u64) -> u64 {
+    acc.wrapping_add(input.wrapping_mul(PRIME64_2))
+        .rotate_left(31)
+        .wrapping_mul(PRIME64_1)
+}
+
+fn merge_round(acc: u64, lane: u64) -> u64 {
+    (acc ^ round(0, lane))
+        .wrapping_mul(PRIME64_1)
+        .wrapping_add(PRIME64_4)
+}
+
+fn xxh64_avalanche(mut h: u64) -> u64 {
+    h ^= h >> 33;
+    h = h.wrapping_mul(PRIME64_2);
+    h ^= h >> 29;
+    h = h.wrapping_mul(PRIME64_3);
+    h ^ h >> 32
+}
+
+impl Default for Xxh64Hasher {
+    fn default() -> Xxh64Hasher {
+        Xxh64Hasher::seed(0)
+    }
+}
+
+impl Hasher for Xxh64Hasher {
+    fn write(&mut self, mut bytes: &[u8]) {
+        self.total += bytes.len() as u64;
+        if self.buffered > 0 {
+            let n = bytes.len().min(32 - self.buffered);
+            self.buffer[self.buffered..self.buffered + n].copy_from_slice(&bytes[..n]);
+            self.buffered += n;
+            bytes = &bytes[n..];
+            if self.buffered < 32 {
+                return;
+            }
+            Xxh64Hasher::stripe(&mut self.lanes, &self.buffer);
+            self.buffered = 0;
+        }
+        let mut stripes = bytes.chunks_exact(32);
+        for stripe in &mut stripes {
+            Xxh64Hasher::stripe(&mut self.lanes, stripe);
+        }
+        let rest = stripes.remainder();
+        self.buffer[..rest.len()].copy_from_slice(rest);
+        self.buffered = rest.len();
+    }
+
+    fn finish(&self) -> u64 {
+        let [v1, v2, v3, v4] = self.lanes;
+        let mut h = if self.total >= 32 {
+            let h = v1
+                .rotate_left(1)
+                .wrapping_add(v2.rotate_left(7))
+                .wrapping_add(v3.rotate_left(12))
+                .wrapping_add(v4.rotate_left(18));
+            self.lanes.iter().fold(h, |h, &lane| merge_round(h, lane))
+        } else {
+            self.seed.wrapping_add(PRIME64_5)
+        };
+        h = h.wrapping_add(self.total);
+        let mut rest = &self.buffer[..self.buffered];
+        while rest.len() >= 8 {
+            h ^= round(0, read64(rest, 0));
+            h = h
+                .rotate_left(27)
+                .wrapping_mul(PRIME64_1)
+                .wrapping_add(PRIME64_4);
+            rest = &rest[8..];
+        }
+        if rest.len() >= 4 {
+            h ^= read32(rest, 0).wrapping_mul(PRIME64_1);
+            h = h
+                .rotate_left(23)
+                .wrapping_mul(PRIME64_2)
+                .wrapping_add(PRIME64_3);
+            rest = &rest[4..];
+        }
+        for &byte in rest {
+            h ^= (byte as u64).wrapping_mul(PRIME64_5);
+            h = h.rotate_left(11).wrapping_mul(PRIME64_1);
+        }
+        xxh64_avalanche(h)
+    }
+}
+
+/// The default secret of XXH3, which seeded hashes are derived from.
+const SECRET: [u8; SECRET_LEN] = [
+    0xb8, 0xfe, 0x6c, 0x39, 0x23, 0xa4, 0x4b, 0xbe, 0x7c, 0x01, 0x81, 0x2c, 0xf7, 0x21, 0xad, 0x1c,
+    0xde, 0xd4, 0x6d, 0xe9, 0x83, 0x90, 0x97, 0xdb, 0x72, 0x40, 0xa4, 0xa4, 0xb7, 0xb3, 0x67, 0x1f,
+    0xcb, 0x79, 0xe6, 0x4e, 0xcc, 0xc0, 0xe5, 0x78, 0x82, 0x5a, 0xd0, 0x7d, 0xcc, 0xff, 0x72, 0x21,
+    0xb8, 0x08, 0x46, 0x74, 0xf7, 0x43, 0x24, 0x8e, 0xe0, 0x35, 0x90, 0xe6, 0x81, 0x3a, 0x26, 0x4c,
+    0x3c, 0x28, 0x52, 0xbb, 0x91, 0xc3, 0x00, 0xcb, 0x88, 0xd0, 0x65, 0x8b, 0x1b, 0x53, 0x2e, 0xa3,
+    0x71, 0x64, 0x48, 0x97, 0xa2, 0x0d, 0xf9, 0x4e, 0x38, 0x19, 0xef, 0x46, 0xa9, 0xde, 0xac, 0xd8,
+    0xa8, 0xfa, 0x76, 0x3f, 0xe3, 0x9c, 0x34, 0x3f, 0xf9, 0xdc, 0xbb, 0xc7, 0xc7, 0x0b, 0x4f, 0x1d,
+    0x8a, 0x51, 0xe0, 0x4b, 0xcd, 0xb4, 0x59, 0x31, 0xc8, 0x9f, 0x7e, 0xc9, 0xd9, 0x78, 0x73, 0x64,
+    0xea, 0xc5, 0xac, 0x83, 0x34, 0xd3, 0xeb, 0xc3, 0xc5, 0x81, 0xa0, 0xff, 0xfa, 0x13, 0x63, 0xeb,
+    0x17, 0x0d, 0xdd, 0x51, 0xb7, 0xf0, 0xda, 0x49, 0xd3, 0x16, 0x55, 0x26, 0x29, 0xd4, 0x68, 0x9e,
+    0x2b, 0x16, 0xbe, 0x58, 0x7d, 0x47, 0xa1, 0xfc, 0x8f, 0xf8, 0xb8, 0xd1, 0x7a, 0xd0, 0x31, 0xce,
+    0x45, 0xcb, 0x3a, 0x8f, 0x95, 0x16, 0x04, 0x28, 0xaf, 0xd7, 0xfb, 0xca, 0xbb, 0x4b, 0x40, 0x7e,
+];
+const SECRET_LEN: usize = 192;
+const STRIPE_LEN: usize = 64;
+/// Stripes per block; the accumulators are scrambled after each block.
+const STRIPES_PER_BLOCK: usize = (SECRET_LEN - STRIPE_LEN) / 8;
+/// Inputs up to this length are hashed without the accumulators.
+const MIDSIZE_MAX: usize = 240;
+/// The stripes an `Xxh3Hasher` collects before accumulating them.
+const BUFFER_LEN: usize = 4 * STRIPE_LEN;
+
+/// Returns the 64-bit XXH3 hash of `bytes`.
+/// ## Examples
+/// ```
+/// # use stdlib_rs::crypto::xxhash::xxh3_64;
+/// assert_eq!(xxh3_64(b"", 0), 0x2D06_8005_38D3_94C2);
+/// ```
+pub fn xxh3_64(bytes: &[u8], seed: u64) -> u64 {
+    if bytes.len() <= MIDSIZE_MAX {
+        return short_64(bytes, seed);
+    }
+    let secret = derive_secret(seed);
+    long(bytes, &secret).merge_64(bytes.len() as u64, &secret)
+}
+
+/// Returns the 128-bit XXH3 hash of `bytes`, with the high half of the
+/// reference's `XXH128_hash_t` in the high bits.
+/// ## Examples
+/// ```
+/// # use stdlib_rs::crypto::xxhash::xxh3_128;
+/// assert_eq!(xxh3_128(b"", 0), 0x99AA_06D3_0147_98D8_6001_C324_468D_497F);
+/// ```
+pub fn xxh3_128(bytes: &[u8], seed: u64) -> u128 {
+    if bytes.len() <= MIDSIZE_MAX {
+        return short_128(bytes, seed);
+    }
+    let secret = derive_secret(seed);
+    long(bytes, &secret).merge_128(bytes.len() as u64, &secret)
+}
+
+/// A hasher that returns 64-bit XXH3 hashes, and 128-bit ones through
+/// `finish_128`.
+/// ## Examples
+/// ```
+/// # use stdlib_rs::crypto::xxhash::{xxh3_128, xxh3_64, Xxh3Hasher};
+/// # use std::hash::Hasher;
+/// let data = vec![42u8; 1000];
+/// let mut hasher = Xxh3Hasher::seed(1);
+/// for chunk in data.chunks(300) {
+///     hasher.write(chunk);
+/// }
+/// assert_eq!(hasher.finish(), xxh3_64(&data, 1));
+/// assert_eq!(hasher.finish_128(), xxh3_128(&data, 1));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Xxh3Hasher {
+    seed: u64,
+    secret: [u8; SECRET_LEN],
+    acc: Accumulator,
+    // Holds the bytes not accumulated yet. Once it has been accumulated, the
+    // bytes past `buffered` are the end of the previous fill.
+    buffer: [u8; BUFFER_LEN],
+    buffered: usize,
+    total: u64,
+}
+
+impl Xxh3Hasher {
+    /// Creates a hasher with the given seed. `seed(0)` is the same as
+    /// `default()`.
+    pub fn seed(seed: u64) -> Xxh3Hasher {
+        Xxh3Hasher {
+            seed,
+            secret: derive_secret(seed),
+            acc: Accumulator::new(),
+            buffer: [0; BUFFER_LEN],
+            buffered: 0,
+            total: 0,
+        }
+    }
+
+    /// Returns the 128-bit XXH3 hash of the bytes written so far.
+    pub fn finish_128(&self) -> u128 {
+        if self.total <= MIDSIZE_MAX as u64 {
+            return short_128(&self.buffer[..self.buffered], self.seed);
+        }
+        self.digest().merge_128(self.total, &self.secret)
+    }
+
+    /// Returns the accumulators with the buffered bytes and the last stripe
+    /// added.
+    fn digest(&self) -> Accumulator {
+        let mut acc = self.acc.clone();
+        let buffered = &self.buffer[..self.buffered];
+        if self.buffered >= STRIPE_LEN {
+            let stripes = (self.buffered - 1) / STRIPE_LEN;
+            acc.consume(&buffered[..stripes * STRIPE_LEN], &self.secret);
+            acc.last_stripe(&buffered[self.buffered - STRIPE_LEN..], &self.secret);
+        } else {
+            // The last stripe starts in the previous fill of the buffer.
+            let mut last = [0; STRIPE_LEN];
+            let carried = STRIPE_LEN - self.buffered;
+            last[..carried].copy_from_slice(&self.buffer[BUFFER_LEN - carried..]);
+            last[carried..].copy_from_slice(buffered);
+            acc.last_stripe(&last, &self.secret);
+        }
+        acc
+    }
+}
+
+impl Default for Xxh3Hasher {
+    fn default() -> Xxh3Hasher {
+        Xxh3Hasher::seed(0)
+    }
+}
+
+impl Hasher for Xxh3Hasher {
+    fn write(&mut self, mut bytes: &[u8]) {
+        self.total += bytes.len() as u64;
+        while !bytes.is_empty() {
+            // A full buffer is only accumulated once more bytes arrive, since
+            // the last stripe is hashed differently.
+            if self.buffered == BUFFER_LEN {
+                self.acc.consume(&self.buffer, &self.secret);
+                self.buffered = 0;
+            }
+            let n = bytes.len().min(BUFFER_LEN - self.buffered);
+            self.buffer[self.buffered..self.buffered + n].copy_from_slice(&bytes[..n]);
+            self.buffered += n;
+            bytes = &bytes[n..];
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        if self.total <= MIDSIZE_MAX as u64 {
+            return short_64(&self.buffer[..self.buffered], self.seed);
+        }
+        self.digest().merge_64(self.total, &self.secret)
+    }
+}
+
+/// Returns the secret for inputs longer than `MIDSIZE_MAX`.
+fn derive_secret(seed: u64) -> [u8; SECRET_LEN] {
+    let mut secret = SECRET;
+    for pair in secret.chunks_exact_mut(16) {
+        let lo = read64(pair, 0).wrapping_add(seed);
+        let hi = read64(pair, 8).wrapping_sub(seed);
+        pair[..8].copy_from_slice(&lo.to_le_bytes());
+        pair[8..].copy_from_slice(&hi.to_le_bytes());
+    }
+    secret
+}
+
+/// The eight lanes that inputs longer than `MIDSIZE_MAX` are accumulated in.
+#[derive(Debug, Clone)]
+struct Accumulator {
+    lanes: [u64; 8],
+    // The stripes accumulated since the last scramble.
+    stripes: usize,
+}
+
+impl Accumulator {
+    fn new() -> Accumulator {
+        Accumulator {
+            lanes: [
+                PRIME32_3, PRIME64_1, PRIME64_2, PRIME64_3, PRIME64_4, PRIME32_2, PRIME64_5,
+                PRIME32_1,
+            ],
+            stripes: 0,
+        }
+    }
+
+    /// Accumulates whole stripes, scrambling after every block.
+    fn consume(&mut self, bytes: &[u8], secret: &[u8]) {
+        for stripe in bytes.chunks_exact(STRIPE_LEN) {
+            self.accumulate(stripe, &secret[8 * self.stripes..]);
+            self.stripes += 1;
+            if self.stripes == STRIPES_PER_BLOCK {
+                self.scramble(&secret[SECRET_LEN - STRIPE_LEN..]);
+                self.stripes = 0;
+            }
+        }
+    }
+
+    /// Accumulates the last 64 bytes of the input.
+    fn last_stripe(&mut self, stripe: &[u8], secret: &[u8]) {
+        self.accumulate(stripe, &secret[SECRET_LEN - STRIPE_LEN - 7..]);
+    }
+
+    fn accumulate(&mut self, stripe: &[u8], secret: &[u8]) {
+        for i in 0..8 {
+            let value = read64(stripe, 8 * i);
+            let key = value ^ read64(secret, 8 * i);
+            self.lanes[i ^ 1] = self.lanes[i ^ 1].wrapping_add(value);
+            self.lanes[i] = self.lanes[i].wrapping_add((key & 0xFFFF_FFFF) * (key >> 32));
+        }
+    }
+
+    fn scramble(&mut self, secret: &[u8]) {
+        for (i, lane) in self.lanes.iter_mut().enumerate() {
+            *lane ^= *lane >> 47;
+            *lane ^= read64(secret, 8 * i);
+            *lane = lane.wrapping_mul(PRIME32_1);
+        }
+    }
+
+    fn merge(&self, secret: &[u8], start: u64) -> u64 {
+        let mut result = start;
+        for i in 0..4 {
+            result = result.wrapping_add(fold(
+                self.lanes[2 * i] ^ read64(secret, 16 * i),
+                self.lanes[2 * i + 1] ^ read64(secret, 16 * i + 8),
+            ));
+        }
+        avalanche(result)
+    }
+
+    fn merge_64(&self, len: u64, secret: &[u8]) -> u64 {
+        self.merge(&secret[11..], len.wrapping_mul(PRIME64_1))
+    }
+
+    fn merge_128(&self, len: u64, secret: &[u8]) -> u128 {
+        let low = self.merge_64(len, secret);
+        let high = self.merge(
+            &secret[SECRET_LEN - STRIPE_LEN - 11..],
+            !len.wrapping_mul(PRIME64_2),
+        );
+        join(low, high)
+    }
+}
+
+/// Accumulates an input longer than `MIDSIZE_MAX`.
+fn long(bytes: &[u8], secret: &[u8]) -> Accumulator {
+    let mut acc = Accumulator::new();
+    let stripes = (bytes.len() - 1) / STRIPE_LEN;
+    acc.consume(&bytes[..stripes * STRIPE_LEN], secret);
+    acc.last_stripe(&bytes[bytes.len() - STRIPE_LEN..], secret);
+    acc
+}
+
+fn multiply(a: u64, b: u64) -> (u64, u64) {
+    let product = a as u128 * b as u128;
+    (product as u64, (product >> 64) as u64)
+}
+
+/// Multiplies to 128 bits and xors the halves together.
+fn fold(a: u64, b: u64) -> u64 {
+    let (low, high) = multiply(a, b);
+    low ^ high
+}
+
+fn join(low: u64, high: u64) -> u128 {
+    (high as u128) << 64 | low as u128
+}
+
+fn avalanche(mut h: u64) -> u64 {
+    h ^= h >> 37;
+    h = h.wrapping_mul(0x1656_6791_9E37_79F9);
+    h ^ h >> 32
+}
+
+fn rrmxmx(mut h: u64, len: u64) -> u64 {
+    h ^= h.rotate_left(49) ^ h.rotate_left(24);
+    h = h.wrapping_mul(0x9FB2_1C65_1E98_DF25);
+    h ^= (h >> 35).wrapping_add(len);
+    h = h.wrapping_mul(0x9FB2_1C65_1E98_DF25);
+    h ^ h >> 28
+}
+
+fn mix_16(bytes: &[u8], secret: &[u8], seed: u64) -> u64 {
+    fold(
+        read64(bytes, 0) ^ read64(secret, 0).wrapping_add(seed),
+        read64(bytes, 8) ^ read64(secret, 8).wrapping_sub(seed),
+    )
+}
+
+/// Hashes an input of at most `MIDSIZE_MAX` bytes to 64 bits.
+fn short_64(p: &[u8], seed: u64) -> u64 {
+    let s = &SECRET;
+    let len = p.len();
+    match len {
+        0 => xxh64_avalanche(seed ^ read64(s, 56) ^ read64(s, 64)),
+        1..=3 => {
+            let combined = (p[0] as u64) << 16
+                | (p[len >> 1] as u64) << 24
+                | p[len - 1] as u64
+                | (len as u64) << 8;
+            let bitflip = (read32(s, 0) ^ read32(s, 4)).wrapping_add(seed);
+            xxh64_avalanche(combined ^ bitflip)
+        }
+        4..=8 => {
+            let seed = seed ^ ((seed as u32).swap_bytes() as u64) << 32;
+            let bitflip = (read64(s, 8) ^ read64(s, 16)).wrapping_sub(seed);
+            let input = read32(p, len - 4).wrapping_add(read32(p, 0) << 32);
+            rrmxmx(input ^ bitflip, len as u64)
+        }
+        9..=16 => {
+            let bitflip_1 = (read64(s, 24) ^ read64(s, 32)).wrapping_add(seed);
+            let bitflip_2 = (read64(s, 40) ^ read64(s, 48)).wrapping_sub(seed);
+            let low = read64(p, 0) ^ bitflip_1;
+            let high = read64(p, len - 8) ^ bitflip_2;
+            let acc = (len as u64)
+                .wrapping_add(low.swap_bytes())
+                .wrapping_add(high)
+                .wrapping_add(fold(low, high));
+            avalanche(acc)
+        }
+        17..=128 => {
+            let mut acc = (len as u64).wrapping_mul(PRIME64_1);
+            // Pairs of 16 bytes from both ends, working inwards.
+            for i in 0..(len - 1) / 32 + 1 {
+                acc = acc
+                    .wrapping_add(mix_16(&p[16 * i..], &s[32 * i..], seed))
+                    .wrapping_add(mix_16(&p[len - 16 * (i + 1)..], &s[32 * i + 16..], seed));
+            }
+            avalanche(acc)
+        }
+        _ => {
+            let mut acc = (len as u64).wrapping_mul(PRIME64_1);
+            for i in 0..8 {
+                acc = acc.wrapping_add(mix_16(&p[16 * i..], &s[16 * i..], seed));
+            }
+            acc = avalanche(acc);
+            for i in 8..len / 16 {
+                acc = acc.wrapping_add(mix_16(&p[16 * i..], &s[16 * (i - 8) + 3..], seed));
+            }
+            acc = acc.wrapping_add(mix_16(&p[len - 16..], &s[136 - 17..], seed));
+            avalanche(acc)
+        }
+    }
+}
+
+/// Mixes 16 bytes from each of `a` and `b` into the two halves of `acc`.
+fn mix_32(acc: (u64, u64), a: &[u8], b: &[u8], secret: &[u8], seed: u64) -> (u64, u64) {
+    let low = acc.0.wrapping_add(mix_16(a, secret, seed)) ^ read64(b, 0).wrapping_add(read64(b, 8));
+    let high = acc.1.wrapping_add(mix_16(b, &secret[16..], seed))
+        ^ read64(a, 0).wrapping_add(read64(a, 8));
+    (low, high)
+}
+
+/// Finishes the two halves of a 17 to 240 byte input.
+fn finish_128(acc: (u64, u64), len: usize, seed: u64) -> u128 {
+    let low = acc.0.wrapping_add(acc.1);
+    let high = acc
+        .0
+        .wrapping_mul(PRIME64_1)
+        .wrapping_add(acc.1.wrapping_mul(PRIME64_4))
+        .wrapping_add((len as u64).wrapping_sub(seed).wrapping_mul(PRIME64_2));
+    join(avalanche(low), avalanche(high).wrapping_neg())
+}
+
+/// Hashes an input of at most `MIDSIZE_MAX` bytes to 128 bits.
+fn short_128(p: &[u8], seed: u64) -> u128 {
+    let s = &SECRET;
+    let len = p.len();
+    match len {
+        0 => join(
+            xxh64_avalanche(seed ^ read64(s, 64) ^ read64(s, 72)),
+            xxh64_avalanche(seed ^ read64(s, 80) ^ read64(s, 88)),
+        ),
+        1..=3 => {
+            let combined_low = (p[0] as u32) << 16
+                | (p[len >> 1] as u32) << 24
+                | p[len - 1] as u32
+                | (len as u32) << 8;
+            let combined_high = combined_low.swap_bytes().rotate_left(13);
+            let bitflip_low = (read32(s, 0) ^ read32(s, 4)).wrapping_add(seed);
+            let bitflip_high = (read32(s, 8) ^ read32(s, 12)).wrapping_sub(seed);
+            join(
+                xxh64_avalanche(combined_low as u64 ^ bitflip_low),
+                xxh64_avalanche(combined_high as u64 ^ bitflip_high),
+            )
+        }
+        4..=8 => {
+            let seed = seed ^ ((seed as u32).swap_bytes() as u64) << 32;
+            let input = read32(p, 0).wrapping_add(read32(p, len - 4) << 32);
+            let bitflip = (read64(s, 16) ^ read64(s, 24)).wrapping_add(seed);
+            let (mut low, mut high) =
+                multiply(input ^ bitflip, PRIME64_1.wrapping_add((len as u64) << 2));
+            high = high.wrapping_add(low << 1);
+            low ^= high >> 3;
+            low ^= low >> 35;
+            low = low.wrapping_mul(0x9FB2_1C65_1E98_DF25);
+            low ^= low >> 28;
+            join(low, avalanche(high))
+        }
+        9..=16 => {
+            let bitflip_low = (read64(s, 32) ^ read64(s, 40)).wrapping_sub(seed);
+            let bitflip_high = (read64(s, 48) ^ read64(s, 56)).wrapping_add(seed);
+            let input_low = read64(p, 0);
+            let input_high = read64(p, len - 8);
+            let (mut low, mut high) = multiply(input_low ^ input_high ^ bitflip_low, PRIME64_1);
+            low = low.wrapping_add(((len - 1) as u64) << 54);
+            let input_high = input_high ^ bitflip_high;
+            high = high
+                .wrapping_add(input_high)
+                .wrapping_add((input_high & 0xFFFF_FFFF) * (PRIME32_2 - 1));
+            low ^= high.swap_bytes();
+            let (h_low, h_high) = multiply(low, PRIME64_2);
+            let h_high = h_high.wrapping_add(high.wrapping_mul(PRIME64_2));
+            join(avalanche(h_low), avalanche(h_high))
+        }
+        17..=128 => {
+            let mut acc = ((len as u64).wrapping_mul(PRIME64_1), 0);
+            for i in (0..(len - 1) / 32 + 1).rev() {
+                let a = &p[16 * i..];
+                let b = &p[len - 16 * (i + 1)..];
+                acc = mix_32(acc, a, b, &s[32 * i..], seed);
+            }
+            finish_128(acc, len, seed)
+        }
+        _ => {
+            let mut acc = ((len as u64).wrapping_mul(PRIME64_1), 0);
+            for i in 0..4 {
+                acc = mix_32(acc, &p[32 * i..], &p[32 * i + 16..], &s[32 * i..], seed);
+            }
+            acc = (avalanche(acc.0), avalanche(acc.1));
+            for i in 4..len / 32 {
+                let offset = 32 * (i - 4) + 3;
+                acc = mix_32(acc, &p[32 * i..], &p[32 * i + 16..], &s[offset..], seed);
+            }
+            acc = mix_32(
+                acc,
+                &p[len - 16..],
+                &p[len - 32..],
+                &s[136 - 17 - 16..],
+                seed.wrapping_neg(),
+            );
+            finish_128(acc, len, seed)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::vec::Vec;
+
+    /// The 64-bit prime of the reference implementation's sanity checks,
+    /// which is not `PRIME64_1`.
+    const PRIME64: u64 = 11_400_714_785_074_694_797;
+
+    /// The input of the reference implementation's sanity checks.
+    fn sanity_buffer(len: usize) -> Vec<u8> {
+        let mut generator = PRIME32_1;
+        (0..len)
+            .map(|_| {
+                let byte = (generator >> 56) as u8;
+                generator = generator.wrapping_mul(PRIME64);
+                byte
+            })
+            .collect()
+    }
+
+    #[test]
+    fn xxh64_vectors() {
+        assert_eq!(xxh64(b"a", 0), 0xD24E_C4F1_A98C_6E5B);
+        assert_eq!(xxh64(b"abc", 0), 0x44BC_2CF5_AD77_0999);
+        assert_eq!(
+            xxh64(b"Nobody inspects the spammish repetition", 0),
+            0xFBCE_A83C_8A37_8BF1
+        );
+        let buffer = sanity_buffer(222);
+        let cases = [
+            (0, 0, 0xEF46_DB37_51D8_E999),
+            (0, PRIME32_1, 0xAC75_FDA2_929B_17EF),
+            (1, 0, 0xE934_A84A_DB05_2768),
+            (1, PRIME32_1, 0x5014_6076_43A9_B4C3),
+            (4, 0, 0x9136_A0DC_A574_57EE),
+            (14, 0, 0x8282_DCC4_994E_35C8),
+            (14, PRIME32_1, 0xC3BD_6BF6_3DEB_6DF0),
+            (222, 0, 0xB641_AE8C_B691_C174),
+            (222, PRIME32_1, 0x20CB_8AB7_AE10_C14A),
+        ];
+        for (len, seed, expected) in cases {
+            assert_eq!(xxh64(&buffer[..len], seed), expected, "{} {}", len, seed);
+        }
+    }
+
+    #[test]
+    fn xxh3_vectors() {
+        let buffer = sanity_buffer(2367);
+        let cases = [
+            (0, 0, 0x2D06_8005_38D3_94C2),
+            (0, PRIME64, 0xA8A6_B918_B2F0_364A),
+            (1, 0, 0xC44B_DFF4_074E_ECDB),
+            (1, PRIME64, 0x032B_E332_DD76_6EF8),
+            (6, 0, 0x27B5_6A84_CD2D_7325),
+            (6, PRIME64, 0x8458_9C11_6AB5_9AB9),
+            (12, 0, 0xA713_DAF0_DFBB_77E7),
+            (12, PRIME64, 0xE730_3E1B_2336_DE0E),
+            (24, 0, 0xA3FE_70BF_9D35_10EB),
+            (24, PRIME64, 0x850E_80FC_35BD_D690),
+            (48, 0, 0x397D_A259_ECBA_1F11),
+            (48, PRIME64, 0xADC2_CBAA_44AC_C616),
+            (80, 0, 0xBCDE_FBBB_2C47_C90A),
+            (80, PRIME64, 0xC6DD_0CB6_9953_2E73),
+            (195, 0, 0xCD94_217E_E362_EC3A),
+            (195, PRIME64, 0xBA68_003D_370C_B3D9),
+            (403, 0, 0xCDEB_804D_65C6_DEA4),
+            (403, PRIME64, 0x6259_F6EC_FD64_43FD),
+            (512, 0, 0x617E_4959_9013_CB6B),
+            (2048, 0, 0xDD59_E2C3_A5F0_38E0),
+            (2240, 0, 0x6E73_A905_39CF_2948),
+            (2367, 0, 0xCB37_AEB9_E5D3_61ED),
+        ];
+        for (len, seed, expected) in cases {
+            assert_eq!(xxh3_64(&buffer[..len], seed), expected, "{} {}", len, seed);
+        }
+    }
+
+    #[test]
+    fn xxh3_128_vectors() {
+        let buffer = sanity_buffer(403);
+        let cases = [
+            (0, 0, 0x99AA_06D3_0147_98D8, 0x6001_C324_468D_497F),
+            (0, PRIME32_1, 0x9222_0AE5_5E14_AB50, 0x5444_F786_9C67_1AB0),
+            (1, 0, 0xA6CD_5E93_9200_0F6A, 0xC44B_DFF4_074E_ECDB),
+            (1, PRIME32_1, 0x89B9_9554_BA22_467C, 0xB53D_5557_E7F7_6F8D),
+            (6, 0, 0x082A_FE0B_8162_D12A, 0x3E70_39BD_DA43_CFC6),
+            (6, PRIME32_1, 0x5A86_5B53_89AB_D2B1, 0x269D_8F70_BE98_856E),
+            (12, 0, 0x6E3E_FD8F_C780_2B18, 0x061A_1927_13F6_9AD9),
+            (12, PRIME32_1, 0xD7E0_9D51_8A34_05D3, 0x9BE9_F9A6_7F3C_7DFB),
+            (24, 0, 0x0CE9_66E4_678D_3761, 0x1E70_44D2_8B1B_901D),
+            (24, PRIME32_1, 0x3162_0267_14A6_A243, 0xD730_4C54_EBAD_40A9),
+            (48, 0, 0xA002_AC4E_5478_227E, 0xF942_219A_ED80_F67B),
+            (48, PRIME32_1, 0x163A_DDE3_6C07_2295, 0x7BA3_C3E4_53A1_934E),
+            (81, 0, 0x4952_F581_81AB_0042, 0x5E8B_AFB9_F95F_B803),
+            (81, PRIME32_1, 0x2724_EC7A_DC75_0FB6, 0x703F_BB3D_7A5F_755C),
+            (222, 0, 0x337E_0964_1B94_8717, 0xF1AE_BD59_7CEC_6B3A),
+            (222, PRIME32_1, 0x9182_0016_621E_97F1, 0xAE99_5BB8_AF91_7A8D),
+            (403, 0, 0x1B6D_E21E_332D_D73D, 0xCDEB_804D_65C6_DEA4),
+        ];
+        for (len, seed, high, low) in cases {
+            assert_eq!(
+                xxh3_128(&buffer[..len], seed),
+                join(low, high),
+                "{} {}",
+                len,
+                seed
+            );
+        }
+    }
+
+    #[test]
+    fn streaming_matches_one_shot() {
+        let buffer = sanity_buffer(3000);
+        for len in [
+            0, 3, 31, 32, 33, 240, 241, 255, 256, 257, 300, 1024, 1025, 3000,
+        ] {
+            let bytes = &buffer[..len];
+            for split in [1, 7, 64, 256] {
+                let mut xxh64_hasher = Xxh64Hasher::seed(len as u64);
+                let mut xxh3_hasher = Xxh3Hasher::seed(len as u64);
+                for chunk in bytes.chunks(split) {
+                    xxh64_hasher.write(chunk);
+                    xxh3_hasher.write(chunk);
+                }
+                assert_eq!(xxh64_hasher.finish(), xxh64(bytes, len as u64));
+                assert_eq!(xxh3_hasher.finish(), xxh3_64(bytes, len as u64));
+                assert_eq!(xxh3_hasher.finish_128(), xxh3_128(bytes, len as u64));
+            }
+        }
+    }
+}
@@ -1,4 +1,11 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+#![cfg_attr(test, allow(clippy::bool_assert_comparison))]
+
+extern crate alloc;
+
 pub mod collections;
+#[cfg(feature = "std")]
+pub mod concurrent;
 pub mod crypto;
 
 pub mod money;